@@ -1,29 +1,59 @@
 use cosmwasm_std::{
-    from_binary, to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo,
-    Response, StdError, StdResult, Storage, Uint128,
+    coins, from_binary, to_binary, to_vec, Addr, Binary, Decimal, Deps, DepsMut, Empty, Env, Event,
+    MessageInfo, Order, Reply, Response, StdError, StdResult, Storage, SubMsg, SubMsgResult,
+    Uint128, WasmMsg,
 };
-use cw20::Cw20ReceiveMsg;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use cw_denom::{CheckedDenom, DenomError, UncheckedDenom};
 use cw_storage_plus::Bound;
-use cw_utils::nonpayable;
+use cw_utils::{nonpayable, parse_execute_response_data, Duration, Expiration};
+use sha2::{Digest, Sha256};
 
 use crate::error::ContractError;
+use crate::ledger::{add_total, backfill_snapshot_total, denom_to_string, subtract_total};
 use crate::msg::{
-    Cw20ReceiverMsg, ExecuteMsg, InstantiateMsg, ListIdsForPayerResponse, ListPaymentsResponse,
-    ListPaymentsToIdResponse, ListTotalsPaidByPayerResponse, ListTotalsPaidToIdResponse,
-    OutputResponse, QueryMsg, ReceiptPayment, ReceiptPaymentWithoutId, Total,
+    AdminLogItem, CampaignContributionsResponse, CampaignResponse, CanonicalIdResponse,
+    CreateReceiptResponse, Cw20ReceiverMsg, ExecuteMsg, ForwardingResponse, HasPaidResponse,
+    IdRulesResponse, InstantiateMsg, ListAdminActionsResponse, ListIdsForPayerResponse,
+    ListOwedToOutputResponse, ListPaymentsResponse, ListPaymentsToIdResponse,
+    ListPeriodPayerTotalsResponse, ListPeriodsResponse, ListProductsResponse,
+    ListReceiptIdsResponse, ListReceiptTransfersResponse, ListSignerKeysResponse,
+    ListTotalsPaidByPayerResponse, ListTotalsPaidToIdResponse, ListUnforwardedResponse, MigrateMsg,
+    OutputResponse, PayResponse, PaymentRequest, PeriodItem, PeriodStatementResponse, ProductItem,
+    ProductResponse, ProductStockResponse, QueryMsg, ReceiptEscrow, ReceiptIdItem,
+    ReceiptPayerResponse, ReceiptResponse, ReceiptTransfer, RecordedPayment, RouterExecuteMsg,
+    SignedPaymentRequest, SwapResponse, SweepableResponse, Total, UncheckedPrice,
 };
 use crate::state::{
-    Payment, OUTPUT, PAYER_RECEIPTS, PAYER_TOTALS, RECEIPT_PAYMENTS, RECEIPT_PAYMENT_COUNT,
-    RECEIPT_TOTALS,
+    idempotency_key_hash, AdminAction, AdminLogEntry, Campaign, Closure, Creation, Dispute, Escrow,
+    ForwardingConfig, IdRules, InFlightSettlement, LegacyMigration, LegacyPayment, LineItem,
+    PaymentRecord, Product, SettlementConfig, Transfer, Void, ADMIN_LOG, ADMIN_LOG_COUNT,
+    BATCHES_IN_FLIGHT, CAMPAIGNS, CAMPAIGN_CONTRIBUTIONS, CAMPAIGN_PAYMENTS, CAMPAIGN_RAISED,
+    ESCROWS, ESCROW_HELD, FORWARDING, FORWARDS_IN_FLIGHT, GENERATED_ID_COUNT, GENERATED_ID_PREFIX,
+    HELD_FOR_CAMPAIGNS, HELD_IN_ESCROW, IDEMPOTENCY_KEYS, IDEMPOTENCY_KEY_EXPIRIES,
+    IDEMPOTENCY_KEY_MAX_LENGTH, IDEMPOTENCY_KEY_PRUNE_LIMIT, IDEMPOTENCY_KEY_TTL_SECONDS, ID_RULES,
+    LEDGER, LEGACY_MIGRATION, LEGACY_MIGRATION_LIMIT, LEGACY_PAYER_TOTALS, LEGACY_RECEIPT_PAYMENTS,
+    LEGACY_RECEIPT_TOTALS, OUTPUT, OWED_TO_OUTPUT, PRODUCTS, PRODUCT_STOCK, RECEIPT_CLOSURES,
+    RECEIPT_CREATIONS, RECEIPT_TRANSFERS, SETTLEMENTS_IN_FLIGHT, SIGNER_KEYS, UNFORWARDED,
+    USED_NONCES,
 };
 use cosmwasm_std::entry_point;
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
 
 // Version info for migration
 const CONTRACT_NAME: &str = "crates.io:cw-receipt";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// Reply IDs carry their kind in the upper 32 bits and an interned denom ID in
+// the lower 32 bits.
+const REPLY_KIND_MASK: u64 = 0xffff_ffff_0000_0000;
+const FORWARD_REPLY_ID: u64 = 1 << 32;
+const SETTLE_REPLY_ID: u64 = 2 << 32;
+const BATCH_REPLY_ID: u64 = 3 << 32;
+
+// Basis points in a whole, for splitting disputed escrows.
+const BPS_DENOMINATOR: u16 = 10_000;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -37,6 +67,13 @@ pub fn instantiate(
     let output = deps.api.addr_validate(&msg.output)?;
     OUTPUT.save(deps.storage, &output)?;
 
+    if let Some(id_rules) = msg.id_rules {
+        save_id_rules(deps.storage, id_rules)?;
+    }
+    if let Some(forwarding) = msg.forwarding {
+        save_forwarding(deps, &forwarding)?;
+    }
+
     Ok(Response::default()
         .add_attribute("method", "instantiate")
         .add_attribute("output", output.to_string())
@@ -50,16 +87,89 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    if LEGACY_MIGRATION.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::MigrationInProgress);
+    }
+
     match msg {
         ExecuteMsg::Receive(msg) => execute_receive_cw20(deps, env, info, msg),
-        ExecuteMsg::Pay { id } => execute_pay(deps, env, info, id),
-        ExecuteMsg::UpdateOutput { output } => execute_update_output(deps, info, output),
+        ExecuteMsg::Pay {
+            id,
+            idempotency_key,
+        } => execute_pay(deps, env, info, id, idempotency_key),
+        ExecuteMsg::CreateReceipt { metadata } => execute_create_receipt(deps, env, info, metadata),
+        ExecuteMsg::PayNew {} => execute_pay_new(deps, env, info),
+        ExecuteMsg::UpdateOutput { output } => execute_update_output(deps, env, info, output),
+        ExecuteMsg::UpdateIdRules { id_rules } => {
+            execute_update_id_rules(deps, env, info, id_rules)
+        }
+        ExecuteMsg::AssignPayer { id, payer } => execute_assign_payer(deps, env, info, id, payer),
+        ExecuteMsg::CloseReceipt { id, reason } => {
+            execute_close_receipt(deps, env, info, id, reason)
+        }
+        ExecuteMsg::ReopenReceipt { id } => execute_reopen_receipt(deps, env, info, id),
+        ExecuteMsg::TransferReceipt { id, new_payer } => {
+            execute_transfer_receipt(deps, env, info, id, new_payer)
+        }
+        ExecuteMsg::VoidPayment {
+            id,
+            payment_index,
+            reason,
+            restock,
+        } => execute_void_payment(deps, env, info, id, payment_index, reason, restock),
+        ExecuteMsg::UpdateForwarding { forwarding } => {
+            execute_update_forwarding(deps, env, info, forwarding)
+        }
+        ExecuteMsg::FlushUnforwarded {} => execute_flush_unforwarded(deps, env, info),
+        ExecuteMsg::Settle { denoms } => execute_settle(deps, env, info, denoms),
+        ExecuteMsg::ClosePeriod {} => execute_close_period(deps, env, info),
+        ExecuteMsg::Sweep {
+            denom,
+            amount,
+            recipient,
+        } => execute_sweep(deps, env, info, denom, amount, recipient),
+        ExecuteMsg::PayWithSignedRequest {
+            request,
+            signature,
+            public_key,
+        } => execute_pay_with_signed_request(deps, env, info, request, signature, public_key),
+        ExecuteMsg::AddSignerKey { public_key } => {
+            execute_add_signer_key(deps, env, info, public_key)
+        }
+        ExecuteMsg::RemoveSignerKey { public_key } => {
+            execute_remove_signer_key(deps, env, info, public_key)
+        }
+        ExecuteMsg::SetProduct {
+            sku,
+            name,
+            prices,
+            active,
+        } => execute_set_product(deps, env, info, sku, name, prices, active),
+        ExecuteMsg::SetProductStock { sku, stock } => {
+            execute_set_product_stock(deps, env, info, sku, stock)
+        }
+        ExecuteMsg::CreateCampaign { id, goal, deadline } => {
+            execute_create_campaign(deps, env, info, id, goal, deadline)
+        }
+        ExecuteMsg::Finalize { id } => execute_finalize(deps, env, info, id),
+        ExecuteMsg::ClaimRefund { id } => execute_claim_refund(deps, env, info, id),
+        ExecuteMsg::AssignArbiter {
+            id,
+            arbiter,
+            dispute_window,
+        } => execute_assign_arbiter(deps, env, info, id, arbiter, dispute_window),
+        ExecuteMsg::OpenDispute { id, reason } => execute_open_dispute(deps, env, info, id, reason),
+        ExecuteMsg::ResolveDispute { id, release_bps } => {
+            execute_resolve_dispute(deps, env, info, id, release_bps)
+        }
+        ExecuteMsg::ReleaseEscrow { id } => execute_release_escrow(deps, env, info, id),
+        ExecuteMsg::PayForItems { id, items } => execute_pay_for_items(deps, env, info, id, items),
         ExecuteMsg::UpdateOwnership(action) => execute_update_owner(deps, env, info, action),
     }
 }
 
 pub fn execute_receive_cw20(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     receive_msg: Cw20ReceiveMsg,
@@ -76,24 +186,67 @@ pub fn execute_receive_cw20(
     let unchecked_denom = UncheckedDenom::Cw20(info.sender.to_string());
     let checked = unchecked_denom.into_checked(deps.as_ref())?;
 
-    match msg {
-        Cw20ReceiverMsg::Pay { id } => {
-            let transfer_msg = record_payment_and_get_transfer_msg(
-                deps.storage,
+    let mut line_items = vec![];
+    let id = match msg {
+        Cw20ReceiverMsg::Pay {
+            id,
+            idempotency_key,
+        } => {
+            let id = canonicalize_receipt_id(deps.storage, &id, &payer)?;
+            if let Some(key) = idempotency_key {
+                claim_idempotency_key(deps.storage, &env, &payer, &id, &key)?;
+            }
+            id
+        }
+        Cw20ReceiverMsg::PayNew {} => allocate_receipt_id(deps.storage, &env, &payer, None)?,
+        Cw20ReceiverMsg::PayForItems { id, items } => {
+            line_items = price_items(deps.storage, &items, &checked, receive_msg.amount)?;
+            take_stock(deps.storage, &line_items)?;
+            canonicalize_receipt_id(deps.storage, &id, &payer)?
+        }
+        Cw20ReceiverMsg::PayWithSignedRequest {
+            request,
+            signature,
+            public_key,
+        } => {
+            let id = request.id.clone();
+            verify_payment_request(
+                deps.branch(),
                 &env,
-                &id,
-                &checked,
-                payer,
-                receive_msg.amount,
+                request,
+                &signature,
+                &public_key,
+                &payer,
+                &[(checked.clone(), receive_msg.amount)],
             )?;
-
-            Ok(Response::new()
-                .add_message(transfer_msg)
-                .add_attribute("method", "receive_cw20")
-                .add_attribute("id", id)
-                .add_attribute("payer", receive_msg.sender))
+            canonicalize_receipt_id(deps.storage, &id, &payer)?
         }
+    };
+
+    let (receipt_payment_id, transfer_msgs) = record_payment_and_get_transfer_msgs(
+        deps.storage,
+        &env,
+        &id,
+        &checked,
+        payer,
+        receive_msg.amount,
+    )?;
+    if !line_items.is_empty() {
+        LEDGER.set_line_items(deps.storage, &id, receipt_payment_id, line_items)?;
     }
+
+    let data = pay_response(
+        deps.storage,
+        &id,
+        vec![(receipt_payment_id, checked, receive_msg.amount)],
+    )?;
+
+    Ok(Response::new()
+        .add_submessages(transfer_msgs)
+        .set_data(to_binary(&data)?)
+        .add_attribute("method", "receive_cw20")
+        .add_attribute("id", id)
+        .add_attribute("payer", receive_msg.sender))
 }
 
 pub fn execute_pay(
@@ -101,12 +254,18 @@ pub fn execute_pay(
     env: Env,
     info: MessageInfo,
     id: String,
+    idempotency_key: Option<String>,
 ) -> Result<Response, ContractError> {
     // Require native tokens.
     if info.funds.is_empty() {
         return Err(ContractError::MissingPayment);
     }
 
+    let id = canonicalize_receipt_id(deps.storage, &id, &info.sender)?;
+    if let Some(key) = &idempotency_key {
+        claim_idempotency_key(deps.storage, &env, &info.sender, &id, key)?;
+    }
+
     // Require native tokens.
     let checked_funds = info
         .funds
@@ -119,275 +278,2603 @@ pub fn execute_pay(
         .collect::<Result<Vec<(CheckedDenom, Uint128)>, DenomError>>()?;
 
     // Record payments and get transfer messages.
-    let transfer_msgs = checked_funds
-        .into_iter()
-        .map(|(checked_denom, amount)| {
-            record_payment_and_get_transfer_msg(
-                deps.storage,
-                &env,
-                &id,
-                &checked_denom,
-                info.sender.clone(),
-                amount,
-            )
-        })
-        .collect::<Result<Vec<CosmosMsg>, ContractError>>()?;
+    let mut recorded = vec![];
+    let mut transfer_msgs = vec![];
+    for (checked_denom, amount) in checked_funds {
+        let (receipt_payment_id, payment_transfer_msgs) = record_payment_and_get_transfer_msgs(
+            deps.storage,
+            &env,
+            &id,
+            &checked_denom,
+            info.sender.clone(),
+            amount,
+        )?;
+        recorded.push((receipt_payment_id, checked_denom, amount));
+        transfer_msgs.extend(payment_transfer_msgs);
+    }
+
+    let data = pay_response(deps.storage, &id, recorded)?;
 
     Ok(Response::new()
-        .add_messages(transfer_msgs)
+        .add_submessages(transfer_msgs)
+        .set_data(to_binary(&data)?)
         .add_attribute("method", "pay")
         .add_attribute("id", id)
         .add_attribute("payer", info.sender))
 }
 
-pub fn execute_update_output(
+pub fn execute_create_receipt(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    output: String,
+    metadata: Option<String>,
 ) -> Result<Response, ContractError> {
-    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    nonpayable(&info)?;
 
-    let output_addr = deps.api.addr_validate(&output)?;
-    OUTPUT.save(deps.storage, &output_addr)?;
+    let id = allocate_receipt_id(deps.storage, &env, &info.sender, metadata)?;
+    // Generated IDs are not bound to their payer, so reserve the receipt for
+    // its creator before anyone else can pay it.
+    LEDGER.set_payer(deps.storage, &id, Some(&info.sender))?;
 
-    Ok(Response::default()
-        .add_attribute("action", "update_output")
-        .add_attribute("output", output))
+    Ok(Response::new()
+        .set_data(to_binary(&CreateReceiptResponse { id: id.clone() })?)
+        .add_attribute("action", "create_receipt")
+        .add_attribute("id", id)
+        .add_attribute("creator", info.sender))
 }
 
-pub fn execute_update_owner(
+pub fn execute_pay_new(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    action: cw_ownable::Action,
 ) -> Result<Response, ContractError> {
-    let ownership = cw_ownable::update_ownership(deps, &env.block, &info.sender, action)?;
-    Ok(Response::default().add_attributes(ownership.into_attributes()))
-}
+    // Require native tokens.
+    if info.funds.is_empty() {
+        return Err(ContractError::MissingPayment);
+    }
 
-fn record_payment_and_get_transfer_msg(
-    storage: &mut dyn Storage,
-    env: &Env,
-    id: &String,
-    denom: &CheckedDenom,
-    payer: Addr,
-    amount: Uint128,
-) -> Result<CosmosMsg, ContractError> {
-    let output = OUTPUT.load(storage)?;
+    let id = allocate_receipt_id(deps.storage, &env, &info.sender, None)?;
 
-    // Get past payment count for receipt.
-    let receipt_payment_count = RECEIPT_PAYMENT_COUNT
-        .may_load(storage, id.to_string())?
-        .unwrap_or(0);
+    execute_pay(deps, env, info, id, None)
+}
 
-    // If at least one payment, verify payer is authorized for this receipt.
-    // Only one payer can pay for a receipt, determined by the first payment.
-    if receipt_payment_count > 0 {
-        let payer_authorized_for_receipt =
-            PAYER_RECEIPTS.has(storage, (payer.clone(), id.to_string()));
-        if !payer_authorized_for_receipt {
-            return Err(ContractError::UnauthorizedPayer);
-        }
+pub fn execute_pay_with_signed_request(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    request: PaymentRequest,
+    signature: Binary,
+    public_key: Binary,
+) -> Result<Response, ContractError> {
+    // Require native tokens.
+    if info.funds.is_empty() {
+        return Err(ContractError::MissingPayment);
     }
-    // If no payments, set payer.
-    else {
-        PAYER_RECEIPTS.save(storage, (payer.clone(), id.to_string()), &Empty {})?;
+
+    let paid = info
+        .funds
+        .iter()
+        .map(|fund| (CheckedDenom::Native(fund.denom.clone()), fund.amount))
+        .collect::<Vec<_>>();
+
+    let id = request.id.clone();
+    let nonce = request.nonce;
+    verify_payment_request(
+        deps.branch(),
+        &env,
+        request,
+        &signature,
+        &public_key,
+        &info.sender,
+        &paid,
+    )?;
+
+    Ok(execute_pay(deps, env, info, id, None)?
+        .add_attribute("signer_key", public_key.to_base64())
+        .add_attribute("nonce", nonce.to_string()))
+}
+
+pub fn execute_add_signer_key(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    public_key: Binary,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    // Compressed or uncompressed SEC1 encoding.
+    if public_key.len() != 33 && public_key.len() != 65 {
+        return Err(ContractError::InvalidSignerKey);
     }
 
-    // Record payment.
-    RECEIPT_PAYMENTS.save(
-        storage,
-        (id.to_string(), receipt_payment_count),
-        &Payment {
-            payer: payer.clone(),
-            block: env.block.clone(),
-            denom: denom.clone(),
-            amount,
+    SIGNER_KEYS.save(deps.storage, public_key.as_slice(), &Empty {})?;
+
+    log_admin_action(
+        deps.storage,
+        &env,
+        &info.sender,
+        AdminAction::AddSignerKey {
+            public_key: public_key.clone(),
         },
     )?;
-    // Increment payment count.
-    RECEIPT_PAYMENT_COUNT.update(storage, id.to_string(), |count| {
-        Ok::<u64, StdError>(count.unwrap_or(0) + 1)
-    })?;
-    // Increase totals.
-    RECEIPT_TOTALS.update(storage, (id.to_string(), denom_to_string(denom)), |total| {
-        Ok::<Uint128, StdError>(total.unwrap_or(Uint128::zero()) + amount)
-    })?;
-    PAYER_TOTALS.update(storage, (payer, denom_to_string(denom)), |total| {
-        Ok::<Uint128, StdError>(total.unwrap_or(Uint128::zero()) + amount)
-    })?;
 
-    Ok(denom.get_transfer_to_message(&output, amount)?)
+    Ok(Response::default()
+        .add_attribute("action", "add_signer_key")
+        .add_attribute("public_key", public_key.to_base64()))
 }
 
-fn denom_to_string(denom: &CheckedDenom) -> String {
-    match denom {
-        CheckedDenom::Native(denom) => format!("n{}", denom),
-        CheckedDenom::Cw20(denom) => format!("c{}", denom),
+pub fn execute_remove_signer_key(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    public_key: Binary,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    if !SIGNER_KEYS.has(deps.storage, public_key.as_slice()) {
+        return Err(ContractError::UnknownSignerKey);
     }
+    SIGNER_KEYS.remove(deps.storage, public_key.as_slice());
+
+    log_admin_action(
+        deps.storage,
+        &env,
+        &info.sender,
+        AdminAction::RemoveSignerKey {
+            public_key: public_key.clone(),
+        },
+    )?;
+
+    Ok(Response::default()
+        .add_attribute("action", "remove_signer_key")
+        .add_attribute("public_key", public_key.to_base64()))
 }
 
-fn string_to_denom(s: String) -> Option<CheckedDenom> {
-    let (prefix, denom) = s.split_at(1);
-    match prefix {
-        "n" => Some(CheckedDenom::Native(denom.to_string())),
-        "c" => Some(CheckedDenom::Cw20(Addr::unchecked(denom))),
-        _ => None,
+pub fn execute_set_product(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    sku: String,
+    name: String,
+    prices: Vec<UncheckedPrice>,
+    active: bool,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let invalid = |reason: &str| ContractError::InvalidProduct {
+        reason: reason.to_string(),
+    };
+    if sku.is_empty() {
+        return Err(invalid("SKU is empty"));
+    }
+    if name.is_empty() {
+        return Err(invalid("name is empty"));
+    }
+    if prices.is_empty() {
+        return Err(invalid("no prices"));
     }
-}
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::ListPayments { start_after, limit } => {
-            query_list_payments(deps, start_after, limit)
+    let mut checked_prices: Vec<Total> = vec![];
+    for price in prices {
+        let denom = price.denom.into_checked(deps.as_ref())?;
+        if price.amount.is_zero() {
+            return Err(invalid("price is zero"));
         }
+        if checked_prices.iter().any(|other| other.denom == denom) {
+            return Err(invalid("duplicate price denom"));
+        }
+        checked_prices.push(Total {
+            denom,
+            amount: price.amount,
+        });
+    }
 
-        QueryMsg::ListPaymentsToId {
-            id,
-            start_after,
-            limit,
-        } => query_list_payments_to_id(deps, id, start_after, limit),
+    let product = Product {
+        name,
+        prices: checked_prices,
+        active,
+    };
+    let old = PRODUCTS.may_load(deps.storage, &sku)?;
+    PRODUCTS.save(deps.storage, &sku, &product)?;
 
-        QueryMsg::ListTotalsPaidToId {
-            id,
-            start_after,
-            limit,
-        } => query_list_totals_paid_to_id(deps, id, start_after, limit),
+    log_admin_action(
+        deps.storage,
+        &env,
+        &info.sender,
+        AdminAction::SetProduct {
+            sku: sku.clone(),
+            old,
+            new: product,
+        },
+    )?;
 
-        QueryMsg::ListIdsForPayer {
-            payer,
-            start_after,
-            limit,
-        } => query_list_ids_for_payer(deps, payer, start_after, limit),
+    Ok(Response::default()
+        .add_attribute("action", "set_product")
+        .add_attribute("sku", sku)
+        .add_attribute("active", active.to_string()))
+}
 
-        QueryMsg::ListTotalsPaidByPayer {
-            payer,
-            start_after,
-            limit,
-        } => query_list_totals_paid_by_payer(deps, payer, start_after, limit),
+pub fn execute_set_product_stock(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    sku: String,
+    stock: Option<u32>,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
 
-        QueryMsg::Output {} => to_binary(&OutputResponse {
-            output: OUTPUT.load(deps.storage)?,
-        }),
+    if !PRODUCTS.has(deps.storage, &sku) {
+        return Err(ContractError::ProductNotFound { sku });
+    }
 
-        QueryMsg::Ownership {} => to_binary(&cw_ownable::get_ownership(deps.storage)?),
+    let old = PRODUCT_STOCK.may_load(deps.storage, &sku)?;
+    match stock {
+        Some(stock) => PRODUCT_STOCK.save(deps.storage, &sku, &stock)?,
+        None => PRODUCT_STOCK.remove(deps.storage, &sku),
     }
-}
 
-pub fn query_list_payments(
-    deps: Deps,
-    start_after: Option<(String, u64)>,
-    limit: Option<u32>,
-) -> StdResult<Binary> {
-    let payments = cw_paginate::paginate_map(
-        RECEIPT_PAYMENTS,
+    log_admin_action(
         deps.storage,
-        start_after.map(Bound::exclusive),
-        limit,
-        |(receipt_id, receipt_payment_id), payment| {
-            Ok::<ReceiptPayment, StdError>(ReceiptPayment {
-                receipt_id,
-                receipt_payment_id,
-                payment,
-            })
+        &env,
+        &info.sender,
+        AdminAction::SetProductStock {
+            sku: sku.clone(),
+            old,
+            new: stock,
         },
     )?;
 
-    to_binary(&ListPaymentsResponse { payments })
+    Ok(Response::default()
+        .add_attribute("action", "set_product_stock")
+        .add_attribute("sku", sku)
+        .add_attribute(
+            "stock",
+            stock.map_or("unlimited".to_string(), |stock| stock.to_string()),
+        ))
 }
 
-pub fn query_list_payments_to_id(
-    deps: Deps,
+pub fn execute_pay_for_items(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
     id: String,
-    start_after: Option<u64>,
-    limit: Option<u32>,
-) -> StdResult<Binary> {
-    let payments = cw_paginate::paginate_map_prefix(
-        RECEIPT_PAYMENTS,
-        deps.storage,
-        id,
-        start_after.map(Bound::exclusive),
-        limit,
-        |receipt_payment_id, payment| {
-            Ok::<ReceiptPaymentWithoutId, StdError>(ReceiptPaymentWithoutId {
-                receipt_payment_id,
-                payment,
+    items: Vec<(String, u32)>,
+) -> Result<Response, ContractError> {
+    // Require a single native token.
+    let fund = match info.funds.as_slice() {
+        [] => return Err(ContractError::MissingPayment),
+        [fund] => fund,
+        _ => {
+            return Err(ContractError::InvalidItems {
+                reason: "items must be paid in a single denom".to_string(),
             })
-        },
+        }
+    };
+    let checked_denom = UncheckedDenom::Native(fund.denom.clone()).into_checked(deps.as_ref())?;
+
+    let line_items = price_items(deps.storage, &items, &checked_denom, fund.amount)?;
+    take_stock(deps.storage, &line_items)?;
+    let id = canonicalize_receipt_id(deps.storage, &id, &info.sender)?;
+
+    let (receipt_payment_id, transfer_msgs) = record_payment_and_get_transfer_msgs(
+        deps.storage,
+        &env,
+        &id,
+        &checked_denom,
+        info.sender.clone(),
+        fund.amount,
     )?;
+    LEDGER.set_line_items(deps.storage, &id, receipt_payment_id, line_items)?;
 
-    to_binary(&ListPaymentsToIdResponse { payments })
+    let data = pay_response(
+        deps.storage,
+        &id,
+        vec![(receipt_payment_id, checked_denom, fund.amount)],
+    )?;
+
+    Ok(Response::new()
+        .add_submessages(transfer_msgs)
+        .set_data(to_binary(&data)?)
+        .add_attribute("method", "pay_for_items")
+        .add_attribute("id", id)
+        .add_attribute("payer", info.sender))
 }
 
-pub fn query_list_totals_paid_to_id(
-    deps: Deps,
-    id: String,
-    start_after: Option<CheckedDenom>,
-    limit: Option<u32>,
-) -> StdResult<Binary> {
-    let totals = cw_paginate::paginate_map_prefix(
-        RECEIPT_TOTALS,
+pub fn execute_update_forwarding(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    forwarding: ForwardingConfig,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let old_forwarding = FORWARDING.may_load(deps.storage)?.unwrap_or_default();
+    save_forwarding(deps.branch(), &forwarding)?;
+
+    log_admin_action(
         deps.storage,
-        id,
-        start_after.map(|denom| Bound::exclusive(denom_to_string(&denom))),
-        limit,
-        |string_denom, amount| {
-            Ok::<Option<Total>, StdError>(
-                string_to_denom(string_denom).map(|denom| Total { denom, amount }),
-            )
+        &env,
+        &info.sender,
+        AdminAction::UpdateForwarding {
+            old: old_forwarding,
+            new: forwarding.clone(),
         },
-    )?
-    .into_iter()
-    .flatten()
-    .collect();
+    )?;
 
-    to_binary(&ListTotalsPaidToIdResponse { totals })
+    Ok(Response::default()
+        .add_attribute("action", "update_forwarding")
+        .add_attribute("resilient", forwarding.resilient.to_string()))
 }
 
-pub fn query_list_ids_for_payer(
-    deps: Deps,
-    payer: String,
-    start_after: Option<String>,
-    limit: Option<u32>,
-) -> StdResult<Binary> {
-    let payer = deps.api.addr_validate(&payer)?;
+pub fn execute_flush_unforwarded(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
 
-    let ids = cw_paginate::paginate_map_prefix(
-        PAYER_RECEIPTS,
+    let output = OUTPUT.load(deps.storage)?;
+    let unforwarded = UNFORWARDED
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    // Transfers are not resilient here, so a failing output reverts the flush
+    // and leaves the balances for a later retry.
+    let mut amounts = vec![];
+    let mut transfer_msgs = vec![];
+    for (denom_id, amount) in unforwarded {
+        UNFORWARDED.remove(deps.storage, denom_id);
+        let total = LEDGER.total(deps.storage, denom_id, amount)?;
+        transfer_msgs.push(total.denom.get_transfer_to_message(&output, amount)?);
+        amounts.push(total);
+    }
+
+    log_admin_action(
         deps.storage,
-        payer,
-        start_after.map(Bound::exclusive),
-        limit,
-        |id, _| Ok::<String, StdError>(id),
+        &env,
+        &info.sender,
+        AdminAction::FlushUnforwarded { amounts },
     )?;
 
-    to_binary(&ListIdsForPayerResponse { ids })
+    Ok(Response::default()
+        .add_messages(transfer_msgs)
+        .add_attribute("action", "flush_unforwarded"))
 }
 
-pub fn query_list_totals_paid_by_payer(
-    deps: Deps,
-    payer: String,
-    start_after: Option<CheckedDenom>,
-    limit: Option<u32>,
-) -> StdResult<Binary> {
-    let payer = deps.api.addr_validate(&payer)?;
-
-    let totals = cw_paginate::paginate_map_prefix(
-        PAYER_TOTALS,
-        deps.storage,
-        payer,
-        start_after.map(|denom| Bound::exclusive(denom_to_string(&denom))),
-        limit,
-        |string_denom, amount| {
-            Ok::<Option<Total>, StdError>(
-                string_to_denom(string_denom).map(|denom| Total { denom, amount }),
-            )
+pub fn execute_settle(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    denoms: Vec<UncheckedDenom>,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+
+    let output = OUTPUT.load(deps.storage)?;
+    let denom_ids = if denoms.is_empty() {
+        OWED_TO_OUTPUT
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?
+    } else {
+        let mut denom_ids = vec![];
+        for denom in denoms {
+            let denom = denom.into_checked(deps.as_ref())?;
+            if let Some(denom_id) = LEDGER.denom_id(deps.storage, &denom)? {
+                denom_ids.push(denom_id);
+            }
+        }
+        denom_ids
+    };
+
+    // Transfers are not resilient here, so a failing output reverts the
+    // settlement and leaves the amounts owed for a later retry.
+    let mut transfer_msgs = vec![];
+    for denom_id in denom_ids {
+        if let Some(amount) = OWED_TO_OUTPUT.may_load(deps.storage, denom_id)? {
+            OWED_TO_OUTPUT.remove(deps.storage, denom_id);
+            let denom = LEDGER.denoms.load(deps.storage, denom_id)?;
+            transfer_msgs.push(denom.get_transfer_to_message(&output, amount)?);
+        }
+    }
+
+    Ok(Response::default()
+        .add_attribute("action", "settle")
+        .add_attribute("transfers", transfer_msgs.len().to_string())
+        .add_messages(transfer_msgs))
+}
+
+pub fn execute_create_campaign(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    goal: Vec<UncheckedPrice>,
+    deadline: Expiration,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let invalid = |reason: &str| ContractError::InvalidCampaign {
+        reason: reason.to_string(),
+    };
+    let id = normalize_receipt_id(deps.storage, &id)?;
+    if CAMPAIGNS.has(deps.storage, &id)
+        || ESCROWS.has(deps.storage, &id)
+        || receipt_id_in_use(deps.storage, &id)?
+    {
+        return Err(invalid("receipt ID is in use"));
+    }
+    if goal.is_empty() {
+        return Err(invalid("no goal"));
+    }
+    if deadline.is_expired(&env.block) {
+        return Err(invalid("deadline has passed"));
+    }
+
+    let mut checked_goal: Vec<Total> = vec![];
+    for amount in goal {
+        let denom = amount.denom.into_checked(deps.as_ref())?;
+        if amount.amount.is_zero() {
+            return Err(invalid("goal is zero"));
+        }
+        if checked_goal.iter().any(|other| other.denom == denom) {
+            return Err(invalid("duplicate goal denom"));
+        }
+        checked_goal.push(Total {
+            denom,
+            amount: amount.amount,
+        });
+    }
+
+    let campaign = Campaign {
+        goal: checked_goal,
+        deadline,
+        finalized: false,
+    };
+    CAMPAIGNS.save(deps.storage, &id, &campaign)?;
+
+    log_admin_action(
+        deps.storage,
+        &env,
+        &info.sender,
+        AdminAction::CreateCampaign {
+            id: id.clone(),
+            campaign,
+        },
+    )?;
+
+    Ok(Response::default()
+        .add_attribute("action", "create_campaign")
+        .add_attribute("id", id)
+        .add_attribute("deadline", deadline.to_string()))
+}
+
+pub fn execute_finalize(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: String,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+
+    let id = normalize_receipt_id(deps.storage, &id)?;
+    let mut campaign = load_campaign(deps.storage, &id)?;
+    if campaign.finalized {
+        return Err(ContractError::CampaignEnded { id });
+    }
+    if !campaign_goal_met(deps.storage, &id, &campaign)? {
+        return Err(ContractError::CampaignGoalNotMet { id });
+    }
+
+    campaign.finalized = true;
+    CAMPAIGNS.save(deps.storage, &id, &campaign)?;
+
+    let output = OUTPUT.load(deps.storage)?;
+    let raised = CAMPAIGN_RAISED
+        .prefix(&id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut transfer_msgs = vec![];
+    for (denom_id, amount) in raised {
+        CAMPAIGN_RAISED.remove(deps.storage, (&id, denom_id));
+        subtract_total(deps.storage, &HELD_FOR_CAMPAIGNS, denom_id, amount)?;
+        let denom = LEDGER.denoms.load(deps.storage, denom_id)?;
+        transfer_msgs.push(denom.get_transfer_to_message(&output, amount)?);
+    }
+
+    Ok(Response::default()
+        .add_messages(transfer_msgs)
+        .add_attribute("action", "finalize")
+        .add_attribute("id", id))
+}
+
+pub fn execute_claim_refund(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+
+    let id = normalize_receipt_id(deps.storage, &id)?;
+    let campaign = load_campaign(deps.storage, &id)?;
+    if campaign.finalized {
+        return Err(ContractError::CampaignEnded { id });
+    }
+    if !campaign.deadline.is_expired(&env.block) {
+        return Err(ContractError::CampaignRunning { id });
+    }
+    if campaign_goal_met(deps.storage, &id, &campaign)? {
+        return Err(ContractError::CampaignGoalMet { id });
+    }
+
+    let contributions = CAMPAIGN_CONTRIBUTIONS
+        .prefix((&id, &info.sender))
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    if contributions.is_empty() {
+        return Err(ContractError::NothingToRefund);
+    }
+
+    let mut transfer_msgs = vec![];
+    for (denom_id, amount) in contributions {
+        CAMPAIGN_CONTRIBUTIONS.remove(deps.storage, (&id, &info.sender, denom_id));
+        subtract_total(
+            deps.storage,
+            &CAMPAIGN_RAISED,
+            (id.as_str(), denom_id),
+            amount,
+        )?;
+        subtract_total(deps.storage, &HELD_FOR_CAMPAIGNS, denom_id, amount)?;
+        let denom = LEDGER.denoms.load(deps.storage, denom_id)?;
+        transfer_msgs.push(denom.get_transfer_to_message(&info.sender, amount)?);
+    }
+
+    // Refunded payments no longer count towards the totals. Payments the owner
+    // already voided were removed from them then.
+    let payment_ids = CAMPAIGN_PAYMENTS
+        .prefix((&id, &info.sender))
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for payment_id in payment_ids {
+        CAMPAIGN_PAYMENTS.remove(deps.storage, (&id, &info.sender, payment_id));
+        let payment = LEDGER
+            .payments
+            .load(deps.storage, (id.clone(), payment_id))?;
+        if payment.voided.is_none() {
+            LEDGER.void(
+                deps.storage,
+                &id,
+                payment_id,
+                Void {
+                    reason: "campaign refund".to_string(),
+                    voided_by: info.sender.clone(),
+                    height: env.block.height,
+                    time: env.block.time,
+                },
+            )?;
+        }
+    }
+
+    Ok(Response::default()
+        .add_messages(transfer_msgs)
+        .add_attribute("action", "claim_refund")
+        .add_attribute("id", id)
+        .add_attribute("payer", info.sender))
+}
+
+pub fn execute_assign_arbiter(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    arbiter: String,
+    dispute_window: Duration,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let arbiter = deps.api.addr_validate(&arbiter)?;
+    let id = normalize_receipt_id(deps.storage, &id)?;
+    if LEDGER.payment_count(deps.storage, &id)? > 0 {
+        return Err(ContractError::ReceiptAlreadyPaid);
+    }
+    if CAMPAIGNS.has(deps.storage, &id) {
+        return Err(ContractError::InvalidReceiptId {
+            reason: "receipt is a campaign".to_string(),
+        });
+    }
+
+    let escrow = Escrow {
+        arbiter,
+        dispute_window,
+        window_ends: None,
+        dispute: None,
+        released: false,
+    };
+    let previous = ESCROWS.may_load(deps.storage, &id)?;
+    ESCROWS.save(deps.storage, &id, &escrow)?;
+
+    log_admin_action(
+        deps.storage,
+        &env,
+        &info.sender,
+        AdminAction::AssignArbiter {
+            id: id.clone(),
+            old: previous,
+            new: escrow.clone(),
+        },
+    )?;
+
+    Ok(Response::default()
+        .add_attribute("action", "assign_arbiter")
+        .add_attribute("id", id)
+        .add_attribute("arbiter", escrow.arbiter)
+        .add_attribute("dispute_window", dispute_window.to_string()))
+}
+
+pub fn execute_open_dispute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    reason: String,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+
+    let id = normalize_receipt_id(deps.storage, &id)?;
+    let mut escrow = load_escrow(deps.storage, &id)?;
+    if LEDGER.payer(deps.storage, &id)?.as_ref() != Some(&info.sender) {
+        return Err(ContractError::UnauthorizedPayer);
+    }
+    if escrow.released {
+        return Err(ContractError::EscrowReleased { id });
+    }
+    if escrow.dispute.is_some() {
+        return Err(ContractError::DisputeOpen { id });
+    }
+    match escrow.window_ends {
+        Some(window_ends) if !window_ends.is_expired(&env.block) => {}
+        _ => return Err(ContractError::DisputeWindowEnded { id }),
+    }
+    if reason.trim().is_empty() {
+        return Err(ContractError::InvalidDispute {
+            reason: "no reason given".to_string(),
+        });
+    }
+
+    escrow.dispute = Some(Dispute {
+        reason: reason.clone(),
+        height: env.block.height,
+        time: env.block.time,
+        release_bps: None,
+    });
+    ESCROWS.save(deps.storage, &id, &escrow)?;
+
+    Ok(Response::default()
+        .add_attribute("action", "open_dispute")
+        .add_attribute("id", id)
+        .add_attribute("reason", reason))
+}
+
+pub fn execute_resolve_dispute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    release_bps: u16,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+
+    let id = normalize_receipt_id(deps.storage, &id)?;
+    let mut escrow = load_escrow(deps.storage, &id)?;
+    if info.sender != escrow.arbiter {
+        return Err(ContractError::Unauthorized);
+    }
+    if escrow.released {
+        return Err(ContractError::EscrowReleased { id });
+    }
+    let dispute = match escrow.dispute.as_mut() {
+        Some(dispute) => dispute,
+        None => return Err(ContractError::NoOpenDispute { id }),
+    };
+    if release_bps > BPS_DENOMINATOR {
+        return Err(ContractError::InvalidReleaseBps { release_bps });
+    }
+
+    dispute.release_bps = Some(release_bps);
+    escrow.released = true;
+    ESCROWS.save(deps.storage, &id, &escrow)?;
+
+    let output = OUTPUT.load(deps.storage)?;
+    let payer = LEDGER
+        .payer(deps.storage, &id)?
+        .ok_or_else(|| ContractError::NoReceiptPayer { id: id.clone() })?;
+    let mut transfer_msgs = vec![];
+    for (denom_id, denom, amount) in take_escrow_held(deps.storage, &id)? {
+        let released = amount.multiply_ratio(release_bps, BPS_DENOMINATOR);
+        let refunded = amount - released;
+        if !released.is_zero() {
+            transfer_msgs.push(denom.get_transfer_to_message(&output, released)?);
+        }
+        if !refunded.is_zero() {
+            // The refunded share no longer counts as paid.
+            LEDGER.void_amount(deps.storage, &id, denom_id, refunded, env.block.height)?;
+            transfer_msgs.push(denom.get_transfer_to_message(&payer, refunded)?);
+        }
+    }
+
+    Ok(Response::default()
+        .add_messages(transfer_msgs)
+        .add_attribute("action", "resolve_dispute")
+        .add_attribute("id", id)
+        .add_attribute("release_bps", release_bps.to_string()))
+}
+
+pub fn execute_release_escrow(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+
+    let id = normalize_receipt_id(deps.storage, &id)?;
+    let mut escrow = load_escrow(deps.storage, &id)?;
+    if escrow.released {
+        return Err(ContractError::EscrowReleased { id });
+    }
+    if escrow.dispute.is_some() {
+        return Err(ContractError::DisputeOpen { id });
+    }
+    match escrow.window_ends {
+        Some(window_ends) if window_ends.is_expired(&env.block) => {}
+        _ => return Err(ContractError::DisputeWindowOpen { id }),
+    }
+
+    escrow.released = true;
+    ESCROWS.save(deps.storage, &id, &escrow)?;
+
+    let output = OUTPUT.load(deps.storage)?;
+    let mut transfer_msgs = vec![];
+    for (_, denom, amount) in take_escrow_held(deps.storage, &id)? {
+        transfer_msgs.push(denom.get_transfer_to_message(&output, amount)?);
+    }
+
+    Ok(Response::default()
+        .add_messages(transfer_msgs)
+        .add_attribute("action", "release_escrow")
+        .add_attribute("id", id))
+}
+
+pub fn execute_close_period(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let period = LEDGER.close_period(deps.storage, &env.block, &info.sender)?;
+
+    log_admin_action(
+        deps.storage,
+        &env,
+        &info.sender,
+        AdminAction::ClosePeriod { period },
+    )?;
+
+    Ok(Response::default()
+        .add_attribute("action", "close_period")
+        .add_attribute("period", period.to_string()))
+}
+
+pub fn execute_sweep(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: UncheckedDenom,
+    amount: Option<Uint128>,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let denom = denom.into_checked(deps.as_ref())?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    let sweepable = sweepable(deps.as_ref(), &env, &denom)?.sweepable;
+    let amount = amount.unwrap_or(sweepable);
+    if amount.is_zero() || amount > sweepable {
+        return Err(ContractError::InsufficientSweepable {
+            denom: denom.to_string(),
+            sweepable,
+        });
+    }
+
+    let transfer_msg = denom.get_transfer_to_message(&recipient, amount)?;
+
+    log_admin_action(
+        deps.storage,
+        &env,
+        &info.sender,
+        AdminAction::Sweep {
+            denom: denom.clone(),
+            amount,
+            recipient: recipient.clone(),
+        },
+    )?;
+
+    Ok(Response::default()
+        .add_message(transfer_msg)
+        .add_attribute("action", "sweep")
+        .add_attribute("denom", denom.to_string())
+        .add_attribute("amount", amount)
+        .add_attribute("recipient", recipient))
+}
+
+pub fn execute_update_output(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    output: String,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let output_addr = deps.api.addr_validate(&output)?;
+    let old_output = OUTPUT.load(deps.storage)?;
+    OUTPUT.save(deps.storage, &output_addr)?;
+
+    log_admin_action(
+        deps.storage,
+        &env,
+        &info.sender,
+        AdminAction::UpdateOutput {
+            old: old_output,
+            new: output_addr,
+        },
+    )?;
+
+    Ok(Response::default()
+        .add_attribute("action", "update_output")
+        .add_attribute("output", output))
+}
+
+pub fn execute_update_id_rules(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id_rules: Option<IdRules>,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let old_id_rules = ID_RULES.may_load(deps.storage)?;
+    match id_rules.clone() {
+        Some(id_rules) => save_id_rules(deps.storage, id_rules)?,
+        None => ID_RULES.remove(deps.storage),
+    }
+
+    log_admin_action(
+        deps.storage,
+        &env,
+        &info.sender,
+        AdminAction::UpdateIdRules {
+            old: old_id_rules,
+            new: id_rules,
+        },
+    )?;
+
+    Ok(Response::default().add_attribute("action", "update_id_rules"))
+}
+
+pub fn execute_assign_payer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    payer: Option<String>,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let payer = payer
+        .map(|payer| deps.api.addr_validate(&payer))
+        .transpose()?;
+    // Assigned IDs must follow the rules, but are never bound to the payer
+    // since the assignment already protects them.
+    let id = match (
+        generated_receipt_id(deps.storage, &id)?,
+        ID_RULES.may_load(deps.storage)?,
+    ) {
+        (Some(id), _) => id,
+        (None, Some(id_rules)) => id_rules
+            .validate_id(&id)
+            .map_err(|reason| ContractError::InvalidReceiptId { reason })?,
+        (None, None) => id,
+    };
+
+    if LEDGER.payment_count(deps.storage, &id)? > 0 {
+        return Err(ContractError::ReceiptAlreadyPaid);
+    }
+
+    let previous = LEDGER.set_payer(deps.storage, &id, payer.as_ref())?;
+
+    log_admin_action(
+        deps.storage,
+        &env,
+        &info.sender,
+        AdminAction::AssignPayer {
+            id: id.clone(),
+            old: previous,
+            new: payer.clone(),
+        },
+    )?;
+
+    Ok(Response::default()
+        .add_attribute("action", "assign_payer")
+        .add_attribute("id", id)
+        .add_attribute(
+            "payer",
+            payer
+                .map(|payer| payer.to_string())
+                .unwrap_or_else(|| "none".to_string()),
+        ))
+}
+
+pub fn execute_close_receipt(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    reason: String,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let id = normalize_receipt_id(deps.storage, &id)?;
+    if RECEIPT_CLOSURES.has(deps.storage, id.clone()) {
+        return Err(ContractError::ReceiptClosed { id });
+    }
+
+    RECEIPT_CLOSURES.save(
+        deps.storage,
+        id.clone(),
+        &Closure {
+            reason: reason.clone(),
+            closed_by: info.sender.clone(),
+            height: env.block.height,
+            time: env.block.time,
+        },
+    )?;
+
+    log_admin_action(
+        deps.storage,
+        &env,
+        &info.sender,
+        AdminAction::CloseReceipt {
+            id: id.clone(),
+            reason: reason.clone(),
+        },
+    )?;
+
+    Ok(Response::default()
+        .add_attribute("action", "close_receipt")
+        .add_attribute("id", id)
+        .add_attribute("reason", reason))
+}
+
+pub fn execute_reopen_receipt(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let id = normalize_receipt_id(deps.storage, &id)?;
+    let closure = RECEIPT_CLOSURES
+        .may_load(deps.storage, id.clone())?
+        .ok_or_else(|| ContractError::ReceiptNotClosed { id: id.clone() })?;
+    RECEIPT_CLOSURES.remove(deps.storage, id.clone());
+
+    log_admin_action(
+        deps.storage,
+        &env,
+        &info.sender,
+        AdminAction::ReopenReceipt {
+            id: id.clone(),
+            old: closure,
+        },
+    )?;
+
+    Ok(Response::default()
+        .add_attribute("action", "reopen_receipt")
+        .add_attribute("id", id))
+}
+
+pub fn execute_transfer_receipt(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    new_payer: String,
+) -> Result<Response, ContractError> {
+    let id = normalize_receipt_id(deps.storage, &id)?;
+    let new_payer = deps.api.addr_validate(&new_payer)?;
+
+    let payer = LEDGER
+        .payer(deps.storage, &id)?
+        .ok_or_else(|| ContractError::NoReceiptPayer { id: id.clone() })?;
+    if info.sender != payer && cw_ownable::assert_owner(deps.storage, &info.sender).is_err() {
+        return Err(ContractError::Unauthorized);
+    }
+
+    // Move payer rights. Totals stay with the payer that paid them.
+    LEDGER.set_payer(deps.storage, &id, Some(&new_payer))?;
+
+    // Record transfer.
+    let transfer_id = RECEIPT_TRANSFERS
+        .prefix(id.clone())
+        .keys(deps.storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map_or(0, |last| last + 1);
+    RECEIPT_TRANSFERS.save(
+        deps.storage,
+        (id.clone(), transfer_id),
+        &Transfer {
+            from: payer.clone(),
+            to: new_payer.clone(),
+            transferred_by: info.sender.clone(),
+            height: env.block.height,
+            time: env.block.time,
+        },
+    )?;
+
+    if info.sender != payer {
+        log_admin_action(
+            deps.storage,
+            &env,
+            &info.sender,
+            AdminAction::TransferReceipt {
+                id: id.clone(),
+                old: payer.clone(),
+                new: new_payer.clone(),
+            },
+        )?;
+    }
+
+    Ok(Response::default()
+        .add_attribute("action", "transfer_receipt")
+        .add_event(
+            Event::new("transfer_receipt")
+                .add_attribute("id", id)
+                .add_attribute("transfer_id", transfer_id.to_string())
+                .add_attribute("from", payer)
+                .add_attribute("to", new_payer)
+                .add_attribute("transferred_by", info.sender),
+        ))
+}
+
+pub fn execute_void_payment(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    payment_index: u64,
+    reason: String,
+    restock: bool,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let id = normalize_receipt_id(deps.storage, &id)?;
+    let payment = LEDGER.void(
+        deps.storage,
+        &id,
+        payment_index,
+        Void {
+            reason: reason.clone(),
+            voided_by: info.sender.clone(),
+            height: env.block.height,
+            time: env.block.time,
+        },
+    )?;
+    if restock {
+        return_stock(deps.storage, &payment.items)?;
+    }
+
+    log_admin_action(
+        deps.storage,
+        &env,
+        &info.sender,
+        AdminAction::VoidPayment {
+            id: id.clone(),
+            payment_index,
+            reason: reason.clone(),
+            restock,
+        },
+    )?;
+
+    Ok(Response::default()
+        .add_attribute("action", "void_payment")
+        .add_attribute("id", id)
+        .add_attribute("payment_index", payment_index.to_string())
+        .add_attribute("reason", reason)
+        .add_attribute("restock", restock.to_string()))
+}
+
+pub fn execute_update_owner(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    action: cw_ownable::Action,
+) -> Result<Response, ContractError> {
+    let old_ownership = cw_ownable::get_ownership(deps.storage)?;
+    let ownership = cw_ownable::update_ownership(deps.branch(), &env.block, &info.sender, action)?;
+
+    log_admin_action(
+        deps.storage,
+        &env,
+        &info.sender,
+        AdminAction::UpdateOwnership {
+            old: old_ownership,
+            new: ownership.clone(),
+        },
+    )?;
+
+    Ok(Response::default().add_attributes(ownership.into_attributes()))
+}
+
+fn save_id_rules(storage: &mut dyn Storage, id_rules: IdRules) -> Result<(), ContractError> {
+    id_rules
+        .validate()
+        .map_err(|reason| ContractError::InvalidIdRules { reason })?;
+    ID_RULES.save(storage, &id_rules)?;
+    Ok(())
+}
+
+fn save_forwarding(deps: DepsMut, forwarding: &ForwardingConfig) -> Result<(), ContractError> {
+    if let Some(batch) = &forwarding.batch {
+        if forwarding.settlement.is_some() {
+            return Err(ContractError::InvalidSettlement {
+                reason: "batching cannot be combined with swapping".to_string(),
+            });
+        }
+        if batch
+            .thresholds
+            .iter()
+            .any(|threshold| threshold.amount.is_zero())
+        {
+            return Err(ContractError::InvalidSettlement {
+                reason: "batch threshold is zero".to_string(),
+            });
+        }
+    }
+    if let Some(settlement) = &forwarding.settlement {
+        deps.api.addr_validate(settlement.router.as_str())?;
+        let unchecked = match &settlement.denom {
+            CheckedDenom::Native(denom) => UncheckedDenom::Native(denom.clone()),
+            CheckedDenom::Cw20(cw20) => UncheckedDenom::Cw20(cw20.to_string()),
+        };
+        unchecked.into_checked(deps.as_ref())?;
+        if settlement.max_slippage > Decimal::one() {
+            return Err(ContractError::InvalidSettlement {
+                reason: "max slippage is above 1".to_string(),
+            });
+        }
+        for (i, reference) in settlement.reference_prices.iter().enumerate() {
+            if reference.price.is_zero() {
+                return Err(ContractError::InvalidSettlement {
+                    reason: format!("reference price for {} is zero", reference.denom),
+                });
+            }
+            if settlement.reference_prices[..i]
+                .iter()
+                .any(|other| other.denom == reference.denom)
+            {
+                return Err(ContractError::InvalidSettlement {
+                    reason: format!("duplicate reference price for {}", reference.denom),
+                });
+            }
+        }
+    }
+    FORWARDING.save(deps.storage, forwarding)?;
+    Ok(())
+}
+
+fn load_campaign(storage: &dyn Storage, id: &str) -> Result<Campaign, ContractError> {
+    CAMPAIGNS
+        .may_load(storage, id)?
+        .ok_or_else(|| ContractError::CampaignNotFound { id: id.to_string() })
+}
+
+fn load_escrow(storage: &dyn Storage, id: &str) -> Result<Escrow, ContractError> {
+    ESCROWS
+        .may_load(storage, id)?
+        .ok_or_else(|| ContractError::EscrowNotFound { id: id.to_string() })
+}
+
+/// Clear the funds held in escrow for a receipt, returning them with their
+/// interned denom IDs.
+fn take_escrow_held(
+    storage: &mut dyn Storage,
+    id: &str,
+) -> StdResult<Vec<(u32, CheckedDenom, Uint128)>> {
+    let held = ESCROW_HELD
+        .prefix(id)
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut taken = vec![];
+    for (denom_id, amount) in held {
+        ESCROW_HELD.remove(storage, (id, denom_id));
+        subtract_total(storage, &HELD_IN_ESCROW, denom_id, amount)?;
+        taken.push((denom_id, LEDGER.denoms.load(storage, denom_id)?, amount));
+    }
+    Ok(taken)
+}
+
+/// Returns whether a campaign holds at least its goal in every denom.
+fn campaign_goal_met(storage: &dyn Storage, id: &str, campaign: &Campaign) -> StdResult<bool> {
+    for goal in &campaign.goal {
+        let raised = match LEDGER.denom_id(storage, &goal.denom)? {
+            Some(denom_id) => CAMPAIGN_RAISED
+                .may_load(storage, (id, denom_id))?
+                .unwrap_or_default(),
+            None => Uint128::zero(),
+        };
+        if raised < goal.amount {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Returns the canonical form of a receipt ID paid by payer, or an error if it
+/// breaks the configured rules.
+fn canonicalize_receipt_id(
+    storage: &dyn Storage,
+    id: &str,
+    payer: &Addr,
+) -> Result<String, ContractError> {
+    if let Some(id) = generated_receipt_id(storage, id)? {
+        return Ok(id);
+    }
+
+    match ID_RULES.may_load(storage)? {
+        Some(id_rules) => {
+            if id_rules.bind_to_payer {
+                // Receipts the payer already holds, either assigned to them or
+                // transferred to them after being bound to another payer, are
+                // not bound again. Nor are campaigns, which everyone pays
+                // under the same ID, or escrowed receipts, whose arbiter is
+                // assigned to the ID as given.
+                for id in [id.to_string(), id_rules.normalize(id)] {
+                    if LEDGER.payer(storage, &id)?.as_ref() == Some(payer)
+                        || CAMPAIGNS.has(storage, &id)
+                        || ESCROWS.has(storage, &id)
+                    {
+                        return Ok(id);
+                    }
+                }
+            } else if receipt_id_in_use(storage, id)? {
+                // Receipts created before the rules changed keep their IDs.
+                return Ok(id.to_string());
+            }
+            id_rules
+                .canonicalize(id, payer)
+                .map_err(|reason| ContractError::InvalidReceiptId { reason })
+        }
+        None => Ok(id.to_string()),
+    }
+}
+
+/// Returns the receipt ID normalized for lookups, without rejecting it. IDs
+/// already in use are returned as-is, since they may predate the rules.
+fn normalize_receipt_id(storage: &dyn Storage, id: &str) -> StdResult<String> {
+    if id.starts_with(GENERATED_ID_PREFIX) {
+        return Ok(id.to_string());
+    }
+
+    Ok(match ID_RULES.may_load(storage)? {
+        Some(id_rules) if !receipt_id_in_use(storage, id)? => id_rules.normalize(id),
+        _ => id.to_string(),
+    })
+}
+
+/// Returns the ID as-is if it has the generated ID prefix, since generated IDs
+/// are exempt from the ID rules. Rejects IDs with the prefix that were never
+/// generated, unless already used before the prefix was reserved.
+fn generated_receipt_id(storage: &dyn Storage, id: &str) -> Result<Option<String>, ContractError> {
+    if !id.starts_with(GENERATED_ID_PREFIX) {
+        return Ok(None);
+    }
+
+    if !RECEIPT_CREATIONS.has(storage, id.to_string()) && !receipt_id_in_use(storage, id)? {
+        return Err(ContractError::InvalidReceiptId {
+            reason: format!(
+                "prefix {} is reserved for generated IDs",
+                GENERATED_ID_PREFIX
+            ),
+        });
+    }
+
+    Ok(Some(id.to_string()))
+}
+
+fn receipt_id_in_use(storage: &dyn Storage, id: &str) -> StdResult<bool> {
+    Ok(LEDGER.payer(storage, id)?.is_some()
+        || LEDGER.payment_count(storage, id)? > 0
+        || RECEIPT_CLOSURES.has(storage, id.to_string()))
+}
+
+/// Allocate the next generated receipt ID, skipping any already in use.
+fn allocate_receipt_id(
+    storage: &mut dyn Storage,
+    env: &Env,
+    creator: &Addr,
+    metadata: Option<String>,
+) -> StdResult<String> {
+    let mut count = GENERATED_ID_COUNT.may_load(storage)?.unwrap_or_default();
+    let id = loop {
+        let id = format!("{}{}", GENERATED_ID_PREFIX, count);
+        count += 1;
+        if !receipt_id_in_use(storage, &id)? {
+            break id;
+        }
+    };
+    GENERATED_ID_COUNT.save(storage, &count)?;
+
+    RECEIPT_CREATIONS.save(
+        storage,
+        id.clone(),
+        &Creation {
+            creator: creator.clone(),
+            metadata,
+            height: env.block.height,
+            time: env.block.time,
+        },
+    )?;
+
+    Ok(id)
+}
+
+fn record_payment_and_get_transfer_msgs(
+    storage: &mut dyn Storage,
+    env: &Env,
+    id: &String,
+    denom: &CheckedDenom,
+    payer: Addr,
+    amount: Uint128,
+) -> Result<(u64, Vec<SubMsg>), ContractError> {
+    let output = OUTPUT.load(storage)?;
+
+    if RECEIPT_CLOSURES.has(storage, id.to_string()) {
+        return Err(ContractError::ReceiptClosed { id: id.to_string() });
+    }
+
+    // Campaigns hold payments from anyone until finalized or refunded.
+    if let Some(campaign) = CAMPAIGNS.may_load(storage, id)? {
+        if campaign.finalized || campaign.deadline.is_expired(&env.block) {
+            return Err(ContractError::CampaignEnded { id: id.to_string() });
+        }
+        if !campaign.goal.iter().any(|goal| goal.denom == *denom) {
+            return Err(ContractError::CampaignDenomNotAccepted {
+                id: id.to_string(),
+                denom: denom.to_string(),
+            });
+        }
+
+        let receipt_payment_id =
+            LEDGER.record_from_any_payer(storage, &env.block, id, &payer, denom, amount)?;
+        let denom_id = LEDGER.intern_denom(storage, denom)?;
+        add_total(storage, &CAMPAIGN_RAISED, (id.as_str(), denom_id), amount)?;
+        add_total(
+            storage,
+            &CAMPAIGN_CONTRIBUTIONS,
+            (id.as_str(), &payer, denom_id),
+            amount,
+        )?;
+        CAMPAIGN_PAYMENTS.save(
+            storage,
+            (id.as_str(), &payer, receipt_payment_id),
+            &Empty {},
+        )?;
+        add_total(storage, &HELD_FOR_CAMPAIGNS, denom_id, amount)?;
+        return Ok((receipt_payment_id, vec![]));
+    }
+
+    let receipt_payment_id = LEDGER.record(storage, &env.block, id, &payer, denom, amount)?;
+    let denom_id = LEDGER.intern_denom(storage, denom)?;
+
+    // Escrowed receipts hold payments until released. The dispute window
+    // starts at the first payment, so later payments cannot keep extending it.
+    if let Some(mut escrow) = ESCROWS.may_load(storage, id)? {
+        if escrow.released {
+            return Err(ContractError::EscrowReleased { id: id.to_string() });
+        }
+        if escrow.dispute.is_some() {
+            return Err(ContractError::DisputeOpen { id: id.to_string() });
+        }
+        if escrow.window_ends.is_none() {
+            escrow.window_ends = Some(escrow.dispute_window.after(&env.block));
+            ESCROWS.save(storage, id, &escrow)?;
+        }
+        add_total(storage, &ESCROW_HELD, (id.as_str(), denom_id), amount)?;
+        add_total(storage, &HELD_IN_ESCROW, denom_id, amount)?;
+        return Ok((receipt_payment_id, vec![]));
+    }
+
+    let forwarding = FORWARDING.may_load(storage)?.unwrap_or_default();
+    if let Some(batch) = forwarding.batch {
+        add_total(storage, &OWED_TO_OUTPUT, denom_id, amount)?;
+        let owed = OWED_TO_OUTPUT.load(storage, denom_id)?;
+        let transfer_msgs = match batch.threshold(denom) {
+            Some(threshold) if owed >= threshold => {
+                vec![get_batch_transfer_msg(
+                    storage,
+                    forwarding.resilient,
+                    &output,
+                    denom_id,
+                    denom,
+                )?]
+            }
+            _ => vec![],
+        };
+        return Ok((receipt_payment_id, transfer_msgs));
+    }
+    if let Some(settlement) = forwarding.settlement {
+        if settlement.denom != *denom {
+            // The reply records what the output received.
+            SETTLEMENTS_IN_FLIGHT.save(
+                storage,
+                denom_id,
+                &InFlightSettlement {
+                    id: id.to_string(),
+                    payment_id: receipt_payment_id,
+                    amount,
+                },
+            )?;
+            let swap_msgs = get_swap_msgs(
+                &settlement,
+                forwarding.resilient,
+                &output,
+                denom_id,
+                denom,
+                amount,
+            )?;
+            return Ok((receipt_payment_id, swap_msgs));
+        }
+        // Already in the settlement denom, so the output receives it as is.
+        LEDGER.set_settled(storage, id, receipt_payment_id, denom, amount)?;
+    }
+
+    let transfer_msg = denom.get_transfer_to_message(&output, amount)?;
+    let transfer_msg = if forwarding.resilient {
+        // Remember the amount in case the transfer fails and the reply needs
+        // to hold it as unforwarded.
+        FORWARDS_IN_FLIGHT.save(storage, denom_id, &amount)?;
+        SubMsg::reply_on_error(transfer_msg, FORWARD_REPLY_ID | denom_id as u64)
+    } else {
+        SubMsg::new(transfer_msg)
+    };
+
+    Ok((receipt_payment_id, vec![transfer_msg]))
+}
+
+/// Returns the message sending everything owed to the output in a denom, and
+/// clears what is owed. If resilient, a failed transfer is owed again.
+fn get_batch_transfer_msg(
+    storage: &mut dyn Storage,
+    resilient: bool,
+    output: &Addr,
+    denom_id: u32,
+    denom: &CheckedDenom,
+) -> StdResult<SubMsg> {
+    let amount = OWED_TO_OUTPUT.load(storage, denom_id)?;
+    OWED_TO_OUTPUT.remove(storage, denom_id);
+
+    let transfer_msg = denom.get_transfer_to_message(output, amount)?;
+    Ok(if resilient {
+        BATCHES_IN_FLIGHT.save(storage, denom_id, &amount)?;
+        SubMsg::reply_on_error(transfer_msg, BATCH_REPLY_ID | denom_id as u64)
+    } else {
+        SubMsg::new(transfer_msg)
+    })
+}
+
+/// Returns messages swapping a payment into the settlement denom through the
+/// router, which sends the proceeds to the output. Allows at most the maximum
+/// slippage below the denom's reference price.
+fn get_swap_msgs(
+    settlement: &SettlementConfig,
+    resilient: bool,
+    output: &Addr,
+    denom_id: u32,
+    denom: &CheckedDenom,
+    amount: Uint128,
+) -> Result<Vec<SubMsg>, ContractError> {
+    let price =
+        settlement
+            .reference_price(denom)
+            .ok_or_else(|| ContractError::NoReferencePrice {
+                denom: denom.to_string(),
+            })?;
+    let min_return = amount * price * (Decimal::one() - settlement.max_slippage);
+
+    let swap_msg = to_binary(&RouterExecuteMsg::Swap {
+        offer: Total {
+            denom: denom.clone(),
+            amount,
+        },
+        ask_denom: settlement.denom.clone(),
+        min_return,
+        recipient: output.to_string(),
+    })?;
+
+    let mut msgs = vec![];
+    let swap_msg = match denom {
+        CheckedDenom::Native(native) => WasmMsg::Execute {
+            contract_addr: settlement.router.to_string(),
+            msg: swap_msg,
+            funds: coins(amount.u128(), native),
+        },
+        CheckedDenom::Cw20(cw20) => {
+            // Let the router pull the tokens.
+            msgs.push(SubMsg::new(WasmMsg::Execute {
+                contract_addr: cw20.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: settlement.router.to_string(),
+                    amount,
+                    expires: None,
+                })?,
+                funds: vec![],
+            }));
+            WasmMsg::Execute {
+                contract_addr: settlement.router.to_string(),
+                msg: swap_msg,
+                funds: vec![],
+            }
+        }
+    };
+
+    // Failed swaps revert the payment unless forwarding is resilient, in which
+    // case the reply holds the payment as unforwarded.
+    let reply_id = SETTLE_REPLY_ID | denom_id as u64;
+    msgs.push(if resilient {
+        SubMsg::reply_always(swap_msg, reply_id)
+    } else {
+        SubMsg::reply_on_success(swap_msg, reply_id)
+    });
+
+    Ok(msgs)
+}
+
+/// Returns the line items for catalog products, erroring unless the amount paid
+/// in the denom is exactly what they cost.
+fn price_items(
+    storage: &dyn Storage,
+    items: &[(String, u32)],
+    denom: &CheckedDenom,
+    amount: Uint128,
+) -> Result<Vec<LineItem>, ContractError> {
+    if items.is_empty() {
+        return Err(ContractError::InvalidItems {
+            reason: "no items".to_string(),
+        });
+    }
+
+    let mut cost = Uint128::zero();
+    let mut line_items = vec![];
+    for (sku, quantity) in items {
+        if *quantity == 0 {
+            return Err(ContractError::InvalidItems {
+                reason: format!("quantity of {} is zero", sku),
+            });
+        }
+        let product = PRODUCTS
+            .may_load(storage, sku)?
+            .ok_or_else(|| ContractError::ProductNotFound { sku: sku.clone() })?;
+        if !product.active {
+            return Err(ContractError::ProductInactive { sku: sku.clone() });
+        }
+        let unit_price = product
+            .price(denom)
+            .ok_or_else(|| ContractError::ProductNotPriced {
+                sku: sku.clone(),
+                denom: denom.to_string(),
+            })?;
+
+        let line_cost = unit_price
+            .checked_mul(Uint128::from(*quantity))
+            .map_err(StdError::from)?;
+        cost = cost.checked_add(line_cost).map_err(StdError::from)?;
+        line_items.push(LineItem {
+            sku: sku.clone(),
+            quantity: *quantity,
+            unit_price,
+        });
+    }
+
+    if amount != cost {
+        return Err(ContractError::ItemsCostMismatch {
+            expected: cost,
+            denom: denom.to_string(),
+        });
+    }
+
+    Ok(line_items)
+}
+
+/// Remove sold line items from the stock of products that track it.
+fn take_stock(storage: &mut dyn Storage, line_items: &[LineItem]) -> Result<(), ContractError> {
+    for item in line_items {
+        if let Some(remaining) = PRODUCT_STOCK.may_load(storage, &item.sku)? {
+            let remaining =
+                remaining
+                    .checked_sub(item.quantity)
+                    .ok_or_else(|| ContractError::SoldOut {
+                        sku: item.sku.clone(),
+                        remaining,
+                    })?;
+            PRODUCT_STOCK.save(storage, &item.sku, &remaining)?;
+        }
+    }
+    Ok(())
+}
+
+/// Return line items to the stock of products that track it.
+fn return_stock(storage: &mut dyn Storage, line_items: &[LineItem]) -> StdResult<()> {
+    for item in line_items {
+        if let Some(remaining) = PRODUCT_STOCK.may_load(storage, &item.sku)? {
+            PRODUCT_STOCK.save(storage, &item.sku, &remaining.saturating_add(item.quantity))?;
+        }
+    }
+    Ok(())
+}
+
+/// Reject the payment if the payer already used the idempotency key on the
+/// receipt ID and it has not expired, and otherwise claim it. Also removes a
+/// few expired keys so storage does not grow without bound.
+fn claim_idempotency_key(
+    storage: &mut dyn Storage,
+    env: &Env,
+    payer: &Addr,
+    id: &str,
+    key: &str,
+) -> Result<(), ContractError> {
+    if key.is_empty() || key.len() > IDEMPOTENCY_KEY_MAX_LENGTH {
+        return Err(ContractError::InvalidIdempotencyKey {
+            max_length: IDEMPOTENCY_KEY_MAX_LENGTH,
+        });
+    }
+
+    let now = env.block.time.seconds();
+
+    let expired = IDEMPOTENCY_KEY_EXPIRIES
+        .keys(storage, None, None, Order::Ascending)
+        .take(IDEMPOTENCY_KEY_PRUNE_LIMIT)
+        .take_while(|key| key.as_ref().map_or(true, |(expires, _)| *expires <= now))
+        .collect::<StdResult<Vec<_>>>()?;
+    for (expires, hash) in expired {
+        IDEMPOTENCY_KEY_EXPIRIES.remove(storage, (expires, &hash));
+        IDEMPOTENCY_KEYS.remove(storage, &hash);
+    }
+
+    let hash = idempotency_key_hash(payer, id, key);
+    if let Some(expires) = IDEMPOTENCY_KEYS.may_load(storage, &hash)? {
+        if expires > now {
+            return Err(ContractError::DuplicatePayment {
+                key: key.to_string(),
+            });
+        }
+        IDEMPOTENCY_KEY_EXPIRIES.remove(storage, (expires, &hash));
+    }
+
+    let expires = now + IDEMPOTENCY_KEY_TTL_SECONDS;
+    IDEMPOTENCY_KEYS.save(storage, &hash, &expires)?;
+    IDEMPOTENCY_KEY_EXPIRIES.save(storage, (expires, &hash), &Empty {})?;
+
+    Ok(())
+}
+
+/// Check that a payment request was signed by a registered signer key, has not
+/// expired or been paid, and matches the payer and funds. Marks the nonce used.
+fn verify_payment_request(
+    deps: DepsMut,
+    env: &Env,
+    request: PaymentRequest,
+    signature: &Binary,
+    public_key: &Binary,
+    payer: &Addr,
+    paid: &[(CheckedDenom, Uint128)],
+) -> Result<(), ContractError> {
+    if !SIGNER_KEYS.has(deps.storage, public_key.as_slice()) {
+        return Err(ContractError::UnknownSignerKey);
+    }
+
+    let nonce = request.nonce;
+    let expires = request.expires;
+    let request_payer = deps.api.addr_validate(&request.payer)?;
+    let request_amounts = request
+        .amounts
+        .iter()
+        .map(|total| (total.denom.clone(), total.amount))
+        .collect::<Vec<_>>();
+
+    let payload = to_vec(&SignedPaymentRequest {
+        contract: env.contract.address.to_string(),
+        request,
+    })?;
+    let hash = Sha256::digest(payload);
+    let verified = deps
+        .api
+        .secp256k1_verify(&hash, signature, public_key)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    if !verified {
+        return Err(ContractError::InvalidSignature);
+    }
+
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::PaymentRequestExpired);
+    }
+    if *payer != request_payer {
+        return Err(ContractError::UnauthorizedPayer);
+    }
+    // Funds have unique denoms, so matching lengths and membership both ways
+    // means the amounts are the same.
+    if paid.len() != request_amounts.len()
+        || paid.iter().any(|fund| !request_amounts.contains(fund))
+        || request_amounts.iter().any(|amount| !paid.contains(amount))
+    {
+        return Err(ContractError::PaymentRequestMismatch);
+    }
+
+    let nonce_key = (public_key.as_slice(), nonce);
+    if USED_NONCES.has(deps.storage, nonce_key) {
+        return Err(ContractError::NonceUsed { nonce });
+    }
+    USED_NONCES.save(deps.storage, nonce_key, &Empty {})?;
+
+    Ok(())
+}
+
+/// Build the response data for payments just recorded to a receipt.
+fn pay_response(
+    storage: &dyn Storage,
+    id: &str,
+    recorded: Vec<(u64, CheckedDenom, Uint128)>,
+) -> StdResult<PayResponse> {
+    let mut totals: Vec<Total> = vec![];
+    for (_, denom, _) in &recorded {
+        if !totals.iter().any(|total| total.denom == *denom) {
+            totals.push(Total {
+                denom: denom.clone(),
+                amount: LEDGER.receipt_total(storage, id, denom)?,
+            });
+        }
+    }
+
+    Ok(PayResponse {
+        payments: recorded
+            .into_iter()
+            .map(|(receipt_payment_id, denom, amount)| RecordedPayment {
+                receipt_id: id.to_string(),
+                receipt_payment_id,
+                denom,
+                amount,
+            })
+            .collect(),
+        totals,
+    })
+}
+
+/// Returns how much of denom the contract holds on behalf of others and
+/// must never sweep.
+fn owed(storage: &dyn Storage, denom: &CheckedDenom) -> StdResult<Uint128> {
+    let denom_id = match LEDGER.denom_id(storage, denom)? {
+        Some(denom_id) => denom_id,
+        None => return Ok(Uint128::zero()),
+    };
+
+    let unforwarded = UNFORWARDED.may_load(storage, denom_id)?.unwrap_or_default();
+    let owed_to_output = OWED_TO_OUTPUT
+        .may_load(storage, denom_id)?
+        .unwrap_or_default();
+    let held_for_campaigns = HELD_FOR_CAMPAIGNS
+        .may_load(storage, denom_id)?
+        .unwrap_or_default();
+    let held_in_escrow = HELD_IN_ESCROW
+        .may_load(storage, denom_id)?
+        .unwrap_or_default();
+    Ok(unforwarded + owed_to_output + held_for_campaigns + held_in_escrow)
+}
+
+fn sweepable(deps: Deps, env: &Env, denom: &CheckedDenom) -> StdResult<SweepableResponse> {
+    let balance = denom.query_balance(&deps.querier, &env.contract.address)?;
+    let owed = owed(deps.storage, denom)?;
+
+    Ok(SweepableResponse {
+        denom: denom.clone(),
+        balance,
+        owed,
+        sweepable: balance.saturating_sub(owed),
+    })
+}
+
+/// Append an entry to the admin log.
+fn log_admin_action(
+    storage: &mut dyn Storage,
+    env: &Env,
+    actor: &Addr,
+    action: AdminAction,
+) -> StdResult<()> {
+    let entry_id = ADMIN_LOG_COUNT.may_load(storage)?.unwrap_or_default();
+    ADMIN_LOG.save(
+        storage,
+        entry_id,
+        &AdminLogEntry {
+            actor: actor.clone(),
+            action,
+            height: env.block.height,
+            time: env.block.time,
+        },
+    )?;
+    ADMIN_LOG_COUNT.save(storage, &(entry_id + 1))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let denom_id = (msg.id & !REPLY_KIND_MASK) as u32;
+    match msg.id & REPLY_KIND_MASK {
+        FORWARD_REPLY_ID => reply_forward(deps, denom_id, msg.result),
+        SETTLE_REPLY_ID => reply_settle(deps, denom_id, msg.result),
+        BATCH_REPLY_ID => reply_batch(deps, denom_id, msg.result),
+        _ => Err(ContractError::UnknownReplyId { id: msg.id }),
+    }
+}
+
+/// Hold the amount of a failed resilient forward as unforwarded. The payment
+/// stays recorded.
+fn reply_forward(
+    deps: DepsMut,
+    denom_id: u32,
+    result: SubMsgResult,
+) -> Result<Response, ContractError> {
+    let error = match result {
+        SubMsgResult::Err(error) => error,
+        SubMsgResult::Ok(_) => return Ok(Response::default()),
+    };
+
+    let amount = FORWARDS_IN_FLIGHT.load(deps.storage, denom_id)?;
+    FORWARDS_IN_FLIGHT.remove(deps.storage, denom_id);
+    add_total(deps.storage, &UNFORWARDED, denom_id, amount)?;
+
+    let denom = LEDGER.denoms.load(deps.storage, denom_id)?;
+
+    Ok(Response::default()
+        .add_attribute("action", "forward_failed")
+        .add_attribute("denom", denom_to_string(&denom))
+        .add_attribute("amount", amount)
+        .add_attribute("error", error))
+}
+
+/// Owe the amount of a failed resilient batch transfer to the output again.
+fn reply_batch(
+    deps: DepsMut,
+    denom_id: u32,
+    result: SubMsgResult,
+) -> Result<Response, ContractError> {
+    let error = match result {
+        SubMsgResult::Err(error) => error,
+        SubMsgResult::Ok(_) => return Ok(Response::default()),
+    };
+
+    let amount = BATCHES_IN_FLIGHT.load(deps.storage, denom_id)?;
+    BATCHES_IN_FLIGHT.remove(deps.storage, denom_id);
+    add_total(deps.storage, &OWED_TO_OUTPUT, denom_id, amount)?;
+
+    let denom = LEDGER.denoms.load(deps.storage, denom_id)?;
+
+    Ok(Response::default()
+        .add_attribute("action", "batch_transfer_failed")
+        .add_attribute("denom", denom_to_string(&denom))
+        .add_attribute("amount", amount)
+        .add_attribute("error", error))
+}
+
+/// Record what the output received for a payment swapped into the settlement
+/// denom. If the swap failed, hold the payment as unforwarded.
+fn reply_settle(
+    deps: DepsMut,
+    denom_id: u32,
+    result: SubMsgResult,
+) -> Result<Response, ContractError> {
+    let in_flight = SETTLEMENTS_IN_FLIGHT.load(deps.storage, denom_id)?;
+    SETTLEMENTS_IN_FLIGHT.remove(deps.storage, denom_id);
+    let denom = LEDGER.denoms.load(deps.storage, denom_id)?;
+
+    let response = match result {
+        SubMsgResult::Ok(response) => response,
+        SubMsgResult::Err(error) => {
+            add_total(deps.storage, &UNFORWARDED, denom_id, in_flight.amount)?;
+
+            // Take back the allowance the router did not use.
+            let mut msgs = vec![];
+            let settlement = FORWARDING.load(deps.storage)?.settlement;
+            if let (CheckedDenom::Cw20(cw20), Some(settlement)) = (&denom, settlement) {
+                msgs.push(WasmMsg::Execute {
+                    contract_addr: cw20.to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::DecreaseAllowance {
+                        spender: settlement.router.to_string(),
+                        amount: in_flight.amount,
+                        expires: None,
+                    })?,
+                    funds: vec![],
+                });
+            }
+
+            return Ok(Response::default()
+                .add_messages(msgs)
+                .add_attribute("action", "settle_failed")
+                .add_attribute("id", in_flight.id)
+                .add_attribute("denom", denom_to_string(&denom))
+                .add_attribute("amount", in_flight.amount)
+                .add_attribute("error", error));
+        }
+    };
+
+    let swap: SwapResponse = response
+        .data
+        .and_then(|data| parse_execute_response_data(&data).ok())
+        .and_then(|response| response.data)
+        .map(|data| from_binary(&data))
+        .transpose()?
+        .ok_or(ContractError::InvalidSwapResponse)?;
+    let settlement = FORWARDING
+        .load(deps.storage)?
+        .settlement
+        .ok_or(ContractError::InvalidSwapResponse)?;
+    LEDGER.set_settled(
+        deps.storage,
+        &in_flight.id,
+        in_flight.payment_id,
+        &settlement.denom,
+        swap.return_amount,
+    )?;
+
+    Ok(Response::default()
+        .add_attribute("action", "settle")
+        .add_attribute("id", in_flight.id)
+        .add_attribute("paid", format!("{}{}", in_flight.amount, denom))
+        .add_attribute(
+            "settled",
+            format!("{}{}", swap.return_amount, settlement.denom),
+        ))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let version = get_contract_version(deps.storage)?;
+    if version.contract != CONTRACT_NAME {
+        return Err(ContractError::CannotMigrate {
+            previous_contract: version.contract,
+        });
+    }
+
+    let previous_version: semver::Version = version
+        .version
+        .parse()
+        .map_err(|_| ContractError::InvalidVersion(version.version.clone()))?;
+    // Payments stored full block info and denoms before v0.3.0. The version
+    // is only bumped once they are all migrated, so migrating again resumes.
+    if previous_version < semver::Version::new(0, 3, 0) {
+        let migration = LEGACY_MIGRATION
+            .may_load(deps.storage)?
+            .unwrap_or(LegacyMigration::Totals);
+        let limit = msg.limit.unwrap_or(LEGACY_MIGRATION_LIMIT).max(1) as usize;
+        if let Some(migration) = migrate_legacy(deps.storage, migration, limit)? {
+            LEGACY_MIGRATION.save(deps.storage, &migration)?;
+            return Ok(Response::default()
+                .add_attribute("method", "migrate")
+                .add_attribute("from_version", version.version)
+                .add_attribute("status", "in_progress"));
+        }
+        LEGACY_MIGRATION.remove(deps.storage);
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::default()
+        .add_attribute("method", "migrate")
+        .add_attribute("from_version", version.version)
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
+/// Migrate up to `limit` entries from the legacy layout, continuing from
+/// `migration`. Returns where to continue from, or `None` once done.
+fn migrate_legacy(
+    storage: &mut dyn Storage,
+    mut migration: LegacyMigration,
+    mut limit: usize,
+) -> StdResult<Option<LegacyMigration>> {
+    loop {
+        migration = match migration {
+            LegacyMigration::Totals => {
+                limit -= remove_legacy_totals(storage, limit)?;
+                if limit == 0 {
+                    return Ok(Some(LegacyMigration::Totals));
+                }
+                LegacyMigration::Payments { start_after: None }
+            }
+            LegacyMigration::Payments { start_after } => {
+                let payments = LEGACY_RECEIPT_PAYMENTS
+                    .range(
+                        storage,
+                        start_after.map(Bound::exclusive),
+                        None,
+                        Order::Ascending,
+                    )
+                    .take(limit)
+                    .collect::<StdResult<Vec<_>>>()?;
+                limit -= payments.len();
+                let last = payments.last().map(|(key, _)| key.clone());
+                for ((id, payment_id), payment) in payments {
+                    migrate_compact_payment(storage, id, payment_id, payment)?;
+                }
+                if limit == 0 {
+                    return Ok(Some(LegacyMigration::Payments { start_after: last }));
+                }
+                LegacyMigration::ReceiptPayers { start_after: None }
+            }
+            LegacyMigration::ReceiptPayers { start_after } => {
+                let payer_receipts = LEDGER
+                    .payer_receipts
+                    .keys(
+                        storage,
+                        start_after.map(Bound::exclusive),
+                        None,
+                        Order::Ascending,
+                    )
+                    .take(limit)
+                    .collect::<StdResult<Vec<_>>>()?;
+                limit -= payer_receipts.len();
+                let last = payer_receipts.last().cloned();
+                for (payer, id) in payer_receipts {
+                    LEDGER.receipt_payers.save(storage, id, &payer)?;
+                }
+                if limit == 0 {
+                    return Ok(Some(LegacyMigration::ReceiptPayers { start_after: last }));
+                }
+                return Ok(None);
+            }
+        }
+    }
+}
+
+/// Remove up to `limit` legacy totals, returning how many were removed.
+/// Totals keys change, so the legacy entries must be removed before the new
+/// ones are written into the same namespaces. They only ever summed the
+/// payments, which are replayed at their own heights afterwards.
+fn remove_legacy_totals(storage: &mut dyn Storage, limit: usize) -> StdResult<usize> {
+    let receipt_totals = LEGACY_RECEIPT_TOTALS
+        .keys(storage, None, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    let payer_totals = LEGACY_PAYER_TOTALS
+        .keys(storage, None, None, Order::Ascending)
+        .take(limit - receipt_totals.len())
+        .collect::<StdResult<Vec<_>>>()?;
+    let removed = receipt_totals.len() + payer_totals.len();
+    for key in receipt_totals {
+        LEGACY_RECEIPT_TOTALS.remove(storage, key);
+    }
+    for key in payer_totals {
+        LEGACY_PAYER_TOTALS.remove(storage, key);
+    }
+    Ok(removed)
+}
+
+/// Rewrite a payment from the legacy layout into the compact layout with an
+/// interned denom, and add it to the totals and their history.
+fn migrate_compact_payment(
+    storage: &mut dyn Storage,
+    id: String,
+    payment_id: u64,
+    payment: LegacyPayment,
+) -> StdResult<()> {
+    let denom_id = LEDGER.intern_denom(storage, &payment.denom)?;
+    let height = payment.block.height;
+    LEDGER.add_to_period(storage, 0, &payment.payer, denom_id, payment.amount)?;
+    // Payments to a receipt are replayed in order, but a payer's payments
+    // across receipts are not, so their history is backfilled.
+    backfill_snapshot_total(
+        storage,
+        &LEDGER.receipt_totals,
+        (id.clone(), denom_id),
+        payment.amount,
+        height,
+    )?;
+    backfill_snapshot_total(
+        storage,
+        &LEDGER.payer_totals,
+        (payment.payer.clone(), denom_id),
+        payment.amount,
+        height,
+    )?;
+    LEDGER.payments.save(
+        storage,
+        (id, payment_id),
+        &PaymentRecord {
+            payer: payment.payer,
+            height,
+            time: payment.block.time,
+            denom: denom_id,
+            amount: payment.amount,
+            period: 0,
+            items: vec![],
+            settled: None,
+            voided: None,
+        },
+    )
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::ListReceiptIds {
+            prefix,
+            start_after,
+            limit,
+        } => query_list_receipt_ids(deps, prefix, start_after, limit),
+
+        QueryMsg::ListPayments { start_after, limit } => {
+            query_list_payments(deps, start_after, limit)
+        }
+
+        QueryMsg::ListPaymentsToId {
+            id,
+            start_after,
+            limit,
+        } => query_list_payments_to_id(deps, id, start_after, limit),
+
+        QueryMsg::ListTotalsPaidToId {
+            id,
+            start_after,
+            limit,
+            height,
+        } => query_list_totals_paid_to_id(deps, id, start_after, limit, height),
+
+        QueryMsg::ListIdsForPayer {
+            payer,
+            start_after,
+            limit,
+        } => query_list_ids_for_payer(deps, payer, start_after, limit),
+
+        QueryMsg::ListTotalsPaidByPayer {
+            payer,
+            start_after,
+            limit,
+            height,
+        } => query_list_totals_paid_by_payer(deps, payer, start_after, limit, height),
+
+        QueryMsg::Output {} => to_binary(&OutputResponse {
+            output: OUTPUT.load(deps.storage)?,
+        }),
+
+        QueryMsg::IdRules {} => to_binary(&IdRulesResponse {
+            id_rules: ID_RULES.may_load(deps.storage)?,
+        }),
+
+        QueryMsg::Receipt { id } => query_receipt(deps, id),
+
+        QueryMsg::ReceiptPayer { id } => to_binary(&ReceiptPayerResponse {
+            payer: LEDGER.payer(deps.storage, &normalize_receipt_id(deps.storage, &id)?)?,
+        }),
+
+        QueryMsg::ListReceiptTransfers {
+            id,
+            start_after,
+            limit,
+        } => query_list_receipt_transfers(deps, id, start_after, limit),
+
+        QueryMsg::HasPaid {
+            id,
+            payer,
+            requirements,
+        } => query_has_paid(deps, id, payer, requirements),
+
+        QueryMsg::ListAdminActions { start_after, limit } => {
+            query_list_admin_actions(deps, start_after, limit)
+        }
+
+        QueryMsg::CanonicalId { id, payer } => query_canonical_id(deps, id, payer),
+
+        QueryMsg::Forwarding {} => to_binary(&ForwardingResponse {
+            forwarding: FORWARDING.may_load(deps.storage)?.unwrap_or_default(),
+        }),
+
+        QueryMsg::ListUnforwarded { start_after, limit } => {
+            query_list_unforwarded(deps, start_after, limit)
+        }
+
+        QueryMsg::ListOwedToOutput { start_after, limit } => {
+            query_list_owed_to_output(deps, start_after, limit)
+        }
+        QueryMsg::Sweepable { denom } => query_sweepable(deps, env, denom),
+        QueryMsg::ListSignerKeys { start_after, limit } => {
+            query_list_signer_keys(deps, start_after, limit)
+        }
+        QueryMsg::Product { sku } => to_binary(&ProductResponse {
+            product: PRODUCTS.may_load(deps.storage, &sku)?,
+        }),
+        QueryMsg::Campaign { id } => query_campaign(deps, id),
+        QueryMsg::CampaignContributions { id, payer } => {
+            query_campaign_contributions(deps, id, payer)
+        }
+        QueryMsg::ProductStock { sku } => to_binary(&ProductStockResponse {
+            stock: PRODUCT_STOCK.may_load(deps.storage, &sku)?,
+        }),
+        QueryMsg::ListProducts { start_after, limit } => {
+            query_list_products(deps, start_after, limit)
+        }
+
+        QueryMsg::GetPeriodStatement { period } => query_period_statement(deps, period),
+
+        QueryMsg::ListPeriodPayerTotals {
+            period,
+            start_after,
+            limit,
+        } => query_list_period_payer_totals(deps, period, start_after, limit),
+
+        QueryMsg::ListPeriods { start_after, limit } => {
+            query_list_periods(deps, start_after, limit)
+        }
+
+        QueryMsg::Ownership {} => to_binary(&cw_ownable::get_ownership(deps.storage)?),
+    }
+}
+
+pub fn query_receipt(deps: Deps, id: String) -> StdResult<Binary> {
+    let id = normalize_receipt_id(deps.storage, &id)?;
+    let escrow = match ESCROWS.may_load(deps.storage, &id)? {
+        Some(escrow) => Some(ReceiptEscrow {
+            escrow,
+            held: ESCROW_HELD
+                .prefix(&id)
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| {
+                    let (denom_id, amount) = item?;
+                    LEDGER.total(deps.storage, denom_id, amount)
+                })
+                .collect::<StdResult<Vec<_>>>()?,
+        }),
+        None => None,
+    };
+
+    to_binary(&ReceiptResponse {
+        payer: LEDGER.payer(deps.storage, &id)?,
+        payment_count: LEDGER.payment_count(deps.storage, &id)?,
+        creation: RECEIPT_CREATIONS.may_load(deps.storage, id.clone())?,
+        closure: RECEIPT_CLOSURES.may_load(deps.storage, id.clone())?,
+        escrow,
+        id,
+    })
+}
+
+pub fn query_list_receipt_transfers(
+    deps: Deps,
+    id: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let id = normalize_receipt_id(deps.storage, &id)?;
+
+    let transfers = cw_paginate::paginate_map_prefix(
+        RECEIPT_TRANSFERS,
+        deps.storage,
+        id,
+        start_after.map(Bound::exclusive),
+        limit,
+        |transfer_id, transfer| {
+            Ok::<ReceiptTransfer, StdError>(ReceiptTransfer {
+                transfer_id,
+                transfer,
+            })
         },
-    )?
-    .into_iter()
-    .flatten()
-    .collect();
+    )?;
+
+    to_binary(&ListReceiptTransfersResponse { transfers })
+}
+
+pub fn query_has_paid(
+    deps: Deps,
+    id: String,
+    payer: String,
+    requirements: Vec<Total>,
+) -> StdResult<Binary> {
+    let payer = deps.api.addr_validate(&payer)?;
+
+    // The ID is looked up as the payer would have paid it, and an ID the rules
+    // reject was never paid. Payments only count for the payer currently
+    // holding the receipt.
+    let held_id = match canonicalize_receipt_id(deps.storage, &id, &payer) {
+        Ok(id) => (LEDGER.payer(deps.storage, &id)?.as_ref() == Some(&payer)).then_some(id),
+        Err(_) => None,
+    };
+
+    let mut missing = vec![];
+    for requirement in requirements {
+        let paid = match &held_id {
+            Some(id) => LEDGER.receipt_total(deps.storage, id, &requirement.denom)?,
+            None => Uint128::zero(),
+        };
+        let amount = requirement.amount.saturating_sub(paid);
+        if !amount.is_zero() {
+            missing.push(Total {
+                denom: requirement.denom,
+                amount,
+            });
+        }
+    }
+
+    to_binary(&HasPaidResponse {
+        paid: held_id.is_some() && missing.is_empty(),
+        missing,
+    })
+}
+
+pub fn query_list_admin_actions(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let actions = cw_paginate::paginate_map(
+        ADMIN_LOG,
+        deps.storage,
+        start_after.map(Bound::exclusive),
+        limit,
+        |entry_id, entry| Ok::<AdminLogItem, StdError>(AdminLogItem { entry_id, entry }),
+    )?;
+
+    to_binary(&ListAdminActionsResponse { actions })
+}
+
+pub fn query_canonical_id(deps: Deps, id: String, payer: String) -> StdResult<Binary> {
+    let payer = deps.api.addr_validate(&payer)?;
+    let id = canonicalize_receipt_id(deps.storage, &id, &payer)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    to_binary(&CanonicalIdResponse { id })
+}
+
+pub fn query_list_unforwarded(
+    deps: Deps,
+    start_after: Option<CheckedDenom>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let unforwarded = cw_paginate::paginate_map(
+        UNFORWARDED,
+        deps.storage,
+        LEDGER.denom_bound(deps.storage, start_after)?,
+        limit,
+        |denom_id, amount| LEDGER.total(deps.storage, denom_id, amount),
+    )?;
+
+    to_binary(&ListUnforwardedResponse { unforwarded })
+}
+
+pub fn query_list_owed_to_output(
+    deps: Deps,
+    start_after: Option<CheckedDenom>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let owed = cw_paginate::paginate_map(
+        OWED_TO_OUTPUT,
+        deps.storage,
+        LEDGER.denom_bound(deps.storage, start_after)?,
+        limit,
+        |denom_id, amount| LEDGER.total(deps.storage, denom_id, amount),
+    )?;
+
+    to_binary(&ListOwedToOutputResponse { owed })
+}
+
+pub fn query_sweepable(deps: Deps, env: Env, denom: UncheckedDenom) -> StdResult<Binary> {
+    let denom = denom
+        .into_checked(deps)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    to_binary(&sweepable(deps, &env, &denom)?)
+}
+
+pub fn query_list_signer_keys(
+    deps: Deps,
+    start_after: Option<Binary>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let public_keys = cw_paginate::paginate_map(
+        SIGNER_KEYS,
+        deps.storage,
+        start_after
+            .as_ref()
+            .map(|key| Bound::exclusive(key.as_slice())),
+        limit,
+        |key, _| Ok::<Binary, StdError>(Binary(key)),
+    )?;
+
+    to_binary(&ListSignerKeysResponse { public_keys })
+}
+
+pub fn query_campaign(deps: Deps, id: String) -> StdResult<Binary> {
+    let id = normalize_receipt_id(deps.storage, &id)?;
+    let campaign = CAMPAIGNS.load(deps.storage, &id)?;
+    let raised = CAMPAIGN_RAISED
+        .prefix(&id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom_id, amount) = item?;
+            LEDGER.total(deps.storage, denom_id, amount)
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    let goal_met = campaign_goal_met(deps.storage, &id, &campaign)?;
+
+    to_binary(&CampaignResponse {
+        campaign,
+        raised,
+        goal_met,
+    })
+}
+
+pub fn query_campaign_contributions(deps: Deps, id: String, payer: String) -> StdResult<Binary> {
+    let id = normalize_receipt_id(deps.storage, &id)?;
+    let payer = deps.api.addr_validate(&payer)?;
+    let contributions = CAMPAIGN_CONTRIBUTIONS
+        .prefix((&id, &payer))
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom_id, amount) = item?;
+            LEDGER.total(deps.storage, denom_id, amount)
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&CampaignContributionsResponse { contributions })
+}
+
+pub fn query_list_products(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let products = cw_paginate::paginate_map(
+        PRODUCTS,
+        deps.storage,
+        start_after.as_deref().map(Bound::exclusive),
+        limit,
+        |sku, product| Ok::<ProductItem, StdError>(ProductItem { sku, product }),
+    )?;
+
+    to_binary(&ListProductsResponse { products })
+}
+
+pub fn query_period_statement(deps: Deps, period: u64) -> StdResult<Binary> {
+    to_binary(&PeriodStatementResponse {
+        period,
+        closed: LEDGER.periods.may_load(deps.storage, period)?,
+        totals: LEDGER.list_period_totals(deps.storage, period)?,
+        voided: LEDGER.list_period_voided(deps.storage, period)?,
+    })
+}
+
+pub fn query_list_period_payer_totals(
+    deps: Deps,
+    period: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let start_after = start_after
+        .map(|payer| deps.api.addr_validate(&payer))
+        .transpose()?;
+
+    let payers = LEDGER.list_period_payer_totals(deps.storage, period, start_after, limit)?;
+
+    to_binary(&ListPeriodPayerTotalsResponse { payers })
+}
+
+pub fn query_list_periods(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let periods = cw_paginate::paginate_map(
+        LEDGER.periods.clone(),
+        deps.storage,
+        start_after.map(Bound::exclusive),
+        limit,
+        |period, closed| Ok::<PeriodItem, StdError>(PeriodItem { period, closed }),
+    )?;
+
+    to_binary(&ListPeriodsResponse {
+        periods,
+        current_period: LEDGER.current_period(deps.storage)?,
+    })
+}
+
+pub fn query_list_receipt_ids(
+    deps: Deps,
+    prefix: Option<String>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let prefix = prefix
+        .map(|prefix| normalize_receipt_id(deps.storage, &prefix))
+        .transpose()?;
+    let start_after = start_after
+        .map(|id| normalize_receipt_id(deps.storage, &id))
+        .transpose()?;
+
+    let receipts = LEDGER
+        .list_receipt_ids(deps.storage, prefix.as_deref(), start_after, limit)?
+        .into_iter()
+        .map(|(id, payment_count)| ReceiptIdItem { id, payment_count })
+        .collect();
+
+    to_binary(&ListReceiptIdsResponse { receipts })
+}
+
+pub fn query_list_payments(
+    deps: Deps,
+    start_after: Option<(String, u64)>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let payments = LEDGER.list_payments(deps.storage, start_after, limit)?;
+
+    to_binary(&ListPaymentsResponse { payments })
+}
+
+pub fn query_list_payments_to_id(
+    deps: Deps,
+    id: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let id = normalize_receipt_id(deps.storage, &id)?;
+
+    let payments = LEDGER.list_receipt_payments(deps.storage, &id, start_after, limit)?;
+
+    to_binary(&ListPaymentsToIdResponse { payments })
+}
+
+pub fn query_list_totals_paid_to_id(
+    deps: Deps,
+    id: String,
+    start_after: Option<CheckedDenom>,
+    limit: Option<u32>,
+    height: Option<u64>,
+) -> StdResult<Binary> {
+    let id = normalize_receipt_id(deps.storage, &id)?;
+
+    let totals = LEDGER.list_receipt_totals(deps.storage, &id, start_after, limit, height)?;
+
+    to_binary(&ListTotalsPaidToIdResponse { totals })
+}
+
+pub fn query_list_ids_for_payer(
+    deps: Deps,
+    payer: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let payer = deps.api.addr_validate(&payer)?;
+
+    let ids = LEDGER.list_payer_receipts(deps.storage, &payer, start_after, limit)?;
+
+    to_binary(&ListIdsForPayerResponse { ids })
+}
+
+pub fn query_list_totals_paid_by_payer(
+    deps: Deps,
+    payer: String,
+    start_after: Option<CheckedDenom>,
+    limit: Option<u32>,
+    height: Option<u64>,
+) -> StdResult<Binary> {
+    let payer = deps.api.addr_validate(&payer)?;
+
+    let totals = LEDGER.list_payer_totals(deps.storage, &payer, start_after, limit, height)?;
 
     to_binary(&ListTotalsPaidByPayerResponse { totals })
 }
@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
 use cw_denom::DenomError;
 use cw_ownable::OwnershipError;
 use cw_utils::PaymentError;
@@ -18,12 +18,156 @@ pub enum ContractError {
     #[error(transparent)]
     Denom(#[from] DenomError),
 
+    #[error("Cannot migrate from a different contract: {previous_contract}")]
+    CannotMigrate { previous_contract: String },
+
+    #[error("Invalid contract version: {0}")]
+    InvalidVersion(String),
+
+    #[error("Migration in progress, migrate again to continue")]
+    MigrationInProgress,
+
     #[error("Invalid denom")]
     InvalidDenom,
 
+    #[error("Invalid receipt ID: {reason}")]
+    InvalidReceiptId { reason: String },
+
+    #[error("Invalid receipt ID rules: {reason}")]
+    InvalidIdRules { reason: String },
+
+    #[error("Receipt already paid")]
+    ReceiptAlreadyPaid,
+
+    #[error("Receipt {id} is closed")]
+    ReceiptClosed { id: String },
+
+    #[error("Receipt {id} is not closed")]
+    ReceiptNotClosed { id: String },
+
+    #[error("Payment {payment_index} not found for receipt {id}")]
+    PaymentNotFound { id: String, payment_index: u64 },
+
+    #[error("Payment {payment_index} for receipt {id} is already voided")]
+    PaymentAlreadyVoided { id: String, payment_index: u64 },
+
     #[error("Missing payment")]
     MissingPayment,
 
+    #[error("Unauthorized")]
+    Unauthorized,
+
+    #[error("Receipt {id} has no payer")]
+    NoReceiptPayer { id: String },
+
     #[error("Unauthorized payer")]
     UnauthorizedPayer,
+
+    #[error("Unknown reply ID {id}")]
+    UnknownReplyId { id: u64 },
+
+    #[error("Only {sweepable} of {denom} can be swept")]
+    InsufficientSweepable { denom: String, sweepable: Uint128 },
+
+    #[error("Signer keys must be 33 or 65 byte secp256k1 public keys")]
+    InvalidSignerKey,
+
+    #[error("Public key is not a signer key")]
+    UnknownSignerKey,
+
+    #[error("Invalid payment request signature")]
+    InvalidSignature,
+
+    #[error("Payment request expired")]
+    PaymentRequestExpired,
+
+    #[error("Payment request nonce {nonce} already used")]
+    NonceUsed { nonce: u64 },
+
+    #[error("Funds do not match the payment request amounts")]
+    PaymentRequestMismatch,
+
+    #[error("Idempotency key must be between 1 and {max_length} characters")]
+    InvalidIdempotencyKey { max_length: usize },
+
+    #[error("Payment with idempotency key {key} already made")]
+    DuplicatePayment { key: String },
+
+    #[error("Invalid product: {reason}")]
+    InvalidProduct { reason: String },
+
+    #[error("Invalid items: {reason}")]
+    InvalidItems { reason: String },
+
+    #[error("Product {sku} not found")]
+    ProductNotFound { sku: String },
+
+    #[error("Product {sku} is not active")]
+    ProductInactive { sku: String },
+
+    #[error("Product {sku} has no price in {denom}")]
+    ProductNotPriced { sku: String, denom: String },
+
+    #[error("Product {sku} is sold out ({remaining} left)")]
+    SoldOut { sku: String, remaining: u32 },
+
+    #[error("Items cost {expected} {denom}")]
+    ItemsCostMismatch { expected: Uint128, denom: String },
+
+    #[error("Invalid settlement: {reason}")]
+    InvalidSettlement { reason: String },
+
+    #[error("No reference price to settle {denom} at")]
+    NoReferencePrice { denom: String },
+
+    #[error("Settlement router did not respond with swap data")]
+    InvalidSwapResponse,
+
+    #[error("Invalid campaign: {reason}")]
+    InvalidCampaign { reason: String },
+
+    #[error("Campaign {id} not found")]
+    CampaignNotFound { id: String },
+
+    #[error("Campaign {id} has ended")]
+    CampaignEnded { id: String },
+
+    #[error("Campaign {id} is still running")]
+    CampaignRunning { id: String },
+
+    #[error("Campaign {id} does not accept {denom}")]
+    CampaignDenomNotAccepted { id: String, denom: String },
+
+    #[error("Campaign {id} has not met its goal")]
+    CampaignGoalNotMet { id: String },
+
+    #[error("Campaign {id} met its goal")]
+    CampaignGoalMet { id: String },
+
+    #[error("Nothing to refund")]
+    NothingToRefund,
+
+    #[error("Invalid dispute: {reason}")]
+    InvalidDispute { reason: String },
+
+    #[error("Receipt {id} has no arbiter")]
+    EscrowNotFound { id: String },
+
+    #[error("Escrow for receipt {id} has been released")]
+    EscrowReleased { id: String },
+
+    #[error("Receipt {id} has an open dispute")]
+    DisputeOpen { id: String },
+
+    #[error("Receipt {id} has no open dispute")]
+    NoOpenDispute { id: String },
+
+    #[error("Dispute window for receipt {id} has ended")]
+    DisputeWindowEnded { id: String },
+
+    #[error("Dispute window for receipt {id} has not ended")]
+    DisputeWindowOpen { id: String },
+
+    #[error("Release basis points must be at most 10000, got {release_bps}")]
+    InvalidReleaseBps { release_bps: u16 },
 }
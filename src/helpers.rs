@@ -0,0 +1,34 @@
+use cosmwasm_std::{to_binary, Addr, QuerierWrapper, QueryRequest, StdResult, WasmQuery};
+
+use crate::msg::{HasPaidResponse, QueryMsg, Total};
+
+/// CwReceiptContract is a wrapper around Addr that provides helpers for other
+/// contracts querying cw-receipt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CwReceiptContract(pub Addr);
+
+impl CwReceiptContract {
+    pub fn addr(&self) -> Addr {
+        self.0.clone()
+    }
+
+    /// Returns whether payer holds receipt ID and has paid at least the given
+    /// amounts to it, along with any missing amounts. Pass the ID as the payer
+    /// paid it, even when IDs are bound to payers.
+    pub fn has_paid(
+        &self,
+        querier: &QuerierWrapper,
+        id: impl Into<String>,
+        payer: impl Into<String>,
+        requirements: Vec<Total>,
+    ) -> StdResult<HasPaidResponse> {
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: self.addr().into(),
+            msg: to_binary(&QueryMsg::HasPaid {
+                id: id.into(),
+                payer: payer.into(),
+                requirements,
+            })?,
+        }))
+    }
+}
@@ -0,0 +1,893 @@
+use cosmwasm_std::{
+    Addr, BlockInfo, CosmosMsg, Empty, Order, StdError, StdResult, Storage, Uint128,
+};
+use cw_denom::CheckedDenom;
+use cw_storage_plus::{
+    Bound, Item, KeyDeserialize, Map, Prefixer, PrimaryKey, SnapshotMap, Strategy,
+};
+
+use crate::error::ContractError;
+use crate::msg::{PayerTotals, Payment, ReceiptPayment, ReceiptPaymentWithoutId, Total};
+use crate::state::{LineItem, PaymentRecord, Period, Settled, Void};
+
+/// Storage namespaces used by a `ReceiptLedger`. Every namespace must be
+/// unique within the contract embedding the ledger.
+pub struct LedgerNamespaces<'a> {
+    pub denoms: &'a str,
+    pub denom_ids: &'a str,
+    pub denom_count: &'a str,
+    pub payments: &'a str,
+    pub payment_counts: &'a str,
+    pub receipt_totals: &'a str,
+    pub receipt_totals_checkpoints: &'a str,
+    pub receipt_totals_changelog: &'a str,
+    pub receipt_payers: &'a str,
+    pub payer_receipts: &'a str,
+    pub payer_totals: &'a str,
+    pub payer_totals_checkpoints: &'a str,
+    pub payer_totals_changelog: &'a str,
+    pub current_period: &'a str,
+    pub periods: &'a str,
+    pub period_totals: &'a str,
+    pub period_payer_totals: &'a str,
+    pub period_voided: &'a str,
+}
+
+/// Records payments to receipt IDs and tracks totals per receipt and per payer.
+/// A receipt ID can have multiple payments. Only one payer can pay for a given
+/// receipt.
+///
+/// The ledger only does bookkeeping. Moving funds is left to the contract
+/// embedding it.
+pub struct ReceiptLedger<'a> {
+    /// Map interned denom ID to denom. Denoms are interned so that payments
+    /// and totals only store a small integer instead of the full denom.
+    pub denoms: Map<'a, u32, CheckedDenom>,
+    /// Map serialized denom to interned denom ID.
+    pub denom_ids: Map<'a, String, u32>,
+    /// Number of denoms interned so far, which is also the next denom ID.
+    pub denom_count: Item<'a, u32>,
+    /// Map receipt ID and incrementing payment ID (starting from 0 for a given
+    /// receipt ID) to the payment.
+    pub payments: Map<'a, (String, u64), PaymentRecord>,
+    /// Map receipt ID to the number of payments for that receipt so far.
+    pub payment_counts: Map<'a, String, u64>,
+    /// Map receipt ID and interned denom ID to total payment amount, with the
+    /// history of every change so totals can be loaded at past heights.
+    /// Totals are kept at zero instead of removed so historical listings still
+    /// find them.
+    pub receipt_totals: SnapshotMap<'a, (String, u32), Uint128>,
+    /// Map receipt ID to its authorized payer, set by the first payment or
+    /// assigned ahead of it.
+    pub receipt_payers: Map<'a, String, Addr>,
+    /// Map authorized payer and receipt ID to an empty value, making it easy
+    /// to list receipts for a given payer.
+    pub payer_receipts: Map<'a, (Addr, String), Empty>,
+    /// Map payer and interned denom ID to total payment amount, with history
+    /// like `receipt_totals`.
+    pub payer_totals: SnapshotMap<'a, (Addr, u32), Uint128>,
+    /// The open accounting period, which new payments fall into. Periods are
+    /// numbered from 0.
+    pub current_period: Item<'a, u64>,
+    /// Map closed period to when and by whom it was closed.
+    pub periods: Map<'a, u64, Period>,
+    /// Map period and interned denom ID to the total paid during the period.
+    pub period_totals: Map<'a, (u64, u32), Uint128>,
+    /// Map period, payer and interned denom ID to the total paid by the payer
+    /// during the period.
+    pub period_payer_totals: Map<'a, (u64, Addr, u32), Uint128>,
+    /// Map period and interned denom ID to the total voided during the period.
+    /// Voids never change the totals of the period the payment fell into, so
+    /// closed periods stay stable.
+    pub period_voided: Map<'a, (u64, u32), Uint128>,
+}
+
+impl<'a> ReceiptLedger<'a> {
+    pub const fn new(namespaces: LedgerNamespaces<'a>) -> Self {
+        Self {
+            denoms: Map::new(namespaces.denoms),
+            denom_ids: Map::new(namespaces.denom_ids),
+            denom_count: Item::new(namespaces.denom_count),
+            payments: Map::new(namespaces.payments),
+            payment_counts: Map::new(namespaces.payment_counts),
+            receipt_totals: SnapshotMap::new(
+                namespaces.receipt_totals,
+                namespaces.receipt_totals_checkpoints,
+                namespaces.receipt_totals_changelog,
+                Strategy::EveryBlock,
+            ),
+            receipt_payers: Map::new(namespaces.receipt_payers),
+            payer_receipts: Map::new(namespaces.payer_receipts),
+            payer_totals: SnapshotMap::new(
+                namespaces.payer_totals,
+                namespaces.payer_totals_checkpoints,
+                namespaces.payer_totals_changelog,
+                Strategy::EveryBlock,
+            ),
+            current_period: Item::new(namespaces.current_period),
+            periods: Map::new(namespaces.periods),
+            period_totals: Map::new(namespaces.period_totals),
+            period_payer_totals: Map::new(namespaces.period_payer_totals),
+            period_voided: Map::new(namespaces.period_voided),
+        }
+    }
+
+    /// Returns the interned ID for a denom, assigning the next ID if the denom
+    /// has not been seen before.
+    pub fn intern_denom(&self, storage: &mut dyn Storage, denom: &CheckedDenom) -> StdResult<u32> {
+        let key = denom_to_string(denom);
+        if let Some(denom_id) = self.denom_ids.may_load(storage, key.clone())? {
+            return Ok(denom_id);
+        }
+
+        let denom_id = self.denom_count.may_load(storage)?.unwrap_or_default();
+        self.denoms.save(storage, denom_id, denom)?;
+        self.denom_ids.save(storage, key, &denom_id)?;
+        self.denom_count.save(storage, &(denom_id + 1))?;
+
+        Ok(denom_id)
+    }
+
+    /// Returns the interned ID for a denom, if it has been seen.
+    pub fn denom_id(&self, storage: &dyn Storage, denom: &CheckedDenom) -> StdResult<Option<u32>> {
+        self.denom_ids.may_load(storage, denom_to_string(denom))
+    }
+
+    /// Returns the payer authorized to pay a receipt, if any.
+    pub fn payer(&self, storage: &dyn Storage, id: &str) -> StdResult<Option<Addr>> {
+        self.receipt_payers.may_load(storage, id.to_string())
+    }
+
+    /// Set or clear the payer authorized to pay a receipt, returning the
+    /// previous payer.
+    pub fn set_payer(
+        &self,
+        storage: &mut dyn Storage,
+        id: &str,
+        payer: Option<&Addr>,
+    ) -> StdResult<Option<Addr>> {
+        let previous = self.payer(storage, id)?;
+        if let Some(previous) = &previous {
+            self.payer_receipts
+                .remove(storage, (previous.clone(), id.to_string()));
+        }
+        match payer {
+            Some(payer) => {
+                self.receipt_payers.save(storage, id.to_string(), payer)?;
+                self.payer_receipts
+                    .save(storage, (payer.clone(), id.to_string()), &Empty {})?;
+            }
+            None => self.receipt_payers.remove(storage, id.to_string()),
+        }
+        Ok(previous)
+    }
+
+    /// Returns the number of payments recorded for a receipt.
+    pub fn payment_count(&self, storage: &dyn Storage, id: &str) -> StdResult<u64> {
+        Ok(self
+            .payment_counts
+            .may_load(storage, id.to_string())?
+            .unwrap_or_default())
+    }
+
+    /// Record a payment and add it to the totals, returning its payment ID.
+    /// The first payment to a receipt without a payer makes the payer the only
+    /// one authorized to pay it.
+    pub fn record(
+        &self,
+        storage: &mut dyn Storage,
+        block: &BlockInfo,
+        id: &str,
+        payer: &Addr,
+        denom: &CheckedDenom,
+        amount: Uint128,
+    ) -> Result<u64, ContractError> {
+        // If a payer is set, verify payer is authorized for this receipt. If no
+        // payer, set payer.
+        match self.payer(storage, id)? {
+            Some(authorized_payer) => {
+                if authorized_payer != *payer {
+                    return Err(ContractError::UnauthorizedPayer);
+                }
+            }
+            None => {
+                self.set_payer(storage, id, Some(payer))?;
+            }
+        }
+
+        self.record_from_any_payer(storage, block, id, payer, denom, amount)
+    }
+
+    /// Record a payment and add it to the totals, returning its payment ID,
+    /// without checking or setting the receipt's payer. For receipts anyone can
+    /// pay, such as campaigns.
+    pub fn record_from_any_payer(
+        &self,
+        storage: &mut dyn Storage,
+        block: &BlockInfo,
+        id: &str,
+        payer: &Addr,
+        denom: &CheckedDenom,
+        amount: Uint128,
+    ) -> Result<u64, ContractError> {
+        let denom_id = self.intern_denom(storage, denom)?;
+        let payment_id = self.payment_count(storage, id)?;
+        let period = self.current_period(storage)?;
+
+        // Record payment.
+        self.payments.save(
+            storage,
+            (id.to_string(), payment_id),
+            &PaymentRecord {
+                payer: payer.clone(),
+                height: block.height,
+                time: block.time,
+                denom: denom_id,
+                amount,
+                period,
+                items: vec![],
+                settled: None,
+                voided: None,
+            },
+        )?;
+        // Increment payment count.
+        self.payment_counts
+            .save(storage, id.to_string(), &(payment_id + 1))?;
+        // Increase totals.
+        add_snapshot_total(
+            storage,
+            &self.receipt_totals,
+            (id.to_string(), denom_id),
+            amount,
+            block.height,
+        )?;
+        add_snapshot_total(
+            storage,
+            &self.payer_totals,
+            (payer.clone(), denom_id),
+            amount,
+            block.height,
+        )?;
+        self.add_to_period(storage, period, payer, denom_id, amount)?;
+
+        Ok(payment_id)
+    }
+
+    /// Mark a payment as voided and remove its amount from the totals,
+    /// returning the voided payment.
+    pub fn void(
+        &self,
+        storage: &mut dyn Storage,
+        id: &str,
+        payment_id: u64,
+        void: Void,
+    ) -> Result<PaymentRecord, ContractError> {
+        let mut payment = self
+            .payments
+            .may_load(storage, (id.to_string(), payment_id))?
+            .ok_or_else(|| ContractError::PaymentNotFound {
+                id: id.to_string(),
+                payment_index: payment_id,
+            })?;
+        if payment.voided.is_some() {
+            return Err(ContractError::PaymentAlreadyVoided {
+                id: id.to_string(),
+                payment_index: payment_id,
+            });
+        }
+
+        let height = void.height;
+        payment.voided = Some(void);
+        self.payments
+            .save(storage, (id.to_string(), payment_id), &payment)?;
+
+        // Decrease totals.
+        subtract_snapshot_total(
+            storage,
+            &self.receipt_totals,
+            (id.to_string(), payment.denom),
+            payment.amount,
+            height,
+        )?;
+        subtract_snapshot_total(
+            storage,
+            &self.payer_totals,
+            (payment.payer.clone(), payment.denom),
+            payment.amount,
+            height,
+        )?;
+        let period = self.current_period(storage)?;
+        add_total(
+            storage,
+            &self.period_voided,
+            (period, payment.denom),
+            payment.amount,
+        )?;
+
+        Ok(payment)
+    }
+
+    /// Remove part of what was paid to a receipt in a denom from the totals,
+    /// such as a share refunded after a dispute, and count it as voided in the
+    /// current period. It is taken from the payers of the latest payments that
+    /// are not voided, so at most what those payments still count is removed.
+    /// Returns the amount removed.
+    pub fn void_amount(
+        &self,
+        storage: &mut dyn Storage,
+        id: &str,
+        denom_id: u32,
+        amount: Uint128,
+        height: u64,
+    ) -> StdResult<Uint128> {
+        let payments = self
+            .payments
+            .prefix(id.to_string())
+            .range(storage, None, None, Order::Descending)
+            .collect::<StdResult<Vec<_>>>()?;
+        let mut remaining = amount;
+        for (_, payment) in payments {
+            if remaining.is_zero() {
+                break;
+            }
+            if payment.denom != denom_id || payment.voided.is_some() {
+                continue;
+            }
+            let voided = remaining.min(payment.amount);
+            subtract_snapshot_total(
+                storage,
+                &self.payer_totals,
+                (payment.payer, denom_id),
+                voided,
+                height,
+            )?;
+            remaining -= voided;
+        }
+
+        let voided = amount - remaining;
+        if voided.is_zero() {
+            return Ok(voided);
+        }
+        subtract_snapshot_total(
+            storage,
+            &self.receipt_totals,
+            (id.to_string(), denom_id),
+            voided,
+            height,
+        )?;
+        let period = self.current_period(storage)?;
+        add_total(storage, &self.period_voided, (period, denom_id), voided)?;
+        Ok(voided)
+    }
+
+    /// Void a payment and return the message sending its amount back to the
+    /// payer. The embedding contract must hold the funds.
+    pub fn refund(
+        &self,
+        storage: &mut dyn Storage,
+        id: &str,
+        payment_id: u64,
+        void: Void,
+    ) -> Result<CosmosMsg, ContractError> {
+        let payment = self.void(storage, id, payment_id, void)?;
+        let denom = self.denoms.load(storage, payment.denom)?;
+        Ok(denom.get_transfer_to_message(&payment.payer, payment.amount)?)
+    }
+
+    /// Returns the total paid to a receipt in a denom.
+    pub fn receipt_total(
+        &self,
+        storage: &dyn Storage,
+        id: &str,
+        denom: &CheckedDenom,
+    ) -> StdResult<Uint128> {
+        Ok(match self.denom_id(storage, denom)? {
+            Some(denom_id) => self
+                .receipt_totals
+                .may_load(storage, (id.to_string(), denom_id))?
+                .unwrap_or_default(),
+            None => Uint128::zero(),
+        })
+    }
+
+    /// Returns totals paid per-denom to a receipt, in the order the denoms
+    /// were first paid to the ledger. If height is set, returns the totals as
+    /// they were at the start of that block.
+    pub fn list_receipt_totals(
+        &self,
+        storage: &dyn Storage,
+        id: &str,
+        start_after: Option<CheckedDenom>,
+        limit: Option<u32>,
+        height: Option<u64>,
+    ) -> StdResult<Vec<Total>> {
+        self.list_snapshot_totals(
+            storage,
+            &self.receipt_totals,
+            id.to_string(),
+            start_after,
+            limit,
+            height,
+        )
+    }
+
+    /// Returns totals paid per-denom by a payer across all receipts. If height
+    /// is set, returns the totals as they were at the start of that block.
+    pub fn list_payer_totals(
+        &self,
+        storage: &dyn Storage,
+        payer: &Addr,
+        start_after: Option<CheckedDenom>,
+        limit: Option<u32>,
+        height: Option<u64>,
+    ) -> StdResult<Vec<Total>> {
+        self.list_snapshot_totals(
+            storage,
+            &self.payer_totals,
+            payer.clone(),
+            start_after,
+            limit,
+            height,
+        )
+    }
+
+    fn list_snapshot_totals<K>(
+        &self,
+        storage: &dyn Storage,
+        totals: &SnapshotMap<'a, (K, u32), Uint128>,
+        prefix: K,
+        start_after: Option<CheckedDenom>,
+        limit: Option<u32>,
+        height: Option<u64>,
+    ) -> StdResult<Vec<Total>>
+    where
+        K: PrimaryKey<'a> + Prefixer<'a> + KeyDeserialize + Clone,
+    {
+        let limit = limit
+            .unwrap_or(cw_paginate::DEFAULT_LIMIT)
+            .min(cw_paginate::MAX_LIMIT) as usize;
+        let start = self.denom_bound(storage, start_after)?;
+
+        let mut result = vec![];
+        for item in totals
+            .prefix(prefix.clone())
+            .range(storage, start, None, Order::Ascending)
+        {
+            if result.len() >= limit {
+                break;
+            }
+            let (denom_id, current) = item?;
+            let amount = match height {
+                Some(height) => totals
+                    .may_load_at_height(storage, (prefix.clone(), denom_id), height)?
+                    .unwrap_or_default(),
+                None => current,
+            };
+            if !amount.is_zero() {
+                result.push(self.total(storage, denom_id, amount)?);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Returns receipt IDs that have payments, with their payment counts,
+    /// optionally only those starting with prefix.
+    pub fn list_receipt_ids(
+        &self,
+        storage: &dyn Storage,
+        prefix: Option<&str>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<(String, u64)>> {
+        let prefix = prefix.unwrap_or_default();
+        // Start at the prefix itself, or after start_after if it is further
+        // along.
+        let min = match start_after {
+            Some(start_after) if start_after.as_str() >= prefix => {
+                Some(Bound::exclusive(start_after))
+            }
+            _ if prefix.is_empty() => None,
+            _ => Some(Bound::inclusive(prefix.to_string())),
+        };
+        let max = prefix_upper_bound(prefix.as_bytes()).map(Bound::ExclusiveRaw);
+
+        cw_paginate::collect(
+            Box::new(
+                self.payment_counts
+                    .range(storage, min, max, Order::Ascending),
+            ),
+            limit,
+            |id, count| Ok::<(String, u64), StdError>((id, count)),
+        )
+    }
+
+    /// Returns payments for all receipts and payers.
+    pub fn list_payments(
+        &self,
+        storage: &dyn Storage,
+        start_after: Option<(String, u64)>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<ReceiptPayment>> {
+        cw_paginate::paginate_map(
+            self.payments.clone(),
+            storage,
+            start_after.map(Bound::exclusive),
+            limit,
+            |(receipt_id, receipt_payment_id), payment| {
+                Ok::<ReceiptPayment, StdError>(ReceiptPayment {
+                    receipt_id,
+                    receipt_payment_id,
+                    payment: self.expand_payment(storage, payment)?,
+                })
+            },
+        )
+    }
+
+    /// Returns payments for a receipt.
+    pub fn list_receipt_payments(
+        &self,
+        storage: &dyn Storage,
+        id: &str,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<ReceiptPaymentWithoutId>> {
+        cw_paginate::paginate_map_prefix(
+            self.payments.clone(),
+            storage,
+            id.to_string(),
+            start_after.map(Bound::exclusive),
+            limit,
+            |receipt_payment_id, payment| {
+                Ok::<ReceiptPaymentWithoutId, StdError>(ReceiptPaymentWithoutId {
+                    receipt_payment_id,
+                    payment: self.expand_payment(storage, payment)?,
+                })
+            },
+        )
+    }
+
+    /// Returns receipt IDs a payer is authorized to pay.
+    pub fn list_payer_receipts(
+        &self,
+        storage: &dyn Storage,
+        payer: &Addr,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<String>> {
+        cw_paginate::paginate_map_prefix(
+            self.payer_receipts.clone(),
+            storage,
+            payer.clone(),
+            start_after.map(Bound::exclusive),
+            limit,
+            |id, _| Ok::<String, StdError>(id),
+        )
+    }
+
+    /// Returns a stored payment with its denom expanded.
+    pub fn expand_payment(
+        &self,
+        storage: &dyn Storage,
+        payment: PaymentRecord,
+    ) -> StdResult<Payment> {
+        Ok(Payment {
+            payer: payment.payer,
+            block_height: payment.height,
+            block_time: payment.time,
+            denom: self.denoms.load(storage, payment.denom)?,
+            amount: payment.amount,
+            period: payment.period,
+            items: payment.items,
+            settled: payment
+                .settled
+                .map(|settled| self.total(storage, settled.denom, settled.amount))
+                .transpose()?,
+            voided: payment.voided,
+        })
+    }
+
+    /// Attach catalog line items to a recorded payment.
+    pub fn set_line_items(
+        &self,
+        storage: &mut dyn Storage,
+        id: &str,
+        payment_id: u64,
+        items: Vec<LineItem>,
+    ) -> StdResult<()> {
+        let key = (id.to_string(), payment_id);
+        let mut payment = self.payments.load(storage, key.clone())?;
+        payment.items = items;
+        self.payments.save(storage, key, &payment)
+    }
+
+    /// Record what the output received for a payment once it was swapped into
+    /// the settlement denom.
+    pub fn set_settled(
+        &self,
+        storage: &mut dyn Storage,
+        id: &str,
+        payment_id: u64,
+        denom: &CheckedDenom,
+        amount: Uint128,
+    ) -> StdResult<()> {
+        let denom = self.intern_denom(storage, denom)?;
+        let key = (id.to_string(), payment_id);
+        let mut payment = self.payments.load(storage, key.clone())?;
+        payment.settled = Some(Settled { denom, amount });
+        self.payments.save(storage, key, &payment)
+    }
+
+    /// Returns the open accounting period.
+    pub fn current_period(&self, storage: &dyn Storage) -> StdResult<u64> {
+        Ok(self.current_period.may_load(storage)?.unwrap_or_default())
+    }
+
+    /// Close the open accounting period, returning it. Later payments fall
+    /// into the next period.
+    pub fn close_period(
+        &self,
+        storage: &mut dyn Storage,
+        block: &BlockInfo,
+        closed_by: &Addr,
+    ) -> StdResult<u64> {
+        let period = self.current_period(storage)?;
+        self.periods.save(
+            storage,
+            period,
+            &Period {
+                closed_by: closed_by.clone(),
+                height: block.height,
+                time: block.time,
+            },
+        )?;
+        self.current_period.save(storage, &(period + 1))?;
+        Ok(period)
+    }
+
+    /// Add a payment to the totals of a period.
+    pub fn add_to_period(
+        &self,
+        storage: &mut dyn Storage,
+        period: u64,
+        payer: &Addr,
+        denom_id: u32,
+        amount: Uint128,
+    ) -> StdResult<()> {
+        add_total(storage, &self.period_totals, (period, denom_id), amount)?;
+        add_total(
+            storage,
+            &self.period_payer_totals,
+            (period, payer.clone(), denom_id),
+            amount,
+        )
+    }
+
+    /// Returns totals paid during a period, per denom.
+    pub fn list_period_totals(&self, storage: &dyn Storage, period: u64) -> StdResult<Vec<Total>> {
+        self.period_totals
+            .prefix(period)
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| {
+                let (denom_id, amount) = item?;
+                self.total(storage, denom_id, amount)
+            })
+            .collect()
+    }
+
+    /// Returns totals voided during a period, per denom.
+    pub fn list_period_voided(&self, storage: &dyn Storage, period: u64) -> StdResult<Vec<Total>> {
+        self.period_voided
+            .prefix(period)
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| {
+                let (denom_id, amount) = item?;
+                self.total(storage, denom_id, amount)
+            })
+            .collect()
+    }
+
+    /// Returns totals paid by each payer during a period, in storage order.
+    pub fn list_period_payer_totals(
+        &self,
+        storage: &dyn Storage,
+        period: u64,
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<PayerTotals>> {
+        let limit = limit
+            .unwrap_or(cw_paginate::DEFAULT_LIMIT)
+            .min(cw_paginate::MAX_LIMIT) as usize;
+        let start = start_after.map(|payer| Bound::exclusive((payer, u32::MAX)));
+        let mut payers: Vec<PayerTotals> = vec![];
+        for item in self.period_payer_totals.sub_prefix(period).range(
+            storage,
+            start,
+            None,
+            Order::Ascending,
+        ) {
+            let ((payer, denom_id), amount) = item?;
+            let total = self.total(storage, denom_id, amount)?;
+            match payers.last_mut() {
+                Some(last) if last.payer == payer => last.totals.push(total),
+                _ => {
+                    if payers.len() >= limit {
+                        break;
+                    }
+                    payers.push(PayerTotals {
+                        payer,
+                        totals: vec![total],
+                    });
+                }
+            }
+        }
+        Ok(payers)
+    }
+
+    /// Returns a total for an interned denom ID.
+    pub fn total(&self, storage: &dyn Storage, denom_id: u32, amount: Uint128) -> StdResult<Total> {
+        Ok(Total {
+            denom: self.denoms.load(storage, denom_id)?,
+            amount,
+        })
+    }
+
+    /// Returns the pagination bound for maps keyed by interned denom ID. A
+    /// denom never paid to the ledger has no position, so listing starts from
+    /// the beginning.
+    pub fn denom_bound(
+        &self,
+        storage: &dyn Storage,
+        start_after: Option<CheckedDenom>,
+    ) -> StdResult<Option<Bound<'a, u32>>> {
+        Ok(match start_after {
+            Some(denom) => self.denom_id(storage, &denom)?.map(Bound::exclusive),
+            None => None,
+        })
+    }
+}
+
+/// Returns the smallest key greater than every key starting with prefix, or
+/// None if there is no such key.
+fn prefix_upper_bound(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut upper = prefix.to_vec();
+    while let Some(last) = upper.pop() {
+        if last < u8::MAX {
+            upper.push(last + 1);
+            return Some(upper);
+        }
+    }
+    None
+}
+
+/// Increase a total.
+pub fn add_total<'a, K>(
+    storage: &mut dyn Storage,
+    totals: &Map<'a, K, Uint128>,
+    key: K,
+    amount: Uint128,
+) -> StdResult<()>
+where
+    K: PrimaryKey<'a> + Clone,
+{
+    totals.update(storage, key, |total| {
+        Ok::<Uint128, StdError>(total.unwrap_or_default().checked_add(amount)?)
+    })?;
+    Ok(())
+}
+
+/// Decrease a total, removing it once it reaches zero.
+pub fn subtract_total<'a, K>(
+    storage: &mut dyn Storage,
+    totals: &Map<'a, K, Uint128>,
+    key: K,
+    amount: Uint128,
+) -> StdResult<()>
+where
+    K: PrimaryKey<'a> + Clone,
+{
+    let total = totals.may_load(storage, key.clone())?.unwrap_or_default();
+    let remaining = total.checked_sub(amount)?;
+    if remaining.is_zero() {
+        totals.remove(storage, key);
+    } else {
+        totals.save(storage, key, &remaining)?;
+    }
+    Ok(())
+}
+
+/// Increase a snapshotted total.
+pub fn add_snapshot_total<'a, K>(
+    storage: &mut dyn Storage,
+    totals: &SnapshotMap<'a, K, Uint128>,
+    key: K,
+    amount: Uint128,
+    height: u64,
+) -> StdResult<()>
+where
+    K: PrimaryKey<'a> + Prefixer<'a> + KeyDeserialize,
+{
+    totals.update(storage, key, height, |total| {
+        Ok::<Uint128, StdError>(total.unwrap_or_default().checked_add(amount)?)
+    })?;
+    Ok(())
+}
+
+/// Increase a snapshotted total by an amount paid at a past height, also
+/// increasing every value recorded in its history after that height. For
+/// rebuilding history from payments recorded out of height order.
+pub fn backfill_snapshot_total<'a, K>(
+    storage: &mut dyn Storage,
+    totals: &SnapshotMap<'a, K, Uint128>,
+    key: K,
+    amount: Uint128,
+    height: u64,
+) -> StdResult<()>
+where
+    K: PrimaryKey<'a> + Prefixer<'a> + KeyDeserialize + Clone,
+{
+    let changelog = totals.changelog();
+    let later = changelog
+        .prefix(key.clone())
+        .range(
+            storage,
+            Some(Bound::exclusive(height)),
+            None,
+            Order::Ascending,
+        )
+        .collect::<StdResult<Vec<_>>>()?;
+    let had_change = changelog.has(storage, (key.clone(), height));
+
+    add_snapshot_total(storage, totals, key.clone(), amount, height)?;
+
+    // The change just written holds the current total, but the total before
+    // height is what the next later change held.
+    if let (false, Some((_, next))) = (had_change, later.first()) {
+        let mut change = changelog.load(storage, (key.clone(), height))?;
+        change.old = next.old;
+        changelog.save(storage, (key.clone(), height), &change)?;
+    }
+    for (later_height, mut change) in later {
+        change.old = Some(change.old.unwrap_or_default().checked_add(amount)?);
+        changelog.save(storage, (key.clone(), later_height), &change)?;
+    }
+    Ok(())
+}
+
+/// Decrease a snapshotted total. Unlike `subtract_total`, the total is kept
+/// at zero so it stays listed in its history.
+pub fn subtract_snapshot_total<'a, K>(
+    storage: &mut dyn Storage,
+    totals: &SnapshotMap<'a, K, Uint128>,
+    key: K,
+    amount: Uint128,
+    height: u64,
+) -> StdResult<()>
+where
+    K: PrimaryKey<'a> + Prefixer<'a> + KeyDeserialize,
+{
+    totals.update(storage, key, height, |total| {
+        Ok::<Uint128, StdError>(total.unwrap_or_default().checked_sub(amount)?)
+    })?;
+    Ok(())
+}
+
+/// Serialize a denom with an `n` (native) or `c` (cw20) prefix.
+pub fn denom_to_string(denom: &CheckedDenom) -> String {
+    match denom {
+        CheckedDenom::Native(denom) => format!("n{}", denom),
+        CheckedDenom::Cw20(denom) => format!("c{}", denom),
+    }
+}
+
+/// Parse a denom serialized by `denom_to_string`.
+pub fn string_to_denom(s: String) -> Option<CheckedDenom> {
+    if s.is_empty() {
+        return None;
+    }
+    let (prefix, denom) = s.split_at(1);
+    match prefix {
+        "n" => Some(CheckedDenom::Native(denom.to_string())),
+        "c" => Some(CheckedDenom::Cw20(Addr::unchecked(denom))),
+        _ => None,
+    }
+}
@@ -1,7 +1,11 @@
 pub mod contract;
 mod tests;
 mod error;
+pub mod helpers;
+pub mod ledger;
 pub mod msg;
 pub mod state;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
 pub use crate::error::ContractError;
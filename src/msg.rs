@@ -1,10 +1,14 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Binary, Timestamp, Uint128};
 use cw20::Cw20ReceiveMsg;
-use cw_denom::CheckedDenom;
+use cw_denom::{CheckedDenom, UncheckedDenom};
 use cw_ownable::{cw_ownable_execute, cw_ownable_query};
+use cw_utils::{Duration, Expiration};
 
-use crate::state::Payment;
+use crate::state::{
+    AdminLogEntry, Campaign, Closure, Creation, Escrow, ForwardingConfig, IdRules, LineItem,
+    Period, Product, Transfer, Void,
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -12,6 +16,10 @@ pub struct InstantiateMsg {
     pub owner: Option<String>,
     /// The output address is where all funds are sent.
     pub output: String,
+    /// Rules receipt IDs must follow. If unset, any receipt ID is accepted.
+    pub id_rules: Option<IdRules>,
+    /// How payments are forwarded to the output. Defaults if unset.
+    pub forwarding: Option<ForwardingConfig>,
 }
 
 #[cw_ownable_execute]
@@ -19,16 +27,217 @@ pub struct InstantiateMsg {
 pub enum ExecuteMsg {
     /// Receive a cw20 token payment.
     Receive(Cw20ReceiveMsg),
-    /// Pay a native token payment.
-    Pay { id: String },
+    /// Pay a native token payment. If an idempotency key is set, a second
+    /// payment by the same payer to the same ID with the same key is rejected
+    /// until the key expires.
+    Pay {
+        id: String,
+        idempotency_key: Option<String>,
+    },
+    /// Allocate a new receipt ID, returned in the response data as a
+    /// `CreateReceiptResponse`. Anyone can call this, and becomes the
+    /// receipt's payer.
+    CreateReceipt { metadata: Option<String> },
+    /// Allocate a new receipt ID and pay a native token payment to it. The ID
+    /// is in the `PayResponse` data.
+    PayNew {},
     /// Update output. Only the owner can call this.
     UpdateOutput { output: String },
+    /// Update the rules receipt IDs must follow. Only the owner can call this.
+    /// Receipts already in use keep their IDs, and are still paid and looked
+    /// up by them exactly, even if the new rules would normalize them.
+    UpdateIdRules { id_rules: Option<IdRules> },
+    /// Assign the only payer allowed to pay a receipt before its first
+    /// payment, or clear the assignment. Only the owner can call this.
+    AssignPayer { id: String, payer: Option<String> },
+    /// Stop accepting payments to a receipt. Only the owner can call this.
+    CloseReceipt { id: String, reason: String },
+    /// Accept payments to a closed receipt again. Only the owner can call this.
+    ReopenReceipt { id: String },
+    /// Move the right to pay a receipt to a new payer. Only the current payer
+    /// or the owner can call this. Totals already paid stay attributed to the
+    /// payer that paid them.
+    TransferReceipt { id: String, new_payer: String },
+    /// Mark a payment recorded in error as voided and remove its amount from
+    /// the totals. The payment stays listed, flagged as voided. Funds already
+    /// forwarded are not affected. Only the owner can call this.
+    VoidPayment {
+        id: String,
+        payment_index: u64,
+        reason: String,
+        /// Return the payment's catalog items to stock, for products that
+        /// track it.
+        #[serde(default)]
+        restock: bool,
+    },
+    /// Update how payments are forwarded to the output. Only the owner can call
+    /// this.
+    UpdateForwarding { forwarding: ForwardingConfig },
+    /// Retry forwarding every unforwarded balance to the output. Only the owner
+    /// can call this.
+    FlushUnforwarded {},
+    /// Send everything owed to the output in the denoms, one transfer per
+    /// denom, or in every denom if none are given. Anyone can call this. See
+    /// `ForwardingConfig::batch`.
+    Settle { denoms: Vec<UncheckedDenom> },
+    /// Close the open accounting period, fixing its statement. Later payments
+    /// fall into the next period. Only the owner can call this.
+    ClosePeriod {},
+    /// Send funds held by the contract that it does not owe to anyone, such as
+    /// tokens sent without paying a receipt, to recipient. Amount defaults to
+    /// everything sweepable. Only the owner can call this.
+    Sweep {
+        denom: UncheckedDenom,
+        amount: Option<Uint128>,
+        recipient: String,
+    },
+    /// Pay a native token payment request signed off-chain by a registered
+    /// signer key. See `SignedPaymentRequest` for what is signed.
+    PayWithSignedRequest {
+        request: PaymentRequest,
+        /// 64-byte secp256k1 signature.
+        signature: Binary,
+        /// Compressed or uncompressed secp256k1 public key of the signer.
+        public_key: Binary,
+    },
+    /// Allow a secp256k1 public key to sign payment requests. Only the owner
+    /// can call this.
+    AddSignerKey { public_key: Binary },
+    /// Stop accepting payment requests signed by a public key. Only the owner
+    /// can call this.
+    RemoveSignerKey { public_key: Binary },
+    /// Add or update a catalog product. Only the owner can call this.
+    SetProduct {
+        sku: String,
+        name: String,
+        /// Unit price in each accepted denom.
+        prices: Vec<UncheckedPrice>,
+        /// Inactive products cannot be paid for.
+        active: bool,
+    },
+    /// Limit how many more units of a product can be sold, or stop tracking
+    /// stock if unset. Only the owner can call this.
+    SetProductStock { sku: String, stock: Option<u32> },
+    /// Pay native tokens for catalog products, given as SKU and quantity
+    /// pairs. Funds must be a single denom and exactly match the items' cost.
+    PayForItems {
+        id: String,
+        items: Vec<(String, u32)>,
+    },
+    /// Turn a receipt ID without payments into a campaign, which anyone can
+    /// pay until the deadline. Payments are held by the contract until the
+    /// campaign is finalized or refunded. Only the owner can call this.
+    CreateCampaign {
+        id: String,
+        /// Amount to raise in each accepted denom.
+        goal: Vec<UncheckedPrice>,
+        deadline: Expiration,
+    },
+    /// Forward the funds raised by a campaign that met its goal to the output,
+    /// ending it. Anyone can call this.
+    Finalize { id: String },
+    /// Send the sender's contributions to a campaign back to them, once it
+    /// ended without meeting its goal. Their payments are voided.
+    ClaimRefund { id: String },
+    /// Escrow payments to a receipt without payments. They are held until
+    /// the dispute window after the first payment ends, or until the arbiter
+    /// resolves a dispute opened by the payer. Only the owner can call this.
+    AssignArbiter {
+        id: String,
+        arbiter: String,
+        dispute_window: Duration,
+    },
+    /// Dispute an escrowed receipt before its dispute window ends, holding
+    /// its funds until the arbiter resolves it. Only the payer can call this.
+    OpenDispute { id: String, reason: String },
+    /// Release `release_bps` basis points of a disputed receipt's held funds
+    /// to the output and send the rest back to the payer, removing it from the
+    /// totals. Only the arbiter can call this.
+    ResolveDispute { id: String, release_bps: u16 },
+    /// Release an undisputed receipt's held funds to the output once its
+    /// dispute window ends. Anyone can call this.
+    ReleaseEscrow { id: String },
+}
+
+#[cw_serde]
+pub struct UncheckedPrice {
+    pub denom: UncheckedDenom,
+    pub amount: Uint128,
+}
+
+/// A payment the merchant asks a payer to make, signed off-chain.
+#[cw_serde]
+pub struct PaymentRequest {
+    pub id: String,
+    /// Exact amounts that must be paid, in any order.
+    pub amounts: Vec<Total>,
+    /// The only payer allowed to pay the request.
+    pub payer: String,
+    pub expires: Expiration,
+    /// Distinguishes otherwise identical requests. Each nonce can only be used
+    /// once per signer key.
+    pub nonce: u64,
+}
+
+/// What a signer key signs: the SHA-256 hash of this struct serialized as
+/// JSON. Including the contract address prevents the request from being paid
+/// to another contract.
+#[cw_serde]
+pub struct SignedPaymentRequest {
+    pub contract: String,
+    pub request: PaymentRequest,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {
+    /// Maximum number of legacy entries to migrate in this call. Migrating
+    /// from before v0.3.0 continues over as many calls as needed, and
+    /// execution is paused until it finishes. Defaults to 250.
+    pub limit: Option<u32>,
+}
+
+/// Messages sent to the settlement router. Routers that speak another
+/// interface can be wrapped in an adapter contract.
+#[cw_serde]
+pub enum RouterExecuteMsg {
+    /// Swap the offer into the ask denom and send the proceeds to the
+    /// recipient. Native offers are attached as funds, and cw20 offers are
+    /// pulled from an allowance. Must fail if the proceeds would be below
+    /// `min_return`, and respond with `SwapResponse` data.
+    Swap {
+        offer: Total,
+        ask_denom: CheckedDenom,
+        min_return: Uint128,
+        recipient: String,
+    },
+}
+
+#[cw_serde]
+pub struct SwapResponse {
+    pub return_amount: Uint128,
 }
 
 // Cw20 receiver message
 #[cw_serde]
 pub enum Cw20ReceiverMsg {
-    Pay { id: String },
+    /// See `ExecuteMsg::Pay`.
+    Pay {
+        id: String,
+        idempotency_key: Option<String>,
+    },
+    /// Allocate a new receipt ID and pay to it.
+    PayNew {},
+    /// Pay a payment request signed off-chain by a registered signer key.
+    PayWithSignedRequest {
+        request: PaymentRequest,
+        signature: Binary,
+        public_key: Binary,
+    },
+    /// See `ExecuteMsg::PayForItems`.
+    PayForItems {
+        id: String,
+        items: Vec<(String, u32)>,
+    },
 }
 
 #[cw_ownable_query]
@@ -39,6 +248,133 @@ pub enum QueryMsg {
     #[returns(OutputResponse)]
     Output {},
 
+    /// Returns the receipt ID rules.
+    #[returns(IdRulesResponse)]
+    IdRules {},
+
+    /// Returns the payer, payment count and closure of receipt ID.
+    #[returns(ReceiptResponse)]
+    Receipt { id: String },
+
+    /// Returns the payer authorized to pay receipt ID, if any.
+    #[returns(ReceiptPayerResponse)]
+    ReceiptPayer { id: String },
+
+    /// Returns the history of payer transfers for receipt ID.
+    #[returns(ListReceiptTransfersResponse)]
+    ListReceiptTransfers {
+        id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Returns whether payer currently holds receipt ID and the receipt's
+    /// totals meet every requirement, along with any missing amounts. The ID
+    /// is canonicalized for the payer the same way as when paying.
+    #[returns(HasPaidResponse)]
+    HasPaid {
+        id: String,
+        payer: String,
+        requirements: Vec<Total>,
+    },
+
+    /// Returns the admin action log, oldest first.
+    #[returns(ListAdminActionsResponse)]
+    ListAdminActions {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the receipt ID recorded when payer pays ID, which differs from
+    /// ID when the rules bind receipts to payers.
+    #[returns(CanonicalIdResponse)]
+    CanonicalId { id: String, payer: String },
+
+    /// Returns how payments are forwarded to the output.
+    #[returns(ForwardingResponse)]
+    Forwarding {},
+
+    /// Returns amounts held by the contract because forwarding them to the
+    /// output failed.
+    #[returns(ListUnforwardedResponse)]
+    ListUnforwarded {
+        start_after: Option<CheckedDenom>,
+        limit: Option<u32>,
+    },
+
+    /// Returns amounts owed to the output while batching.
+    #[returns(ListOwedToOutputResponse)]
+    ListOwedToOutput {
+        start_after: Option<CheckedDenom>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the contract's balance of denom, how much of it the contract
+    /// owes, and how much can be swept.
+    #[returns(SweepableResponse)]
+    Sweepable { denom: UncheckedDenom },
+
+    /// Returns the totals paid and voided during period, and when it was
+    /// closed if it is not the open period.
+    #[returns(PeriodStatementResponse)]
+    GetPeriodStatement { period: u64 },
+
+    /// Returns totals paid by each payer during period.
+    #[returns(ListPeriodPayerTotalsResponse)]
+    ListPeriodPayerTotals {
+        period: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns closed periods, oldest first, and the open period.
+    #[returns(ListPeriodsResponse)]
+    ListPeriods {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the public keys allowed to sign payment requests.
+    #[returns(ListSignerKeysResponse)]
+    ListSignerKeys {
+        start_after: Option<Binary>,
+        limit: Option<u32>,
+    },
+
+    /// Returns a catalog product, if it exists.
+    #[returns(ProductResponse)]
+    Product { sku: String },
+
+    /// Returns a campaign and how much it raised.
+    #[returns(CampaignResponse)]
+    Campaign { id: String },
+
+    /// Returns how much a payer contributed to a campaign and has not had
+    /// refunded.
+    #[returns(CampaignContributionsResponse)]
+    CampaignContributions { id: String, payer: String },
+
+    /// Returns how many more units of a product can be sold. Unset if the
+    /// product does not track stock.
+    #[returns(ProductStockResponse)]
+    ProductStock { sku: String },
+
+    /// Returns catalog products, ordered by SKU.
+    #[returns(ListProductsResponse)]
+    ListProducts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns receipt IDs that have payments, with their payment counts. If
+    /// prefix is set, only IDs starting with it are returned.
+    #[returns(ListReceiptIdsResponse)]
+    ListReceiptIds {
+        prefix: Option<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
     /// Returns list of payments for all receipts and payers.
     #[returns(ListPaymentsResponse)]
     ListPayments {
@@ -54,12 +390,14 @@ pub enum QueryMsg {
         limit: Option<u32>,
     },
 
-    /// Returns total paid per-denom to receipt ID.
+    /// Returns total paid per-denom to receipt ID. If height is set, returns
+    /// the totals as they were at the start of that block.
     #[returns(ListTotalsPaidToIdResponse)]
     ListTotalsPaidToId {
         id: String,
         start_after: Option<CheckedDenom>,
         limit: Option<u32>,
+        height: Option<u64>,
     },
 
     /// Returns list of receipt IDs for payer.
@@ -70,12 +408,14 @@ pub enum QueryMsg {
         limit: Option<u32>,
     },
 
-    /// Returns total paid per-denom by payer across all receipt IDs.
+    /// Returns total paid per-denom by payer across all receipt IDs. If height
+    /// is set, returns the totals as they were at the start of that block.
     #[returns(ListTotalsPaidByPayerResponse)]
     ListTotalsPaidByPayer {
         payer: String,
         start_after: Option<CheckedDenom>,
         limit: Option<u32>,
+        height: Option<u64>,
     },
 }
 
@@ -84,6 +424,146 @@ pub struct OutputResponse {
     pub output: Addr,
 }
 
+#[cw_serde]
+pub struct IdRulesResponse {
+    pub id_rules: Option<IdRules>,
+}
+
+#[cw_serde]
+pub struct ReceiptResponse {
+    pub id: String,
+    pub payer: Option<Addr>,
+    pub payment_count: u64,
+    /// Set if the receipt ID was generated by the contract.
+    pub creation: Option<Creation>,
+    /// Set if the receipt is closed.
+    pub closure: Option<Closure>,
+    /// Set if the receipt has an arbiter.
+    pub escrow: Option<ReceiptEscrow>,
+}
+
+#[cw_serde]
+pub struct ReceiptEscrow {
+    pub escrow: Escrow,
+    /// Funds held until released or the dispute is resolved.
+    pub held: Vec<Total>,
+}
+
+#[cw_serde]
+pub struct ReceiptPayerResponse {
+    pub payer: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct ReceiptTransfer {
+    pub transfer_id: u64,
+    pub transfer: Transfer,
+}
+
+#[cw_serde]
+pub struct ListReceiptTransfersResponse {
+    pub transfers: Vec<ReceiptTransfer>,
+}
+
+#[cw_serde]
+pub struct HasPaidResponse {
+    pub paid: bool,
+    /// Amount still missing for each unmet requirement.
+    pub missing: Vec<Total>,
+}
+
+#[cw_serde]
+pub struct AdminLogItem {
+    pub entry_id: u64,
+    pub entry: AdminLogEntry,
+}
+
+#[cw_serde]
+pub struct ListAdminActionsResponse {
+    pub actions: Vec<AdminLogItem>,
+}
+
+#[cw_serde]
+pub struct CanonicalIdResponse {
+    pub id: String,
+}
+
+#[cw_serde]
+pub struct ForwardingResponse {
+    pub forwarding: ForwardingConfig,
+}
+
+#[cw_serde]
+pub struct ListUnforwardedResponse {
+    pub unforwarded: Vec<Total>,
+}
+
+#[cw_serde]
+pub struct ListOwedToOutputResponse {
+    pub owed: Vec<Total>,
+}
+
+#[cw_serde]
+pub struct PeriodStatementResponse {
+    pub period: u64,
+    /// Set once the period is closed.
+    pub closed: Option<Period>,
+    /// Paid during the period, including payments voided later.
+    pub totals: Vec<Total>,
+    /// Voided during the period, whichever period the payments fell into.
+    pub voided: Vec<Total>,
+}
+
+#[cw_serde]
+pub struct PayerTotals {
+    pub payer: Addr,
+    pub totals: Vec<Total>,
+}
+
+#[cw_serde]
+pub struct ListPeriodPayerTotalsResponse {
+    pub payers: Vec<PayerTotals>,
+}
+
+#[cw_serde]
+pub struct PeriodItem {
+    pub period: u64,
+    pub closed: Period,
+}
+
+#[cw_serde]
+pub struct ListPeriodsResponse {
+    pub periods: Vec<PeriodItem>,
+    pub current_period: u64,
+}
+
+#[cw_serde]
+pub struct SweepableResponse {
+    pub denom: CheckedDenom,
+    pub balance: Uint128,
+    /// Held for obligations, such as unforwarded payments.
+    pub owed: Uint128,
+    pub sweepable: Uint128,
+}
+
+#[cw_serde]
+pub struct Payment {
+    pub payer: Addr,
+    pub block_height: u64,
+    pub block_time: Timestamp,
+    pub denom: CheckedDenom,
+    pub amount: Uint128,
+    /// Accounting period the payment fell into.
+    pub period: u64,
+    /// Catalog items the payment was for, if paid with `PayForItems`.
+    pub items: Vec<LineItem>,
+    /// What the output received, if the payment was settled. See
+    /// `ForwardingConfig::settlement`.
+    pub settled: Option<Total>,
+    /// Set if the payment was voided and no longer counts towards totals.
+    pub voided: Option<Void>,
+}
+
 #[cw_serde]
 pub struct ReceiptPaymentWithoutId {
     pub receipt_payment_id: u64,
@@ -103,6 +583,30 @@ pub struct Total {
     pub amount: Uint128,
 }
 
+/// Set as the response data of `CreateReceipt`.
+#[cw_serde]
+pub struct CreateReceiptResponse {
+    pub id: String,
+}
+
+/// Set as the response data of `Pay`, `PayNew` and their cw20 equivalents, so
+/// calling contracts can read the payments recorded in a `reply`.
+#[cw_serde]
+pub struct PayResponse {
+    /// One entry per coin paid, in the order the coins were sent.
+    pub payments: Vec<RecordedPayment>,
+    /// Totals paid to the receipt after the payments, for each denom paid.
+    pub totals: Vec<Total>,
+}
+
+#[cw_serde]
+pub struct RecordedPayment {
+    pub receipt_id: String,
+    pub receipt_payment_id: u64,
+    pub denom: CheckedDenom,
+    pub amount: Uint128,
+}
+
 #[cw_serde]
 pub struct ListPaymentsResponse {
     pub payments: Vec<ReceiptPayment>,
@@ -118,6 +622,56 @@ pub struct ListTotalsPaidToIdResponse {
     pub totals: Vec<Total>,
 }
 
+#[cw_serde]
+pub struct ListSignerKeysResponse {
+    pub public_keys: Vec<Binary>,
+}
+
+#[cw_serde]
+pub struct ProductResponse {
+    pub product: Option<Product>,
+}
+
+#[cw_serde]
+pub struct CampaignResponse {
+    pub campaign: Campaign,
+    /// Funds held for the campaign, until finalized.
+    pub raised: Vec<Total>,
+    pub goal_met: bool,
+}
+
+#[cw_serde]
+pub struct CampaignContributionsResponse {
+    pub contributions: Vec<Total>,
+}
+
+#[cw_serde]
+pub struct ProductStockResponse {
+    pub stock: Option<u32>,
+}
+
+#[cw_serde]
+pub struct ProductItem {
+    pub sku: String,
+    pub product: Product,
+}
+
+#[cw_serde]
+pub struct ListProductsResponse {
+    pub products: Vec<ProductItem>,
+}
+
+#[cw_serde]
+pub struct ReceiptIdItem {
+    pub id: String,
+    pub payment_count: u64,
+}
+
+#[cw_serde]
+pub struct ListReceiptIdsResponse {
+    pub receipts: Vec<ReceiptIdItem>,
+}
+
 #[cw_serde]
 pub struct ListIdsForPayerResponse {
     pub ids: Vec<String>,
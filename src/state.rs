@@ -1,33 +1,609 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, BlockInfo, Empty, Uint128};
+use cosmwasm_std::{Addr, Binary, BlockInfo, Decimal, Empty, Timestamp, Uint128};
 
 use cw_denom::CheckedDenom;
+use cw_ownable::Ownership;
 use cw_storage_plus::{Item, Map};
+use cw_utils::{Duration, Expiration};
+use sha2::{Digest, Sha256};
+
+use crate::ledger::{LedgerNamespaces, ReceiptLedger};
+use crate::msg::Total;
 
 pub const OUTPUT: Item<Addr> = Item::new("output");
+// Rules receipt IDs must follow. If unset, any receipt ID is accepted as-is.
+pub const ID_RULES: Item<IdRules> = Item::new("id_rules");
+
+// Payments, totals and authorized payers. The namespaces are the ones used
+// before the ledger was extracted, so existing data stays readable.
+pub const LEDGER: ReceiptLedger = ReceiptLedger::new(LedgerNamespaces {
+    denoms: "denoms",
+    denom_ids: "denom_ids",
+    denom_count: "denom_count",
+    payments: "receipt_payments",
+    payment_counts: "receipt_payment_count",
+    receipt_totals: "receipt_totals",
+    receipt_totals_checkpoints: "receipt_totals__checkpoints",
+    receipt_totals_changelog: "receipt_totals__changelog",
+    receipt_payers: "receipt_payer",
+    payer_receipts: "payer_receipts",
+    payer_totals: "payer_totals",
+    payer_totals_checkpoints: "payer_totals__checkpoints",
+    payer_totals_changelog: "payer_totals__changelog",
+    current_period: "current_period",
+    periods: "periods",
+    period_totals: "period_totals",
+    period_payer_totals: "period_payer_totals",
+    period_voided: "period_voided",
+});
+
+// Map receipt ID to why and by whom it was closed. Closed receipts cannot be
+// paid until reopened.
+pub const RECEIPT_CLOSURES: Map<String, Closure> = Map::new("receipt_closures");
+// Map receipt ID and incrementing transfer ID to a transfer of the receipt's
+// payer rights. Totals stay attributed to the payer that actually paid.
+pub const RECEIPT_TRANSFERS: Map<(String, u64), Transfer> = Map::new("receipt_transfers");
+
+// How payments are forwarded to the output. Defaults if unset.
+pub const FORWARDING: Item<ForwardingConfig> = Item::new("forwarding");
+// Map interned denom ID to the amount being forwarded in the current
+// transaction, read if the resilient forward fails. Overwritten by each
+// payment in the denom.
+pub const FORWARDS_IN_FLIGHT: Map<u32, Uint128> = Map::new("forwards_in_flight");
+// Map interned denom ID to the amount owed to the output while batching.
+pub const OWED_TO_OUTPUT: Map<u32, Uint128> = Map::new("owed_to_output");
+// Map interned denom ID to the amount being settled to the output while
+// batching, in case the transfer fails and it must be owed again.
+pub const BATCHES_IN_FLIGHT: Map<u32, Uint128> = Map::new("batches_in_flight");
+// Map interned denom ID to the payment in the denom being swapped into the
+// settlement denom.
+pub const SETTLEMENTS_IN_FLIGHT: Map<u32, InFlightSettlement> = Map::new("settlements_in_flight");
+// Map interned denom ID to the amount held by the contract after forwarding
+// it to the output failed.
+pub const UNFORWARDED: Map<u32, Uint128> = Map::new("unforwarded");
+
+// Set of secp256k1 public keys allowed to sign payment requests.
+pub const SIGNER_KEYS: Map<&[u8], Empty> = Map::new("signer_keys");
+// Set of signer key and nonce pairs already used by paid payment requests.
+pub const USED_NONCES: Map<(&[u8], u64), Empty> = Map::new("used_nonces");
+
+// How long an idempotency key blocks repeat payments, in seconds.
+pub const IDEMPOTENCY_KEY_TTL_SECONDS: u64 = 24 * 60 * 60;
+// Maximum length of an idempotency key.
+pub const IDEMPOTENCY_KEY_MAX_LENGTH: usize = 64;
+// Maximum number of expired idempotency keys removed per payment.
+pub const IDEMPOTENCY_KEY_PRUNE_LIMIT: usize = 10;
+// Map idempotency key hash (see `idempotency_key_hash`) to when it expires, in
+// seconds.
+pub const IDEMPOTENCY_KEYS: Map<&[u8], u64> = Map::new("idempotency_keys");
+// Index of idempotency key hashes by expiry, used to prune expired keys.
+pub const IDEMPOTENCY_KEY_EXPIRIES: Map<(u64, &[u8]), Empty> = Map::new("idempotency_key_expiries");
+
+// Map SKU to catalog product.
+pub const PRODUCTS: Map<&str, Product> = Map::new("products");
+// Map SKU to how many more units can be sold. Products without an entry have
+// unlimited stock.
+pub const PRODUCT_STOCK: Map<&str, u32> = Map::new("product_stock");
+
+// Map receipt ID to its campaign.
+pub const CAMPAIGNS: Map<&str, Campaign> = Map::new("campaigns");
+// Map campaign receipt ID and interned denom ID to the amount held for it.
+pub const CAMPAIGN_RAISED: Map<(&str, u32), Uint128> = Map::new("campaign_raised");
+// Map campaign receipt ID, payer and interned denom ID to the amount the payer
+// contributed and has not had refunded.
+pub const CAMPAIGN_CONTRIBUTIONS: Map<(&str, &Addr, u32), Uint128> =
+    Map::new("campaign_contributions");
+// Set of campaign receipt ID, payer and payment ID of each contribution that
+// has not been refunded.
+pub const CAMPAIGN_PAYMENTS: Map<(&str, &Addr, u64), Empty> = Map::new("campaign_payments");
+// Map interned denom ID to the amount held for all campaigns.
+pub const HELD_FOR_CAMPAIGNS: Map<u32, Uint128> = Map::new("held_for_campaigns");
+
+// Map receipt ID to its escrow, if it has an arbiter.
+pub const ESCROWS: Map<&str, Escrow> = Map::new("escrows");
+// Map escrowed receipt ID and interned denom ID to the amount held for it.
+pub const ESCROW_HELD: Map<(&str, u32), Uint128> = Map::new("escrow_held");
+// Map interned denom ID to the amount held for all escrows.
+pub const HELD_IN_ESCROW: Map<u32, Uint128> = Map::new("held_in_escrow");
+
+// Prefix of receipt IDs generated by the contract. Users cannot choose IDs
+// with this prefix, so generated IDs never collide with theirs.
+pub const GENERATED_ID_PREFIX: &str = "#";
+// Number of receipt IDs generated so far, used to allocate the next one.
+pub const GENERATED_ID_COUNT: Item<u64> = Item::new("generated_id_count");
+// Map generated receipt ID to who created it and its metadata.
+pub const RECEIPT_CREATIONS: Map<String, Creation> = Map::new("receipt_creations");
+
+#[cw_serde]
+#[derive(Default)]
+pub struct ForwardingConfig {
+    /// If true, a payment is still recorded when forwarding it to the output
+    /// fails. The funds stay in the contract as unforwarded until the owner
+    /// flushes them.
+    pub resilient: bool,
+    /// If set, payments in other denoms are swapped into the settlement denom
+    /// on their way to the output. A failed swap is held as unforwarded when
+    /// resilient.
+    #[serde(default)]
+    pub settlement: Option<SettlementConfig>,
+    /// If set, payments are not forwarded one by one. They accumulate as owed
+    /// to the output until settled, sending one transfer per denom. Cannot be
+    /// combined with `settlement`.
+    #[serde(default)]
+    pub batch: Option<BatchConfig>,
+}
+
+#[cw_serde]
+pub struct BatchConfig {
+    /// Settle a denom as soon as the amount owed in it reaches its threshold.
+    /// Other denoms wait for `Settle`.
+    pub thresholds: Vec<Total>,
+}
+
+impl BatchConfig {
+    /// Returns the threshold for a denom, if it has one.
+    pub fn threshold(&self, denom: &CheckedDenom) -> Option<Uint128> {
+        self.thresholds
+            .iter()
+            .find(|threshold| threshold.denom == *denom)
+            .map(|threshold| threshold.amount)
+    }
+}
+
+#[cw_serde]
+pub struct SettlementConfig {
+    /// DEX router implementing `RouterExecuteMsg`.
+    pub router: Addr,
+    /// Denom the output receives.
+    pub denom: CheckedDenom,
+    /// What a unit of each denom paid is worth in the settlement denom. Swaps
+    /// are only made from denoms with a reference price, so the router cannot
+    /// set its own minimum return.
+    pub reference_prices: Vec<ReferencePrice>,
+    /// Largest fraction below the reference price a swap may return, between
+    /// 0 and 1.
+    pub max_slippage: Decimal,
+}
+
+impl SettlementConfig {
+    /// Returns the reference price of a denom, if it has one.
+    pub fn reference_price(&self, denom: &CheckedDenom) -> Option<Decimal> {
+        self.reference_prices
+            .iter()
+            .find(|reference| reference.denom == *denom)
+            .map(|reference| reference.price)
+    }
+}
+
+#[cw_serde]
+pub struct ReferencePrice {
+    pub denom: CheckedDenom,
+    /// Settlement denom units per unit of `denom`.
+    pub price: Decimal,
+}
 
-// A receipt ID can have multiple payments. Only one payer can pay for a given
-// receipt.
+/// A payment being swapped into the settlement denom, awaiting its reply.
+#[cw_serde]
+pub struct InFlightSettlement {
+    pub id: String,
+    pub payment_id: u64,
+    pub amount: Uint128,
+}
 
-// Map receipt ID and incrementing payment ID (starting from 0 for a given
-// receipt ID) to the payment.
-pub const RECEIPT_PAYMENTS: Map<(String, u64), Payment> = Map::new("receipt_payments");
-// Map receipt ID to the number of payments for that receipt so far.
-pub const RECEIPT_PAYMENT_COUNT: Map<String, u64> = Map::new("receipt_payment_count");
-// Map receipt ID and serialized denom to total payment amount.
-pub const RECEIPT_TOTALS: Map<(String, String), Uint128> = Map::new("receipt_totals");
+#[cw_serde]
+pub struct IdRules {
+    /// Minimum length in bytes.
+    pub min_length: Option<u32>,
+    /// Maximum length in bytes.
+    pub max_length: Option<u32>,
+    /// Characters allowed in the ID, including the prefix.
+    pub charset: Option<IdCharset>,
+    /// Prefix every ID must start with.
+    pub prefix: Option<String>,
+    /// Case the ID is normalized to before it is validated and stored.
+    pub case: Option<IdCase>,
+    /// If true, the ID is stored as a commitment to the payer: the hex-encoded
+    /// SHA-256 of the canonical ID, a zero byte, and the payer address. Someone
+    /// else paying the same ID records a different receipt, so a receipt
+    /// cannot be squatted. The other rules apply to the ID before hashing, and
+    /// the hex digits are in the configured case. Campaign and escrowed IDs are
+    /// not bound.
+    #[serde(default)]
+    pub bind_to_payer: bool,
+}
 
-// Map authorized payer and receipt ID to an empty value, making it easy to
-// check if a payer is authorized for a given receipt and list receipts for a
-// given payer.
-pub const PAYER_RECEIPTS: Map<(Addr, String), Empty> = Map::new("payer_receipts");
-// Map payer and serialized denom to total payment amount.
-pub const PAYER_TOTALS: Map<(Addr, String), Uint128> = Map::new("payer_totals");
+#[cw_serde]
+pub enum IdCharset {
+    /// ASCII letters and digits.
+    Alphanumeric,
+    /// ASCII letters, digits, `-`, `_` and `.`.
+    AlphanumericWithSeparators,
+    /// Only the characters in the given string.
+    Custom(String),
+}
 
 #[cw_serde]
-pub struct Payment {
+pub enum IdCase {
+    Lower,
+    Upper,
+}
+
+impl IdCharset {
+    pub fn allows(&self, c: char) -> bool {
+        match self {
+            IdCharset::Alphanumeric => c.is_ascii_alphanumeric(),
+            IdCharset::AlphanumericWithSeparators => {
+                c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.'
+            }
+            IdCharset::Custom(chars) => chars.contains(c),
+        }
+    }
+}
+
+impl IdCase {
+    pub fn apply(&self, id: &str) -> String {
+        match self {
+            IdCase::Lower => id.to_lowercase(),
+            IdCase::Upper => id.to_uppercase(),
+        }
+    }
+}
+
+impl IdRules {
+    /// Returns the ID with case normalization applied.
+    pub fn normalize(&self, id: &str) -> String {
+        match &self.case {
+            Some(case) => case.apply(id),
+            None => id.to_string(),
+        }
+    }
+
+    /// Returns the canonical form of the ID paid by the payer, or the reason it
+    /// is invalid.
+    pub fn canonicalize(&self, id: &str, payer: &Addr) -> Result<String, String> {
+        let id = self.validate_id(id)?;
+        Ok(if self.bind_to_payer {
+            // Lookups normalize the case, so bound IDs must already be in it.
+            self.normalize(&payer_bound_id(&id, payer))
+        } else {
+            id
+        })
+    }
+
+    /// Returns the normalized ID, or the reason it is invalid.
+    pub fn validate_id(&self, id: &str) -> Result<String, String> {
+        let id = self.normalize(id);
+
+        if id.is_empty() {
+            return Err("empty".to_string());
+        }
+        if let Some(min_length) = self.min_length {
+            if id.len() < min_length as usize {
+                return Err(format!("shorter than {} bytes", min_length));
+            }
+        }
+        if let Some(max_length) = self.max_length {
+            if id.len() > max_length as usize {
+                return Err(format!("longer than {} bytes", max_length));
+            }
+        }
+        if let Some(prefix) = &self.prefix {
+            if !id.starts_with(&self.normalize(prefix)) {
+                return Err(format!("missing prefix {}", prefix));
+            }
+        }
+        if let Some(charset) = &self.charset {
+            if let Some(c) = id.chars().find(|c| !charset.allows(*c)) {
+                return Err(format!("character {:?} not allowed", c));
+            }
+        }
+
+        Ok(id)
+    }
+
+    /// Returns why the rules cannot be satisfied by any ID, if so.
+    pub fn validate(&self) -> Result<(), String> {
+        if let (Some(min_length), Some(max_length)) = (self.min_length, self.max_length) {
+            if min_length > max_length {
+                return Err("min length is greater than max length".to_string());
+            }
+        }
+        if let (Some(prefix), Some(max_length)) = (&self.prefix, self.max_length) {
+            if prefix.len() > max_length as usize {
+                return Err("prefix is longer than max length".to_string());
+            }
+        }
+        if let Some(IdCharset::Custom(chars)) = &self.charset {
+            if chars.is_empty() {
+                return Err("custom charset is empty".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the receipt ID committing to the payer. See
+/// `IdRules::bind_to_payer`.
+pub fn payer_bound_id(id: &str, payer: &Addr) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(id.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(payer.as_bytes());
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Returns the storage key for an idempotency key used by a payer on a receipt
+/// ID. Parts are length-prefixed so different splits never collide.
+pub fn idempotency_key_hash(payer: &Addr, id: &str, key: &str) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    for part in [payer.as_str(), id, key] {
+        hasher.update((part.len() as u32).to_be_bytes());
+        hasher.update(part.as_bytes());
+    }
+    hasher.finalize().to_vec()
+}
+
+#[cw_serde]
+pub struct Creation {
+    pub creator: Addr,
+    pub metadata: Option<String>,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+#[cw_serde]
+pub struct Closure {
+    pub reason: String,
+    pub closed_by: Addr,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+#[cw_serde]
+pub struct Transfer {
+    pub from: Addr,
+    pub to: Addr,
+    /// The payer or owner that made the transfer.
+    pub transferred_by: Addr,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+/// A payment as it is stored. Use `crate::msg::Payment` for the expanded form
+/// returned by queries.
+#[cw_serde]
+pub struct PaymentRecord {
+    pub payer: Addr,
+    pub height: u64,
+    pub time: Timestamp,
+    /// Interned denom ID. See `ReceiptLedger::denoms`.
+    pub denom: u32,
+    pub amount: Uint128,
+    /// Accounting period the payment fell into.
+    #[serde(default)]
+    pub period: u64,
+    /// Catalog items the payment was for. Omitted from storage if empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<LineItem>,
+    /// What the output received once the payment was swapped into the
+    /// settlement denom. Omitted from storage otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settled: Option<Settled>,
+    /// Set once the owner voids the payment. Omitted from storage otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voided: Option<Void>,
+}
+
+#[cw_serde]
+pub struct Product {
+    pub name: String,
+    /// Unit price in each accepted denom.
+    pub prices: Vec<Total>,
+    pub active: bool,
+}
+
+impl Product {
+    /// Returns the unit price in a denom, if the product accepts it.
+    pub fn price(&self, denom: &CheckedDenom) -> Option<Uint128> {
+        self.prices
+            .iter()
+            .find(|price| price.denom == *denom)
+            .map(|price| price.amount)
+    }
+}
+
+/// A catalog product paid for, priced in the payment's denom when it was made.
+#[cw_serde]
+pub struct LineItem {
+    pub sku: String,
+    pub quantity: u32,
+    pub unit_price: Uint128,
+}
+
+#[cw_serde]
+pub struct Campaign {
+    /// Amount to raise in each accepted denom.
+    pub goal: Vec<Total>,
+    pub deadline: Expiration,
+    /// Set once the funds raised are forwarded to the output.
+    pub finalized: bool,
+}
+
+/// Payments to a receipt with an arbiter are held until released to the
+/// output, or until the arbiter resolves a dispute opened by the payer.
+#[cw_serde]
+pub struct Escrow {
+    pub arbiter: Addr,
+    /// How long after the first payment the payer can open a dispute.
+    pub dispute_window: Duration,
+    /// When the dispute window ends. Unset until the first payment.
+    pub window_ends: Option<Expiration>,
+    pub dispute: Option<Dispute>,
+    /// Set once the held funds are released or the dispute resolved.
+    pub released: bool,
+}
+
+#[cw_serde]
+pub struct Dispute {
+    pub reason: String,
+    pub height: u64,
+    pub time: Timestamp,
+    /// Basis points of the held funds the arbiter released to the output. The
+    /// rest went back to the payer. Unset until resolved.
+    pub release_bps: Option<u16>,
+}
+
+#[cw_serde]
+pub struct Period {
+    pub closed_by: Addr,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+#[cw_serde]
+pub struct Settled {
+    /// Interned denom ID. See `ReceiptLedger::denoms`.
+    pub denom: u32,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct Void {
+    pub reason: String,
+    pub voided_by: Addr,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+#[cw_serde]
+pub struct AdminLogEntry {
+    pub actor: Addr,
+    pub action: AdminAction,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+#[cw_serde]
+pub enum AdminAction {
+    UpdateOutput {
+        old: Addr,
+        new: Addr,
+    },
+    UpdateOwnership {
+        old: Ownership<Addr>,
+        new: Ownership<Addr>,
+    },
+    UpdateIdRules {
+        old: Option<IdRules>,
+        new: Option<IdRules>,
+    },
+    AssignPayer {
+        id: String,
+        old: Option<Addr>,
+        new: Option<Addr>,
+    },
+    CloseReceipt {
+        id: String,
+        reason: String,
+    },
+    ReopenReceipt {
+        id: String,
+        old: Closure,
+    },
+    /// Only logged when the owner transfers a receipt on the payer's behalf.
+    TransferReceipt {
+        id: String,
+        old: Addr,
+        new: Addr,
+    },
+    VoidPayment {
+        id: String,
+        payment_index: u64,
+        reason: String,
+        #[serde(default)]
+        restock: bool,
+    },
+    UpdateForwarding {
+        old: ForwardingConfig,
+        new: ForwardingConfig,
+    },
+    FlushUnforwarded {
+        amounts: Vec<Total>,
+    },
+    ClosePeriod {
+        period: u64,
+    },
+    Sweep {
+        denom: CheckedDenom,
+        amount: Uint128,
+        recipient: Addr,
+    },
+    AddSignerKey {
+        public_key: Binary,
+    },
+    RemoveSignerKey {
+        public_key: Binary,
+    },
+    SetProduct {
+        sku: String,
+        old: Option<Product>,
+        new: Product,
+    },
+    SetProductStock {
+        sku: String,
+        old: Option<u32>,
+        new: Option<u32>,
+    },
+    CreateCampaign {
+        id: String,
+        campaign: Campaign,
+    },
+    AssignArbiter {
+        id: String,
+        old: Option<Escrow>,
+        new: Escrow,
+    },
+}
+
+// Append-only log of every action taken by the owner, keyed by incrementing
+// entry ID.
+pub const ADMIN_LOG: Map<u64, AdminLogEntry> = Map::new("admin_log");
+// Number of admin log entries so far, which is also the next entry ID.
+pub const ADMIN_LOG_COUNT: Item<u64> = Item::new("admin_log_count");
+
+// Storage layout used before v0.3.0. Only read when migrating.
+
+// Progress of a migration from the legacy layout, which may take several
+// `migrate` calls. Present only while one is in progress.
+pub const LEGACY_MIGRATION: Item<LegacyMigration> = Item::new("legacy_migration");
+// Default number of legacy entries migrated per `migrate` call.
+pub const LEGACY_MIGRATION_LIMIT: u32 = 250;
+
+pub const LEGACY_RECEIPT_PAYMENTS: Map<(String, u64), LegacyPayment> = Map::new("receipt_payments");
+// Map receipt ID and `n`/`c` prefixed denom string to total payment amount.
+pub const LEGACY_RECEIPT_TOTALS: Map<(String, String), Uint128> = Map::new("receipt_totals");
+// Map payer and `n`/`c` prefixed denom string to total payment amount.
+pub const LEGACY_PAYER_TOTALS: Map<(Addr, String), Uint128> = Map::new("payer_totals");
+
+#[cw_serde]
+pub struct LegacyPayment {
     pub payer: Addr,
     pub block: BlockInfo,
     pub denom: CheckedDenom,
     pub amount: Uint128,
 }
+
+#[cw_serde]
+pub enum LegacyMigration {
+    /// Removing legacy totals, which share their namespaces with the new
+    /// totals and must be gone before any are written.
+    Totals,
+    /// Rewriting payments and rebuilding totals from them.
+    Payments { start_after: Option<(String, u64)> },
+    /// Indexing receipt payers by receipt ID.
+    ReceiptPayers { start_after: Option<(Addr, String)> },
+}
@@ -0,0 +1,399 @@
+//! Multi-test harness for contracts integrating with cw-receipt. Enable the
+//! `testing` feature to use it from another crate's tests.
+
+use anyhow::Result as AnyResult;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    coins, to_binary, Addr, Binary, Coin, Decimal, Deps, DepsMut, Empty, Env, MessageInfo,
+    Response, StdError, StdResult, Uint128, WasmMsg,
+};
+use cw20::{Cw20Coin, Cw20ExecuteMsg};
+use cw_denom::CheckedDenom;
+use cw_multi_test::{App, AppResponse, BankSudo, Contract, ContractWrapper, Executor, SudoMsg};
+use cw_storage_plus::Item;
+
+use crate::msg::{
+    Cw20ReceiverMsg, ExecuteMsg, InstantiateMsg, ListTotalsPaidByPayerResponse,
+    ListTotalsPaidToIdResponse, QueryMsg, ReceiptResponse, RouterExecuteMsg, SwapResponse, Total,
+};
+use crate::state::{ForwardingConfig, IdRules};
+
+pub const OWNER: &str = "owner";
+pub const OUTPUT: &str = "output";
+
+pub fn receipt_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        crate::contract::execute,
+        crate::contract::instantiate,
+        crate::contract::query,
+    )
+    .with_reply(crate::contract::reply)
+    .with_migrate(crate::contract::migrate);
+    Box::new(contract)
+}
+
+pub fn cw20_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        cw20_base::contract::execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    );
+    Box::new(contract)
+}
+
+#[cw_serde]
+pub struct MockRouterInstantiateMsg {
+    /// Ask amount returned per unit offered.
+    pub rate: Decimal,
+}
+
+// Rate the mock router swaps at.
+const MOCK_ROUTER_RATE: Item<Decimal> = Item::new("mock_router_rate");
+
+/// A settlement router that swaps any offer at a fixed rate, paying out of its
+/// own balance. Fund it with the ask denom before swapping.
+pub fn mock_router_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        mock_router_execute,
+        mock_router_instantiate,
+        mock_router_query,
+    );
+    Box::new(contract)
+}
+
+fn mock_router_instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: MockRouterInstantiateMsg,
+) -> StdResult<Response> {
+    MOCK_ROUTER_RATE.save(deps.storage, &msg.rate)?;
+    Ok(Response::default())
+}
+
+fn mock_router_execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: RouterExecuteMsg,
+) -> StdResult<Response> {
+    let RouterExecuteMsg::Swap {
+        offer,
+        ask_denom,
+        min_return,
+        recipient,
+    } = msg;
+    let rate = MOCK_ROUTER_RATE.load(deps.storage)?;
+
+    let mut msgs = vec![];
+    match &offer.denom {
+        CheckedDenom::Native(denom) => {
+            if info.funds != coins(offer.amount.u128(), denom) {
+                return Err(StdError::generic_err("offer not attached"));
+            }
+        }
+        CheckedDenom::Cw20(cw20) => msgs.push(
+            WasmMsg::Execute {
+                contract_addr: cw20.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: info.sender.to_string(),
+                    recipient: env.contract.address.to_string(),
+                    amount: offer.amount,
+                })?,
+                funds: vec![],
+            }
+            .into(),
+        ),
+    }
+
+    let return_amount = offer.amount * rate;
+    if return_amount < min_return {
+        return Err(StdError::generic_err(format!(
+            "return {} is below minimum {}",
+            return_amount, min_return
+        )));
+    }
+    let recipient = deps.api.addr_validate(&recipient)?;
+    msgs.push(ask_denom.get_transfer_to_message(&recipient, return_amount)?);
+
+    Ok(Response::default()
+        .add_messages(msgs)
+        .set_data(to_binary(&SwapResponse { return_amount })?))
+}
+
+fn mock_router_query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    Err(StdError::generic_err("mock router has no queries"))
+}
+
+/// Builds an `App` with cw-receipt instantiated, along with any cw20 tokens
+/// and funded payers.
+pub struct ReceiptAppBuilder {
+    owner: String,
+    output: String,
+    id_rules: Option<IdRules>,
+    forwarding: Option<ForwardingConfig>,
+    native_balances: Vec<(String, Vec<Coin>)>,
+    cw20_balances: Vec<Vec<Cw20Coin>>,
+}
+
+impl Default for ReceiptAppBuilder {
+    fn default() -> Self {
+        Self {
+            owner: OWNER.to_string(),
+            output: OUTPUT.to_string(),
+            id_rules: None,
+            forwarding: None,
+            native_balances: vec![],
+            cw20_balances: vec![],
+        }
+    }
+}
+
+impl ReceiptAppBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_owner(mut self, owner: impl Into<String>) -> Self {
+        self.owner = owner.into();
+        self
+    }
+
+    pub fn with_output(mut self, output: impl Into<String>) -> Self {
+        self.output = output.into();
+        self
+    }
+
+    pub fn with_id_rules(mut self, id_rules: IdRules) -> Self {
+        self.id_rules = Some(id_rules);
+        self
+    }
+
+    pub fn with_forwarding(mut self, forwarding: ForwardingConfig) -> Self {
+        self.forwarding = Some(forwarding);
+        self
+    }
+
+    /// Mint native coins to an address.
+    pub fn with_native_balance(mut self, address: impl Into<String>, amount: Vec<Coin>) -> Self {
+        self.native_balances.push((address.into(), amount));
+        self
+    }
+
+    /// Add a cw20 token with the given initial balances. Tokens are available
+    /// from `ReceiptApp::cw20s` in the order they were added.
+    pub fn with_cw20(mut self, initial_balances: Vec<Cw20Coin>) -> Self {
+        self.cw20_balances.push(initial_balances);
+        self
+    }
+
+    pub fn build(self) -> ReceiptApp {
+        let mut app = App::default();
+
+        for (address, amount) in self.native_balances {
+            app.sudo(SudoMsg::Bank(BankSudo::Mint {
+                to_address: address,
+                amount,
+            }))
+            .unwrap();
+        }
+
+        let owner = Addr::unchecked(&self.owner);
+
+        let code_id = app.store_code(receipt_contract());
+        let receipt = app
+            .instantiate_contract(
+                code_id,
+                owner.clone(),
+                &InstantiateMsg {
+                    owner: Some(self.owner),
+                    output: self.output,
+                    id_rules: self.id_rules,
+                    forwarding: self.forwarding,
+                },
+                &[],
+                "receipt",
+                None,
+            )
+            .unwrap();
+
+        let cw20_code_id = app.store_code(cw20_contract());
+        let cw20s = self
+            .cw20_balances
+            .into_iter()
+            .enumerate()
+            .map(|(index, initial_balances)| {
+                app.instantiate_contract(
+                    cw20_code_id,
+                    owner.clone(),
+                    &cw20_base::msg::InstantiateMsg {
+                        name: "Test".to_string(),
+                        // Symbols may only contain letters.
+                        symbol: format!("TEST{}", (b'A' + index as u8) as char),
+                        decimals: 6,
+                        initial_balances,
+                        mint: None,
+                        marketing: None,
+                    },
+                    &[],
+                    "cw20",
+                    None,
+                )
+                .unwrap()
+            })
+            .collect();
+
+        ReceiptApp {
+            app,
+            receipt,
+            cw20s,
+            owner,
+        }
+    }
+}
+
+/// An `App` with cw-receipt instantiated. See `ReceiptAppBuilder`.
+pub struct ReceiptApp {
+    pub app: App,
+    /// The cw-receipt contract.
+    pub receipt: Addr,
+    /// The cw20 token contracts, in the order they were added.
+    pub cw20s: Vec<Addr>,
+    pub owner: Addr,
+}
+
+impl ReceiptApp {
+    /// Execute a message on cw-receipt.
+    pub fn execute(
+        &mut self,
+        sender: &str,
+        msg: &ExecuteMsg,
+        funds: &[Coin],
+    ) -> AnyResult<AppResponse> {
+        self.app
+            .execute_contract(Addr::unchecked(sender), self.receipt.clone(), msg, funds)
+    }
+
+    /// Instantiate a mock settlement router and mint it native liquidity. See
+    /// `mock_router_contract`.
+    pub fn instantiate_mock_router(
+        &mut self,
+        msg: MockRouterInstantiateMsg,
+        liquidity: Vec<Coin>,
+    ) -> Addr {
+        let code_id = self.app.store_code(mock_router_contract());
+        let router = self
+            .app
+            .instantiate_contract(code_id, self.owner.clone(), &msg, &[], "router", None)
+            .unwrap();
+        self.app
+            .sudo(SudoMsg::Bank(BankSudo::Mint {
+                to_address: router.to_string(),
+                amount: liquidity,
+            }))
+            .unwrap();
+        router
+    }
+
+    /// Pay native coins to a receipt ID.
+    pub fn pay_native(&mut self, payer: &str, id: &str, funds: &[Coin]) -> AnyResult<AppResponse> {
+        self.execute(
+            payer,
+            &ExecuteMsg::Pay {
+                id: id.to_string(),
+                idempotency_key: None,
+            },
+            funds,
+        )
+    }
+
+    /// Pay cw20 tokens to a receipt ID.
+    pub fn pay_cw20(
+        &mut self,
+        payer: &str,
+        cw20: &Addr,
+        id: &str,
+        amount: u128,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(payer),
+            cw20.clone(),
+            &cw20::Cw20ExecuteMsg::Send {
+                contract: self.receipt.to_string(),
+                amount: Uint128::new(amount),
+                msg: to_binary(&Cw20ReceiverMsg::Pay {
+                    id: id.to_string(),
+                    idempotency_key: None,
+                })?,
+            },
+            &[],
+        )
+    }
+
+    pub fn query_receipt(&self, id: &str) -> ReceiptResponse {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.receipt, &QueryMsg::Receipt { id: id.to_string() })
+            .unwrap()
+    }
+
+    pub fn query_totals_paid_to_id(&self, id: &str) -> Vec<Total> {
+        self.app
+            .wrap()
+            .query_wasm_smart::<ListTotalsPaidToIdResponse>(
+                &self.receipt,
+                &QueryMsg::ListTotalsPaidToId {
+                    id: id.to_string(),
+                    start_after: None,
+                    limit: None,
+                    height: None,
+                },
+            )
+            .unwrap()
+            .totals
+    }
+
+    pub fn query_totals_paid_by_payer(&self, payer: &str) -> Vec<Total> {
+        self.app
+            .wrap()
+            .query_wasm_smart::<ListTotalsPaidByPayerResponse>(
+                &self.receipt,
+                &QueryMsg::ListTotalsPaidByPayer {
+                    payer: payer.to_string(),
+                    start_after: None,
+                    limit: None,
+                    height: None,
+                },
+            )
+            .unwrap()
+            .totals
+    }
+
+    /// Assert the totals paid to a receipt ID, in the order the denoms were
+    /// first paid.
+    pub fn assert_receipt_totals(&self, id: &str, expected: &[Total]) {
+        assert_eq!(self.query_totals_paid_to_id(id), expected);
+    }
+
+    /// Assert the totals paid by a payer, in the order the denoms were first
+    /// paid.
+    pub fn assert_payer_totals(&self, payer: &str, expected: &[Total]) {
+        assert_eq!(self.query_totals_paid_by_payer(payer), expected);
+    }
+}
+
+/// Shorthand for a native `Total`.
+pub fn native_total(denom: &str, amount: u128) -> Total {
+    Total {
+        denom: CheckedDenom::Native(denom.to_string()),
+        amount: Uint128::new(amount),
+    }
+}
+
+/// Shorthand for a cw20 `Total`.
+pub fn cw20_total(cw20: &Addr, amount: u128) -> Total {
+    Total {
+        denom: CheckedDenom::Cw20(cw20.clone()),
+        amount: Uint128::new(amount),
+    }
+}
@@ -1,14 +1,29 @@
 #![cfg(test)]
-use cosmwasm_std::{coins, to_binary, Addr, Empty, Uint128};
+use cosmwasm_std::{coins, to_binary, Addr, Binary, Empty, Uint128};
 use cw_denom::CheckedDenom;
-use cw_multi_test::{App, BankSudo, Contract, ContractWrapper, Executor};
+use cw_multi_test::{App, BankSudo, Executor};
 
 use crate::msg::{
-    Cw20ReceiverMsg, ExecuteMsg, InstantiateMsg, ListIdsForPayerResponse, ListPaymentsResponse,
-    ListPaymentsToIdResponse, ListTotalsPaidByPayerResponse, ListTotalsPaidToIdResponse,
-    OutputResponse, QueryMsg, ReceiptPayment, ReceiptPaymentWithoutId, Total,
+    AdminLogItem, CampaignContributionsResponse, CampaignResponse, CanonicalIdResponse,
+    CreateReceiptResponse, Cw20ReceiverMsg, ExecuteMsg, ForwardingResponse, HasPaidResponse,
+    IdRulesResponse, ListAdminActionsResponse, ListIdsForPayerResponse, ListOwedToOutputResponse,
+    ListPaymentsResponse, ListPaymentsToIdResponse, ListPeriodPayerTotalsResponse,
+    ListPeriodsResponse, ListProductsResponse, ListReceiptIdsResponse,
+    ListReceiptTransfersResponse, ListSignerKeysResponse, ListTotalsPaidByPayerResponse,
+    ListTotalsPaidToIdResponse, ListUnforwardedResponse, OutputResponse, PayResponse, PayerTotals,
+    Payment, PaymentRequest, PeriodStatementResponse, ProductResponse, ProductStockResponse,
+    QueryMsg, ReceiptPayerResponse, ReceiptPayment, ReceiptPaymentWithoutId, ReceiptResponse,
+    ReceiptTransfer, RecordedPayment, SignedPaymentRequest, SweepableResponse, Total,
+    UncheckedPrice,
+};
+use crate::state::{
+    idempotency_key_hash, AdminAction, AdminLogEntry, BatchConfig, Closure, ForwardingConfig,
+    IdCase, IdCharset, IdRules, LineItem, Period, Product, ReferencePrice, SettlementConfig,
+    Transfer, Void, IDEMPOTENCY_KEYS, IDEMPOTENCY_KEY_MAX_LENGTH, IDEMPOTENCY_KEY_TTL_SECONDS,
+};
+use crate::testing::{
+    cw20_total, native_total, MockRouterInstantiateMsg, ReceiptApp, ReceiptAppBuilder,
 };
-use crate::state::Payment;
 use crate::ContractError;
 
 const OUTPUT: &str = "output";
@@ -18,86 +33,31 @@ const OTHER_PAYER: &str = "other_payer";
 const NATIVE_DENOM: &str = "uwasm";
 const RECEIPT_ID: &str = "receipt_id";
 
-fn setup_contract() -> Box<dyn Contract<Empty>> {
-    let contract = ContractWrapper::new(
-        crate::contract::execute,
-        crate::contract::instantiate,
-        crate::contract::query,
-    );
-    Box::new(contract)
-}
-
-fn setup_cw20_contract() -> Box<dyn Contract<Empty>> {
-    let contract = ContractWrapper::new(
-        cw20_base::contract::execute,
-        cw20_base::contract::instantiate,
-        cw20_base::contract::query,
-    );
-    Box::new(contract)
-}
-
 fn instantiate() -> (App, Addr, Addr) {
-    let mut app = App::default();
-
-    // Initialize payers native balances.
-    app.sudo(cw_multi_test::SudoMsg::Bank(BankSudo::Mint {
-        to_address: PAYER.to_string(),
-        amount: coins(10, NATIVE_DENOM),
-    }))
-    .unwrap();
-    app.sudo(cw_multi_test::SudoMsg::Bank(BankSudo::Mint {
-        to_address: OTHER_PAYER.to_string(),
-        amount: coins(10, NATIVE_DENOM),
-    }))
-    .unwrap();
-
-    // Instantiate contract.
-    let code_id = app.store_code(setup_contract());
-    let addr = app
-        .instantiate_contract(
-            code_id,
-            Addr::unchecked(OWNER),
-            &InstantiateMsg {
-                owner: Some(OWNER.to_string()),
-                output: OUTPUT.to_string(),
+    // Initialize payers native and cw20 balances.
+    let ReceiptApp {
+        app,
+        receipt,
+        cw20s,
+        ..
+    } = ReceiptAppBuilder::new()
+        .with_owner(OWNER)
+        .with_output(OUTPUT)
+        .with_native_balance(PAYER, coins(10, NATIVE_DENOM))
+        .with_native_balance(OTHER_PAYER, coins(10, NATIVE_DENOM))
+        .with_cw20(vec![
+            cw20::Cw20Coin {
+                address: PAYER.to_string(),
+                amount: Uint128::new(10),
             },
-            &[],
-            "receipt",
-            None,
-        )
-        .unwrap();
-
-    // Instantiate cw20 contract.
-    let cw20_code_id = app.store_code(setup_cw20_contract());
-    let cw20_addr = app
-        .instantiate_contract(
-            cw20_code_id,
-            Addr::unchecked(OWNER),
-            &cw20_base::msg::InstantiateMsg {
-                name: "Test".to_string(),
-                symbol: "TEST".to_string(),
-                decimals: 6,
-                // Initialize payers cw20 balances.
-                initial_balances: vec![
-                    cw20::Cw20Coin {
-                        address: PAYER.to_string(),
-                        amount: Uint128::new(10),
-                    },
-                    cw20::Cw20Coin {
-                        address: OTHER_PAYER.to_string(),
-                        amount: Uint128::new(10),
-                    },
-                ],
-                mint: None,
-                marketing: None,
+            cw20::Cw20Coin {
+                address: OTHER_PAYER.to_string(),
+                amount: Uint128::new(10),
             },
-            &[],
-            "cw20",
-            None,
-        )
-        .unwrap();
+        ])
+        .build();
 
-    (app, addr, cw20_addr)
+    (app, receipt, cw20s[0].clone())
 }
 
 #[test]
@@ -294,6 +254,7 @@ pub fn test_native_pay() {
             addr.clone(),
             &ExecuteMsg::Pay {
                 id: RECEIPT_ID.to_string(),
+                idempotency_key: None,
             },
             &[],
         )
@@ -309,6 +270,7 @@ pub fn test_native_pay() {
         addr.clone(),
         &ExecuteMsg::Pay {
             id: RECEIPT_ID.to_string(),
+            idempotency_key: None,
         },
         &coins(amount, NATIVE_DENOM),
     )
@@ -344,9 +306,14 @@ pub fn test_native_pay() {
                 receipt_payment_id: 0,
                 payment: Payment {
                     payer: Addr::unchecked(PAYER),
-                    block: block.clone(),
+                    block_height: block.height,
+                    block_time: block.time,
                     denom: CheckedDenom::Native(NATIVE_DENOM.to_string()),
                     amount: Uint128::from(amount),
+                    period: 0,
+                    items: vec![],
+                    settled: None,
+                    voided: None,
                 }
             }]
         }
@@ -371,9 +338,14 @@ pub fn test_native_pay() {
                 receipt_payment_id: 0,
                 payment: Payment {
                     payer: Addr::unchecked(PAYER),
-                    block: block.clone(),
+                    block_height: block.height,
+                    block_time: block.time,
                     denom: CheckedDenom::Native(NATIVE_DENOM.to_string()),
                     amount: Uint128::from(amount),
+                    period: 0,
+                    items: vec![],
+                    settled: None,
+                    voided: None,
                 }
             }]
         }
@@ -405,6 +377,7 @@ pub fn test_native_pay() {
             addr.clone(),
             &ExecuteMsg::Pay {
                 id: RECEIPT_ID.to_string(),
+                idempotency_key: None,
             },
             &coins(amount, NATIVE_DENOM),
         )
@@ -437,6 +410,7 @@ pub fn test_native_pay() {
         addr.clone(),
         &ExecuteMsg::Pay {
             id: RECEIPT_ID.to_string(),
+            idempotency_key: None,
         },
         &coins(amount * 2, NATIVE_DENOM),
     )
@@ -466,18 +440,28 @@ pub fn test_native_pay() {
                     receipt_payment_id: 0,
                     payment: Payment {
                         payer: Addr::unchecked(PAYER),
-                        block: block.clone(),
+                        block_height: block.height,
+                        block_time: block.time,
                         denom: CheckedDenom::Native(NATIVE_DENOM.to_string()),
                         amount: Uint128::from(amount),
+                        period: 0,
+                        items: vec![],
+                        settled: None,
+                        voided: None,
                     }
                 },
                 ReceiptPaymentWithoutId {
                     receipt_payment_id: 1,
                     payment: Payment {
                         payer: Addr::unchecked(PAYER),
-                        block: block.clone(),
+                        block_height: block.height,
+                        block_time: block.time,
                         denom: CheckedDenom::Native(NATIVE_DENOM.to_string()),
                         amount: Uint128::from(amount * 2),
+                        period: 0,
+                        items: vec![],
+                        settled: None,
+                        voided: None,
                     }
                 }
             ]
@@ -504,9 +488,14 @@ pub fn test_native_pay() {
                     receipt_payment_id: 0,
                     payment: Payment {
                         payer: Addr::unchecked(PAYER),
-                        block: block.clone(),
+                        block_height: block.height,
+                        block_time: block.time,
                         denom: CheckedDenom::Native(NATIVE_DENOM.to_string()),
                         amount: Uint128::from(amount),
+                        period: 0,
+                        items: vec![],
+                        settled: None,
+                        voided: None,
                     }
                 },
                 ReceiptPayment {
@@ -514,9 +503,14 @@ pub fn test_native_pay() {
                     receipt_payment_id: 1,
                     payment: Payment {
                         payer: Addr::unchecked(PAYER),
-                        block,
+                        block_height: block.height,
+                        block_time: block.time,
                         denom: CheckedDenom::Native(NATIVE_DENOM.to_string()),
                         amount: Uint128::from(amount * 2),
+                        period: 0,
+                        items: vec![],
+                        settled: None,
+                        voided: None,
                     }
                 }
             ]
@@ -532,6 +526,7 @@ pub fn test_native_pay() {
                 id: RECEIPT_ID.to_string(),
                 start_after: None,
                 limit: None,
+                height: None,
             },
         )
         .unwrap();
@@ -554,6 +549,7 @@ pub fn test_native_pay() {
                 payer: PAYER.to_string(),
                 start_after: None,
                 limit: None,
+                height: None,
             },
         )
         .unwrap();
@@ -576,6 +572,7 @@ pub fn test_native_pay() {
                 payer: OTHER_PAYER.to_string(),
                 start_after: None,
                 limit: None,
+                height: None,
             },
         )
         .unwrap();
@@ -590,6 +587,7 @@ pub fn test_native_pay() {
                 id: "unused_receipt".to_string(),
                 start_after: None,
                 limit: None,
+                height: None,
             },
         )
         .unwrap();
@@ -623,6 +621,7 @@ pub fn test_cw20_pay() {
             amount: Uint128::from(amount),
             msg: to_binary(&Cw20ReceiverMsg::Pay {
                 id: RECEIPT_ID.to_string(),
+                idempotency_key: None,
             })
             .unwrap(),
         },
@@ -673,9 +672,14 @@ pub fn test_cw20_pay() {
                 receipt_payment_id: 0,
                 payment: Payment {
                     payer: Addr::unchecked(PAYER),
-                    block: block.clone(),
+                    block_height: block.height,
+                    block_time: block.time,
                     denom: CheckedDenom::Cw20(cw20_addr.clone()),
                     amount: Uint128::from(amount),
+                    period: 0,
+                    items: vec![],
+                    settled: None,
+                    voided: None,
                 }
             }]
         }
@@ -700,9 +704,14 @@ pub fn test_cw20_pay() {
                 receipt_payment_id: 0,
                 payment: Payment {
                     payer: Addr::unchecked(PAYER),
-                    block: block.clone(),
+                    block_height: block.height,
+                    block_time: block.time,
                     denom: CheckedDenom::Cw20(cw20_addr.clone()),
                     amount: Uint128::from(amount),
+                    period: 0,
+                    items: vec![],
+                    settled: None,
+                    voided: None,
                 }
             }]
         }
@@ -737,6 +746,7 @@ pub fn test_cw20_pay() {
                 amount: Uint128::from(amount),
                 msg: to_binary(&Cw20ReceiverMsg::Pay {
                     id: RECEIPT_ID.to_string(),
+                    idempotency_key: None,
                 })
                 .unwrap(),
             },
@@ -782,6 +792,7 @@ pub fn test_cw20_pay() {
             amount: Uint128::from(amount * 2),
             msg: to_binary(&Cw20ReceiverMsg::Pay {
                 id: RECEIPT_ID.to_string(),
+                idempotency_key: None,
             })
             .unwrap(),
         },
@@ -821,18 +832,28 @@ pub fn test_cw20_pay() {
                     receipt_payment_id: 0,
                     payment: Payment {
                         payer: Addr::unchecked(PAYER),
-                        block: block.clone(),
+                        block_height: block.height,
+                        block_time: block.time,
                         denom: CheckedDenom::Cw20(cw20_addr.clone()),
                         amount: Uint128::from(amount),
+                        period: 0,
+                        items: vec![],
+                        settled: None,
+                        voided: None,
                     }
                 },
                 ReceiptPaymentWithoutId {
                     receipt_payment_id: 1,
                     payment: Payment {
                         payer: Addr::unchecked(PAYER),
-                        block: block.clone(),
+                        block_height: block.height,
+                        block_time: block.time,
                         denom: CheckedDenom::Cw20(cw20_addr.clone()),
                         amount: Uint128::from(amount * 2),
+                        period: 0,
+                        items: vec![],
+                        settled: None,
+                        voided: None,
                     }
                 }
             ]
@@ -859,9 +880,14 @@ pub fn test_cw20_pay() {
                     receipt_payment_id: 0,
                     payment: Payment {
                         payer: Addr::unchecked(PAYER),
-                        block: block.clone(),
+                        block_height: block.height,
+                        block_time: block.time,
                         denom: CheckedDenom::Cw20(cw20_addr.clone()),
                         amount: Uint128::from(amount),
+                        period: 0,
+                        items: vec![],
+                        settled: None,
+                        voided: None,
                     }
                 },
                 ReceiptPayment {
@@ -869,9 +895,14 @@ pub fn test_cw20_pay() {
                     receipt_payment_id: 1,
                     payment: Payment {
                         payer: Addr::unchecked(PAYER),
-                        block,
+                        block_height: block.height,
+                        block_time: block.time,
                         denom: CheckedDenom::Cw20(cw20_addr.clone()),
                         amount: Uint128::from(amount * 2),
+                        period: 0,
+                        items: vec![],
+                        settled: None,
+                        voided: None,
                     }
                 }
             ]
@@ -887,6 +918,7 @@ pub fn test_cw20_pay() {
                 id: RECEIPT_ID.to_string(),
                 start_after: None,
                 limit: None,
+                height: None,
             },
         )
         .unwrap();
@@ -909,6 +941,7 @@ pub fn test_cw20_pay() {
                 payer: PAYER.to_string(),
                 start_after: None,
                 limit: None,
+                height: None,
             },
         )
         .unwrap();
@@ -931,6 +964,7 @@ pub fn test_cw20_pay() {
                 payer: OTHER_PAYER.to_string(),
                 start_after: None,
                 limit: None,
+                height: None,
             },
         )
         .unwrap();
@@ -945,6 +979,7 @@ pub fn test_cw20_pay() {
                 id: "unused_receipt".to_string(),
                 start_after: None,
                 limit: None,
+                height: None,
             },
         )
         .unwrap();
@@ -979,6 +1014,7 @@ pub fn test_both_pay() {
         addr.clone(),
         &ExecuteMsg::Pay {
             id: RECEIPT_ID.to_string(),
+            idempotency_key: None,
         },
         &coins(native_amount, NATIVE_DENOM),
     )
@@ -994,6 +1030,7 @@ pub fn test_both_pay() {
             amount: Uint128::from(cw20_amount),
             msg: to_binary(&Cw20ReceiverMsg::Pay {
                 id: RECEIPT_ID.to_string(),
+                idempotency_key: None,
             })
             .unwrap(),
         },
@@ -1056,18 +1093,28 @@ pub fn test_both_pay() {
                     receipt_payment_id: 0,
                     payment: Payment {
                         payer: Addr::unchecked(PAYER),
-                        block: block.clone(),
+                        block_height: block.height,
+                        block_time: block.time,
                         denom: CheckedDenom::Native(NATIVE_DENOM.to_string()),
                         amount: Uint128::from(native_amount),
+                        period: 0,
+                        items: vec![],
+                        settled: None,
+                        voided: None,
                     }
                 },
                 ReceiptPaymentWithoutId {
                     receipt_payment_id: 1,
                     payment: Payment {
                         payer: Addr::unchecked(PAYER),
-                        block: block.clone(),
+                        block_height: block.height,
+                        block_time: block.time,
                         denom: CheckedDenom::Cw20(cw20_addr.clone()),
                         amount: Uint128::from(cw20_amount),
+                        period: 0,
+                        items: vec![],
+                        settled: None,
+                        voided: None,
                     }
                 }
             ]
@@ -1094,9 +1141,14 @@ pub fn test_both_pay() {
                     receipt_payment_id: 0,
                     payment: Payment {
                         payer: Addr::unchecked(PAYER),
-                        block: block.clone(),
+                        block_height: block.height,
+                        block_time: block.time,
                         denom: CheckedDenom::Native(NATIVE_DENOM.to_string()),
                         amount: Uint128::from(native_amount),
+                        period: 0,
+                        items: vec![],
+                        settled: None,
+                        voided: None,
                     }
                 },
                 ReceiptPayment {
@@ -1104,9 +1156,14 @@ pub fn test_both_pay() {
                     receipt_payment_id: 1,
                     payment: Payment {
                         payer: Addr::unchecked(PAYER),
-                        block,
+                        block_height: block.height,
+                        block_time: block.time,
                         denom: CheckedDenom::Cw20(cw20_addr.clone()),
                         amount: Uint128::from(cw20_amount),
+                        period: 0,
+                        items: vec![],
+                        settled: None,
+                        voided: None,
                     }
                 }
             ]
@@ -1132,7 +1189,8 @@ pub fn test_both_pay() {
         }
     );
 
-    // Ensure both totals accumulated for receipt.
+    // Ensure both totals accumulated for receipt, in the order the denoms were
+    // first paid.
     let res: ListTotalsPaidToIdResponse = app
         .wrap()
         .query_wasm_smart(
@@ -1141,6 +1199,7 @@ pub fn test_both_pay() {
                 id: RECEIPT_ID.to_string(),
                 start_after: None,
                 limit: None,
+                height: None,
             },
         )
         .unwrap();
@@ -1148,27 +1207,29 @@ pub fn test_both_pay() {
         res,
         ListTotalsPaidToIdResponse {
             totals: vec![
-                Total {
-                    denom: CheckedDenom::Cw20(cw20_addr.clone()),
-                    amount: Uint128::from(cw20_amount),
-                },
                 Total {
                     denom: CheckedDenom::Native(NATIVE_DENOM.to_string()),
                     amount: Uint128::from(native_amount),
+                },
+                Total {
+                    denom: CheckedDenom::Cw20(cw20_addr.clone()),
+                    amount: Uint128::from(cw20_amount),
                 }
             ]
         }
     );
 
-    // Ensure both totals accumulated for payer.
+    // Ensure both totals accumulated for payer, in the order the denoms were
+    // first paid.
     let res: ListTotalsPaidByPayerResponse = app
         .wrap()
         .query_wasm_smart(
-            addr,
+            addr.clone(),
             &QueryMsg::ListTotalsPaidByPayer {
                 payer: PAYER.to_string(),
                 start_after: None,
                 limit: None,
+                height: None,
             },
         )
         .unwrap();
@@ -1176,15 +1237,5112 @@ pub fn test_both_pay() {
         res,
         ListTotalsPaidByPayerResponse {
             totals: vec![
-                Total {
-                    denom: CheckedDenom::Cw20(cw20_addr),
-                    amount: Uint128::from(cw20_amount),
-                },
                 Total {
                     denom: CheckedDenom::Native(NATIVE_DENOM.to_string()),
                     amount: Uint128::from(native_amount),
+                },
+                Total {
+                    denom: CheckedDenom::Cw20(cw20_addr.clone()),
+                    amount: Uint128::from(cw20_amount),
                 }
             ]
         }
     );
+
+    // Ensure totals paginate by denom, and a denom never paid starts from the
+    // beginning.
+    for (start_after, expected) in [
+        (
+            CheckedDenom::Native(NATIVE_DENOM.to_string()),
+            vec![CheckedDenom::Cw20(cw20_addr.clone())],
+        ),
+        (
+            CheckedDenom::Native("unpaid".to_string()),
+            vec![
+                CheckedDenom::Native(NATIVE_DENOM.to_string()),
+                CheckedDenom::Cw20(cw20_addr.clone()),
+            ],
+        ),
+    ] {
+        let res: ListTotalsPaidToIdResponse = app
+            .wrap()
+            .query_wasm_smart(
+                addr.clone(),
+                &QueryMsg::ListTotalsPaidToId {
+                    id: RECEIPT_ID.to_string(),
+                    start_after: Some(start_after),
+                    limit: None,
+                    height: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            res.totals
+                .into_iter()
+                .map(|total| total.denom)
+                .collect::<Vec<_>>(),
+            expected
+        );
+    }
+}
+
+#[test]
+pub fn test_id_rules() {
+    let (mut app, addr, cw20_addr) = instantiate();
+
+    // Ensure no rules by default.
+    let res: IdRulesResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::IdRules {})
+        .unwrap();
+    assert_eq!(res, IdRulesResponse { id_rules: None });
+
+    let id_rules = IdRules {
+        min_length: Some(8),
+        max_length: Some(16),
+        charset: Some(IdCharset::AlphanumericWithSeparators),
+        prefix: Some("order-".to_string()),
+        case: Some(IdCase::Lower),
+        bind_to_payer: false,
+    };
+
+    // Ensure non-owner cannot update rules.
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(PAYER),
+            addr.clone(),
+            &ExecuteMsg::UpdateIdRules {
+                id_rules: Some(id_rules.clone()),
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::Ownable(cw_ownable::OwnershipError::NotOwner)
+    );
+
+    // Ensure unsatisfiable rules are rejected.
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            addr.clone(),
+            &ExecuteMsg::UpdateIdRules {
+                id_rules: Some(IdRules {
+                    min_length: Some(17),
+                    ..id_rules.clone()
+                }),
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::InvalidIdRules {
+            reason: "min length is greater than max length".to_string()
+        }
+    );
+
+    // Update rules.
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        addr.clone(),
+        &ExecuteMsg::UpdateIdRules {
+            id_rules: Some(id_rules.clone()),
+        },
+        &[],
+    )
+    .unwrap();
+    let res: IdRulesResponse = app
+        .wrap()
+        .query_wasm_smart(addr.clone(), &QueryMsg::IdRules {})
+        .unwrap();
+    assert_eq!(
+        res,
+        IdRulesResponse {
+            id_rules: Some(id_rules)
+        }
+    );
+
+    // Ensure invalid IDs are rejected for native payments.
+    for (id, reason) in [
+        ("", "empty"),
+        ("order-1", "shorter than 8 bytes"),
+        ("order-1234567890ab", "longer than 16 bytes"),
+        ("invoice-1234", "missing prefix order-"),
+        ("order-12 34", "character ' ' not allowed"),
+    ] {
+        let err: ContractError = app
+            .execute_contract(
+                Addr::unchecked(PAYER),
+                addr.clone(),
+                &ExecuteMsg::Pay {
+                    id: id.to_string(),
+                    idempotency_key: None,
+                },
+                &coins(1, NATIVE_DENOM),
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::InvalidReceiptId {
+                reason: reason.to_string()
+            }
+        );
+    }
+
+    // Ensure invalid IDs are rejected for cw20 payments.
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(PAYER),
+            cw20_addr.clone(),
+            &cw20::Cw20ExecuteMsg::Send {
+                contract: addr.to_string(),
+                amount: Uint128::new(1),
+                msg: to_binary(&Cw20ReceiverMsg::Pay {
+                    id: "order-1".to_string(),
+                    idempotency_key: None,
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::InvalidReceiptId {
+            reason: "shorter than 8 bytes".to_string()
+        }
+    );
+
+    // Pay to IDs differing only in case.
+    app.execute_contract(
+        Addr::unchecked(PAYER),
+        addr.clone(),
+        &ExecuteMsg::Pay {
+            id: "ORDER-ABC123".to_string(),
+            idempotency_key: None,
+        },
+        &coins(1, NATIVE_DENOM),
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(PAYER),
+        cw20_addr,
+        &cw20::Cw20ExecuteMsg::Send {
+            contract: addr.to_string(),
+            amount: Uint128::new(1),
+            msg: to_binary(&Cw20ReceiverMsg::Pay {
+                id: "Order-Abc123".to_string(),
+                idempotency_key: None,
+            })
+            .unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Ensure both payments recorded under the canonical ID.
+    let res: ListIdsForPayerResponse = app
+        .wrap()
+        .query_wasm_smart(
+            addr.clone(),
+            &QueryMsg::ListIdsForPayer {
+                payer: PAYER.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        ListIdsForPayerResponse {
+            ids: vec!["order-abc123".to_string()]
+        }
+    );
+
+    // Ensure lookups are normalized too.
+    let res: ListPaymentsToIdResponse = app
+        .wrap()
+        .query_wasm_smart(
+            addr,
+            &QueryMsg::ListPaymentsToId {
+                id: "ORDER-abc123".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.payments.len(), 2);
+}
+
+#[test]
+pub fn test_id_rules_keep_existing_ids() {
+    let (mut app, addr, _) = instantiate();
+    let pay = |app: &mut App, payer: &str| {
+        app.execute_contract(
+            Addr::unchecked(payer),
+            addr.clone(),
+            &ExecuteMsg::Pay {
+                id: "Order-A".to_string(),
+                idempotency_key: None,
+            },
+            &coins(1, NATIVE_DENOM),
+        )
+    };
+
+    pay(&mut app, PAYER).unwrap();
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        addr.clone(),
+        &ExecuteMsg::UpdateIdRules {
+            id_rules: Some(IdRules {
+                min_length: None,
+                max_length: None,
+                charset: None,
+                prefix: None,
+                case: Some(IdCase::Lower),
+                bind_to_payer: false,
+            }),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Ensure the existing receipt is still paid and found by its ID, and
+    // other payers cannot pay it as a new receipt.
+    pay(&mut app, PAYER).unwrap();
+    let err: ContractError = pay(&mut app, OTHER_PAYER).unwrap_err().downcast().unwrap();
+    assert_eq!(err, ContractError::UnauthorizedPayer);
+    let res: ListTotalsPaidToIdResponse = app
+        .wrap()
+        .query_wasm_smart(
+            addr,
+            &QueryMsg::ListTotalsPaidToId {
+                id: "Order-A".to_string(),
+                start_after: None,
+                limit: None,
+                height: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res.totals,
+        vec![Total {
+            denom: CheckedDenom::Native(NATIVE_DENOM.to_string()),
+            amount: Uint128::new(2),
+        }]
+    );
+}
+
+#[test]
+pub fn test_assign_payer() {
+    let (mut app, addr, cw20_addr) = instantiate();
+
+    // Ensure non-owner cannot assign payer.
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(OTHER_PAYER),
+            addr.clone(),
+            &ExecuteMsg::AssignPayer {
+                id: RECEIPT_ID.to_string(),
+                payer: Some(OTHER_PAYER.to_string()),
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::Ownable(cw_ownable::OwnershipError::NotOwner)
+    );
+
+    // Assign payer before any payment.
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        addr.clone(),
+        &ExecuteMsg::AssignPayer {
+            id: RECEIPT_ID.to_string(),
+            payer: Some(PAYER.to_string()),
+        },
+        &[],
+    )
+    .unwrap();
+    let res: ReceiptPayerResponse = app
+        .wrap()
+        .query_wasm_smart(
+            addr.clone(),
+            &QueryMsg::ReceiptPayer {
+                id: RECEIPT_ID.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        ReceiptPayerResponse {
+            payer: Some(Addr::unchecked(PAYER))
+        }
+    );
+
+    // Ensure assigned receipt listed for payer.
+    let res: ListIdsForPayerResponse = app
+        .wrap()
+        .query_wasm_smart(
+            addr.clone(),
+            &QueryMsg::ListIdsForPayer {
+                payer: PAYER.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        ListIdsForPayerResponse {
+            ids: vec![RECEIPT_ID.to_string()]
+        }
+    );
+
+    // Ensure squatter cannot make the first payment, native or cw20.
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(OTHER_PAYER),
+            addr.clone(),
+            &ExecuteMsg::Pay {
+                id: RECEIPT_ID.to_string(),
+                idempotency_key: None,
+            },
+            &coins(1, NATIVE_DENOM),
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::UnauthorizedPayer);
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(OTHER_PAYER),
+            cw20_addr,
+            &cw20::Cw20ExecuteMsg::Send {
+                contract: addr.to_string(),
+                amount: Uint128::new(1),
+                msg: to_binary(&Cw20ReceiverMsg::Pay {
+                    id: RECEIPT_ID.to_string(),
+                    idempotency_key: None,
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::UnauthorizedPayer);
+
+    // Assigned payer pays.
+    app.execute_contract(
+        Addr::unchecked(PAYER),
+        addr.clone(),
+        &ExecuteMsg::Pay {
+            id: RECEIPT_ID.to_string(),
+            idempotency_key: None,
+        },
+        &coins(1, NATIVE_DENOM),
+    )
+    .unwrap();
+
+    // Ensure assignment cannot change once paid.
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            addr.clone(),
+            &ExecuteMsg::AssignPayer {
+                id: RECEIPT_ID.to_string(),
+                payer: Some(OTHER_PAYER.to_string()),
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::ReceiptAlreadyPaid);
+
+    // Assign and then clear another receipt.
+    let other_id = "other_receipt_id";
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        addr.clone(),
+        &ExecuteMsg::AssignPayer {
+            id: other_id.to_string(),
+            payer: Some(PAYER.to_string()),
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        addr.clone(),
+        &ExecuteMsg::AssignPayer {
+            id: other_id.to_string(),
+            payer: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Ensure anyone can pay the cleared receipt again.
+    app.execute_contract(
+        Addr::unchecked(OTHER_PAYER),
+        addr.clone(),
+        &ExecuteMsg::Pay {
+            id: other_id.to_string(),
+            idempotency_key: None,
+        },
+        &coins(1, NATIVE_DENOM),
+    )
+    .unwrap();
+    let res: ListIdsForPayerResponse = app
+        .wrap()
+        .query_wasm_smart(
+            addr,
+            &QueryMsg::ListIdsForPayer {
+                payer: PAYER.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        ListIdsForPayerResponse {
+            ids: vec![RECEIPT_ID.to_string()]
+        }
+    );
+}
+
+#[test]
+pub fn test_payer_bound_ids() {
+    let (mut app, addr, _) = instantiate();
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        addr.clone(),
+        &ExecuteMsg::UpdateIdRules {
+            id_rules: Some(IdRules {
+                min_length: None,
+                max_length: None,
+                charset: None,
+                prefix: None,
+                case: None,
+                bind_to_payer: true,
+            }),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Both payers pay the same order ID.
+    for payer in [PAYER, OTHER_PAYER] {
+        app.execute_contract(
+            Addr::unchecked(payer),
+            addr.clone(),
+            &ExecuteMsg::Pay {
+                id: RECEIPT_ID.to_string(),
+                idempotency_key: None,
+            },
+            &coins(1, NATIVE_DENOM),
+        )
+        .unwrap();
+    }
+
+    // Ensure each payment is recorded under a receipt bound to its payer.
+    let payer_id = crate::state::payer_bound_id(RECEIPT_ID, &Addr::unchecked(PAYER));
+    let other_payer_id = crate::state::payer_bound_id(RECEIPT_ID, &Addr::unchecked(OTHER_PAYER));
+    assert_ne!(payer_id, other_payer_id);
+
+    let res: CanonicalIdResponse = app
+        .wrap()
+        .query_wasm_smart(
+            addr.clone(),
+            &QueryMsg::CanonicalId {
+                id: RECEIPT_ID.to_string(),
+                payer: PAYER.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        CanonicalIdResponse {
+            id: payer_id.clone()
+        }
+    );
+
+    for (payer, id) in [(PAYER, payer_id), (OTHER_PAYER, other_payer_id)] {
+        let res: ReceiptPayerResponse = app
+            .wrap()
+            .query_wasm_smart(addr.clone(), &QueryMsg::ReceiptPayer { id })
+            .unwrap();
+        assert_eq!(
+            res,
+            ReceiptPayerResponse {
+                payer: Some(Addr::unchecked(payer))
+            }
+        );
+    }
+}
+
+#[test]
+pub fn test_payer_bound_ids_in_case() {
+    let (mut app, addr, _) = instantiate();
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        addr.clone(),
+        &ExecuteMsg::UpdateIdRules {
+            id_rules: Some(IdRules {
+                min_length: None,
+                max_length: None,
+                charset: None,
+                prefix: None,
+                case: Some(IdCase::Upper),
+                bind_to_payer: true,
+            }),
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(PAYER),
+        addr.clone(),
+        &ExecuteMsg::Pay {
+            id: "order-1".to_string(),
+            idempotency_key: None,
+        },
+        &coins(1, NATIVE_DENOM),
+    )
+    .unwrap();
+
+    // Ensure the bound ID is stored in the configured case.
+    let bound_id = crate::state::payer_bound_id("ORDER-1", &Addr::unchecked(PAYER)).to_uppercase();
+    let res: ListIdsForPayerResponse = app
+        .wrap()
+        .query_wasm_smart(
+            addr.clone(),
+            &QueryMsg::ListIdsForPayer {
+                payer: PAYER.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.ids, vec![bound_id.clone()]);
+
+    // Ensure the bound ID is found in either case.
+    for id in [bound_id.clone(), bound_id.to_lowercase()] {
+        let res: ListPaymentsToIdResponse = app
+            .wrap()
+            .query_wasm_smart(
+                addr.clone(),
+                &QueryMsg::ListPaymentsToId {
+                    id,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.payments.len(), 1);
+    }
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        addr.clone(),
+        &ExecuteMsg::VoidPayment {
+            id: bound_id.to_lowercase(),
+            payment_index: 0,
+            reason: "duplicate".to_string(),
+            restock: false,
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        addr,
+        &ExecuteMsg::CloseReceipt {
+            id: bound_id,
+            reason: "refunded".to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+#[test]
+pub fn test_payer_bound_ids_held_by_payer() {
+    let (mut app, addr, _) = instantiate();
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        addr.clone(),
+        &ExecuteMsg::UpdateIdRules {
+            id_rules: Some(IdRules {
+                min_length: None,
+                max_length: None,
+                charset: None,
+                prefix: None,
+                case: None,
+                bind_to_payer: true,
+            }),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let payment_count = |app: &App, id: &str| {
+        let res: ListPaymentsToIdResponse = app
+            .wrap()
+            .query_wasm_smart(
+                addr.clone(),
+                &QueryMsg::ListPaymentsToId {
+                    id: id.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        res.payments.len()
+    };
+
+    // Ensure a receipt assigned to the payer is paid as-is, not bound again.
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        addr.clone(),
+        &ExecuteMsg::AssignPayer {
+            id: "assigned".to_string(),
+            payer: Some(PAYER.to_string()),
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(PAYER),
+        addr.clone(),
+        &ExecuteMsg::Pay {
+            id: "assigned".to_string(),
+            idempotency_key: None,
+        },
+        &coins(1, NATIVE_DENOM),
+    )
+    .unwrap();
+    assert_eq!(payment_count(&app, "assigned"), 1);
+    let assigned_id = crate::state::payer_bound_id("assigned", &Addr::unchecked(PAYER));
+    assert_eq!(payment_count(&app, &assigned_id), 0);
+
+    // Ensure a bound receipt transferred to another payer is topped up as-is
+    // by the new payer.
+    app.execute_contract(
+        Addr::unchecked(PAYER),
+        addr.clone(),
+        &ExecuteMsg::Pay {
+            id: RECEIPT_ID.to_string(),
+            idempotency_key: None,
+        },
+        &coins(1, NATIVE_DENOM),
+    )
+    .unwrap();
+    let payer_id = crate::state::payer_bound_id(RECEIPT_ID, &Addr::unchecked(PAYER));
+    app.execute_contract(
+        Addr::unchecked(PAYER),
+        addr.clone(),
+        &ExecuteMsg::TransferReceipt {
+            id: payer_id.clone(),
+            new_payer: OTHER_PAYER.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(OTHER_PAYER),
+        addr.clone(),
+        &ExecuteMsg::Pay {
+            id: payer_id.clone(),
+            idempotency_key: None,
+        },
+        &coins(1, NATIVE_DENOM),
+    )
+    .unwrap();
+    assert_eq!(payment_count(&app, &payer_id), 2);
+    let rebound_id = crate::state::payer_bound_id(&payer_id, &Addr::unchecked(OTHER_PAYER));
+    assert_eq!(payment_count(&app, &rebound_id), 0);
+
+    // Ensure the previous payer can no longer pay it, and binds it instead.
+    let res: CanonicalIdResponse = app
+        .wrap()
+        .query_wasm_smart(
+            addr.clone(),
+            &QueryMsg::CanonicalId {
+                id: payer_id.clone(),
+                payer: PAYER.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        CanonicalIdResponse {
+            id: crate::state::payer_bound_id(&payer_id, &Addr::unchecked(PAYER))
+        }
+    );
+}
+
+#[test]
+pub fn test_close_and_reopen_receipt() {
+    let (mut app, addr, cw20_addr) = instantiate();
+
+    // Pay before closing.
+    app.execute_contract(
+        Addr::unchecked(PAYER),
+        addr.clone(),
+        &ExecuteMsg::Pay {
+            id: RECEIPT_ID.to_string(),
+            idempotency_key: None,
+        },
+        &coins(1, NATIVE_DENOM),
+    )
+    .unwrap();
+
+    // Ensure non-owner cannot close.
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(PAYER),
+            addr.clone(),
+            &ExecuteMsg::CloseReceipt {
+                id: RECEIPT_ID.to_string(),
+                reason: "shipped".to_string(),
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::Ownable(cw_ownable::OwnershipError::NotOwner)
+    );
+
+    // Ensure cannot reopen an open receipt.
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            addr.clone(),
+            &ExecuteMsg::ReopenReceipt {
+                id: RECEIPT_ID.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::ReceiptNotClosed {
+            id: RECEIPT_ID.to_string()
+        }
+    );
+
+    // Close receipt.
+    app.update_block(|block| block.height += 1);
+    let block = app.block_info();
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        addr.clone(),
+        &ExecuteMsg::CloseReceipt {
+            id: RECEIPT_ID.to_string(),
+            reason: "shipped".to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Ensure closure is returned.
+    let res: ReceiptResponse = app
+        .wrap()
+        .query_wasm_smart(
+            addr.clone(),
+            &QueryMsg::Receipt {
+                id: RECEIPT_ID.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        ReceiptResponse {
+            id: RECEIPT_ID.to_string(),
+            payer: Some(Addr::unchecked(PAYER)),
+            payment_count: 1,
+            creation: None,
+            closure: Some(Closure {
+                reason: "shipped".to_string(),
+                closed_by: Addr::unchecked(OWNER),
+                height: block.height,
+                time: block.time,
+            }),
+            escrow: None,
+        }
+    );
+
+    // Ensure closed receipt cannot be paid with native or cw20 tokens.
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(PAYER),
+            addr.clone(),
+            &ExecuteMsg::Pay {
+                id: RECEIPT_ID.to_string(),
+                idempotency_key: None,
+            },
+            &coins(1, NATIVE_DENOM),
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::ReceiptClosed {
+            id: RECEIPT_ID.to_string()
+        }
+    );
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(PAYER),
+            cw20_addr,
+            &cw20::Cw20ExecuteMsg::Send {
+                contract: addr.to_string(),
+                amount: Uint128::new(1),
+                msg: to_binary(&Cw20ReceiverMsg::Pay {
+                    id: RECEIPT_ID.to_string(),
+                    idempotency_key: None,
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::ReceiptClosed {
+            id: RECEIPT_ID.to_string()
+        }
+    );
+
+    // Ensure cannot close twice.
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            addr.clone(),
+            &ExecuteMsg::CloseReceipt {
+                id: RECEIPT_ID.to_string(),
+                reason: "cancelled".to_string(),
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::ReceiptClosed {
+            id: RECEIPT_ID.to_string()
+        }
+    );
+
+    // Reopen and pay again.
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        addr.clone(),
+        &ExecuteMsg::ReopenReceipt {
+            id: RECEIPT_ID.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(PAYER),
+        addr.clone(),
+        &ExecuteMsg::Pay {
+            id: RECEIPT_ID.to_string(),
+            idempotency_key: None,
+        },
+        &coins(1, NATIVE_DENOM),
+    )
+    .unwrap();
+
+    let res: ReceiptResponse = app
+        .wrap()
+        .query_wasm_smart(
+            addr,
+            &QueryMsg::Receipt {
+                id: RECEIPT_ID.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.payment_count, 2);
+    assert_eq!(res.closure, None);
+}
+
+#[test]
+pub fn test_transfer_receipt() {
+    let (mut app, addr, _) = instantiate();
+    let new_payer = "new_payer";
+
+    // Ensure receipt without payer cannot be transferred.
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(PAYER),
+            addr.clone(),
+            &ExecuteMsg::TransferReceipt {
+                id: RECEIPT_ID.to_string(),
+                new_payer: new_payer.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::NoReceiptPayer {
+            id: RECEIPT_ID.to_string()
+        }
+    );
+
+    app.execute_contract(
+        Addr::unchecked(PAYER),
+        addr.clone(),
+        &ExecuteMsg::Pay {
+            id: RECEIPT_ID.to_string(),
+            idempotency_key: None,
+        },
+        &coins(2, NATIVE_DENOM),
+    )
+    .unwrap();
+
+    // Ensure others cannot transfer.
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(OTHER_PAYER),
+            addr.clone(),
+            &ExecuteMsg::TransferReceipt {
+                id: RECEIPT_ID.to_string(),
+                new_payer: OTHER_PAYER.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::Unauthorized);
+
+    // Payer transfers to new wallet.
+    let block = app.block_info();
+    let res = app
+        .execute_contract(
+            Addr::unchecked(PAYER),
+            addr.clone(),
+            &ExecuteMsg::TransferReceipt {
+                id: RECEIPT_ID.to_string(),
+                new_payer: new_payer.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(res.has_event(
+        &cosmwasm_std::Event::new("wasm-transfer_receipt")
+            .add_attribute("from", PAYER)
+            .add_attribute("to", new_payer)
+    ));
+
+    // Ensure old payer can no longer pay, and new payer can.
+    app.sudo(cw_multi_test::SudoMsg::Bank(BankSudo::Mint {
+        to_address: new_payer.to_string(),
+        amount: coins(10, NATIVE_DENOM),
+    }))
+    .unwrap();
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(PAYER),
+            addr.clone(),
+            &ExecuteMsg::Pay {
+                id: RECEIPT_ID.to_string(),
+                idempotency_key: None,
+            },
+            &coins(1, NATIVE_DENOM),
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::UnauthorizedPayer);
+    app.execute_contract(
+        Addr::unchecked(new_payer),
+        addr.clone(),
+        &ExecuteMsg::Pay {
+            id: RECEIPT_ID.to_string(),
+            idempotency_key: None,
+        },
+        &coins(3, NATIVE_DENOM),
+    )
+    .unwrap();
+
+    // Ensure receipt listed only for new payer.
+    for (payer, ids) in [(PAYER, vec![]), (new_payer, vec![RECEIPT_ID.to_string()])] {
+        let res: ListIdsForPayerResponse = app
+            .wrap()
+            .query_wasm_smart(
+                addr.clone(),
+                &QueryMsg::ListIdsForPayer {
+                    payer: payer.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res, ListIdsForPayerResponse { ids });
+    }
+
+    // Ensure totals stay attributed to whoever paid them.
+    for (payer, amount) in [(PAYER, 2u128), (new_payer, 3)] {
+        let res: ListTotalsPaidByPayerResponse = app
+            .wrap()
+            .query_wasm_smart(
+                addr.clone(),
+                &QueryMsg::ListTotalsPaidByPayer {
+                    payer: payer.to_string(),
+                    start_after: None,
+                    limit: None,
+                    height: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            res.totals,
+            vec![Total {
+                denom: CheckedDenom::Native(NATIVE_DENOM.to_string()),
+                amount: Uint128::new(amount),
+            }]
+        );
+    }
+
+    // Owner transfers back.
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        addr.clone(),
+        &ExecuteMsg::TransferReceipt {
+            id: RECEIPT_ID.to_string(),
+            new_payer: PAYER.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Ensure history kept.
+    let res: ListReceiptTransfersResponse = app
+        .wrap()
+        .query_wasm_smart(
+            addr,
+            &QueryMsg::ListReceiptTransfers {
+                id: RECEIPT_ID.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        ListReceiptTransfersResponse {
+            transfers: vec![
+                ReceiptTransfer {
+                    transfer_id: 0,
+                    transfer: Transfer {
+                        from: Addr::unchecked(PAYER),
+                        to: Addr::unchecked(new_payer),
+                        transferred_by: Addr::unchecked(PAYER),
+                        height: block.height,
+                        time: block.time,
+                    },
+                },
+                ReceiptTransfer {
+                    transfer_id: 1,
+                    transfer: Transfer {
+                        from: Addr::unchecked(new_payer),
+                        to: Addr::unchecked(PAYER),
+                        transferred_by: Addr::unchecked(OWNER),
+                        height: block.height,
+                        time: block.time,
+                    },
+                },
+            ]
+        }
+    );
+}
+
+#[test]
+pub fn test_void_payment() {
+    let (mut app, addr, _) = instantiate();
+
+    // Pay twice.
+    for amount in [2u128, 3] {
+        app.execute_contract(
+            Addr::unchecked(PAYER),
+            addr.clone(),
+            &ExecuteMsg::Pay {
+                id: RECEIPT_ID.to_string(),
+                idempotency_key: None,
+            },
+            &coins(amount, NATIVE_DENOM),
+        )
+        .unwrap();
+    }
+
+    // Ensure non-owner cannot void.
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(PAYER),
+            addr.clone(),
+            &ExecuteMsg::VoidPayment {
+                id: RECEIPT_ID.to_string(),
+                payment_index: 0,
+                reason: "test payment".to_string(),
+                restock: false,
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::Ownable(cw_ownable::OwnershipError::NotOwner)
+    );
+
+    // Ensure missing payment cannot be voided.
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            addr.clone(),
+            &ExecuteMsg::VoidPayment {
+                id: RECEIPT_ID.to_string(),
+                payment_index: 2,
+                reason: "test payment".to_string(),
+                restock: false,
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::PaymentNotFound {
+            id: RECEIPT_ID.to_string(),
+            payment_index: 2
+        }
+    );
+
+    // Void first payment.
+    let block = app.block_info();
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        addr.clone(),
+        &ExecuteMsg::VoidPayment {
+            id: RECEIPT_ID.to_string(),
+            payment_index: 0,
+            reason: "test payment".to_string(),
+            restock: false,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Ensure cannot void twice.
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            addr.clone(),
+            &ExecuteMsg::VoidPayment {
+                id: RECEIPT_ID.to_string(),
+                payment_index: 0,
+                reason: "test payment".to_string(),
+                restock: false,
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::PaymentAlreadyVoided {
+            id: RECEIPT_ID.to_string(),
+            payment_index: 0
+        }
+    );
+
+    // Ensure payment flagged in both list queries.
+    let void = Void {
+        reason: "test payment".to_string(),
+        voided_by: Addr::unchecked(OWNER),
+        height: block.height,
+        time: block.time,
+    };
+    let res: ListPaymentsToIdResponse = app
+        .wrap()
+        .query_wasm_smart(
+            addr.clone(),
+            &QueryMsg::ListPaymentsToId {
+                id: RECEIPT_ID.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res.payments
+            .iter()
+            .map(|p| p.payment.voided.clone())
+            .collect::<Vec<_>>(),
+        vec![Some(void.clone()), None]
+    );
+    let res: ListPaymentsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            addr.clone(),
+            &QueryMsg::ListPayments {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res.payments
+            .iter()
+            .map(|p| p.payment.voided.clone())
+            .collect::<Vec<_>>(),
+        vec![Some(void), None]
+    );
+
+    // Ensure totals exclude voided payment.
+    let res: ListTotalsPaidToIdResponse = app
+        .wrap()
+        .query_wasm_smart(
+            addr.clone(),
+            &QueryMsg::ListTotalsPaidToId {
+                id: RECEIPT_ID.to_string(),
+                start_after: None,
+                limit: None,
+                height: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res.totals,
+        vec![Total {
+            denom: CheckedDenom::Native(NATIVE_DENOM.to_string()),
+            amount: Uint128::new(3),
+        }]
+    );
+    let res: ListTotalsPaidByPayerResponse = app
+        .wrap()
+        .query_wasm_smart(
+            addr.clone(),
+            &QueryMsg::ListTotalsPaidByPayer {
+                payer: PAYER.to_string(),
+                start_after: None,
+                limit: None,
+                height: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res.totals,
+        vec![Total {
+            denom: CheckedDenom::Native(NATIVE_DENOM.to_string()),
+            amount: Uint128::new(3),
+        }]
+    );
+
+    // Void second payment and ensure empty totals are removed.
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        addr.clone(),
+        &ExecuteMsg::VoidPayment {
+            id: RECEIPT_ID.to_string(),
+            payment_index: 1,
+            reason: "mistake".to_string(),
+            restock: false,
+        },
+        &[],
+    )
+    .unwrap();
+    let res: ListTotalsPaidToIdResponse = app
+        .wrap()
+        .query_wasm_smart(
+            addr.clone(),
+            &QueryMsg::ListTotalsPaidToId {
+                id: RECEIPT_ID.to_string(),
+                start_after: None,
+                limit: None,
+                height: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res, ListTotalsPaidToIdResponse { totals: vec![] });
+
+    // Ensure audit log records both voids.
+    let res: ListAdminActionsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            addr,
+            &QueryMsg::ListAdminActions {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        ListAdminActionsResponse {
+            actions: vec![
+                AdminLogItem {
+                    entry_id: 0,
+                    entry: AdminLogEntry {
+                        actor: Addr::unchecked(OWNER),
+                        action: AdminAction::VoidPayment {
+                            id: RECEIPT_ID.to_string(),
+                            payment_index: 0,
+                            reason: "test payment".to_string(),
+                            restock: false,
+                        },
+                        height: block.height,
+                        time: block.time,
+                    },
+                },
+                AdminLogItem {
+                    entry_id: 1,
+                    entry: AdminLogEntry {
+                        actor: Addr::unchecked(OWNER),
+                        action: AdminAction::VoidPayment {
+                            id: RECEIPT_ID.to_string(),
+                            payment_index: 1,
+                            reason: "mistake".to_string(),
+                            restock: false,
+                        },
+                        height: block.height,
+                        time: block.time,
+                    },
+                },
+            ]
+        }
+    );
+}
+
+#[test]
+pub fn test_admin_log() {
+    let (mut app, addr, _) = instantiate();
+    let block = app.block_info();
+
+    // Update output.
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        addr.clone(),
+        &ExecuteMsg::UpdateOutput {
+            output: "new_output".to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Propose and accept new owner.
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        addr.clone(),
+        &ExecuteMsg::UpdateOwnership(cw_ownable::Action::TransferOwnership {
+            new_owner: "new_owner".to_string(),
+            expiry: None,
+        }),
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked("new_owner"),
+        addr.clone(),
+        &ExecuteMsg::UpdateOwnership(cw_ownable::Action::AcceptOwnership),
+        &[],
+    )
+    .unwrap();
+
+    // Ensure failed admin actions are not logged.
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        addr.clone(),
+        &ExecuteMsg::UpdateOutput {
+            output: OUTPUT.to_string(),
+        },
+        &[],
+    )
+    .unwrap_err();
+
+    // Ensure each action is logged with old and new values.
+    let entry = |action| AdminLogEntry {
+        actor: Addr::unchecked(OWNER),
+        action,
+        height: block.height,
+        time: block.time,
+    };
+    let res: ListAdminActionsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            addr.clone(),
+            &QueryMsg::ListAdminActions {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        ListAdminActionsResponse {
+            actions: vec![
+                AdminLogItem {
+                    entry_id: 0,
+                    entry: entry(AdminAction::UpdateOutput {
+                        old: Addr::unchecked(OUTPUT),
+                        new: Addr::unchecked("new_output"),
+                    }),
+                },
+                AdminLogItem {
+                    entry_id: 1,
+                    entry: entry(AdminAction::UpdateOwnership {
+                        old: cw_ownable::Ownership {
+                            owner: Some(Addr::unchecked(OWNER)),
+                            pending_owner: None,
+                            pending_expiry: None,
+                        },
+                        new: cw_ownable::Ownership {
+                            owner: Some(Addr::unchecked(OWNER)),
+                            pending_owner: Some(Addr::unchecked("new_owner")),
+                            pending_expiry: None,
+                        },
+                    }),
+                },
+                AdminLogItem {
+                    entry_id: 2,
+                    entry: AdminLogEntry {
+                        actor: Addr::unchecked("new_owner"),
+                        ..entry(AdminAction::UpdateOwnership {
+                            old: cw_ownable::Ownership {
+                                owner: Some(Addr::unchecked(OWNER)),
+                                pending_owner: Some(Addr::unchecked("new_owner")),
+                                pending_expiry: None,
+                            },
+                            new: cw_ownable::Ownership {
+                                owner: Some(Addr::unchecked("new_owner")),
+                                pending_owner: None,
+                                pending_expiry: None,
+                            },
+                        })
+                    },
+                },
+            ]
+        }
+    );
+
+    // Ensure log paginates.
+    let res: ListAdminActionsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            addr,
+            &QueryMsg::ListAdminActions {
+                start_after: Some(0),
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res.actions
+            .into_iter()
+            .map(|item| item.entry_id)
+            .collect::<Vec<_>>(),
+        vec![1]
+    );
+}
+
+#[test]
+pub fn test_has_paid() {
+    let (mut app, addr, cw20_addr) = instantiate();
+    let receipt = crate::helpers::CwReceiptContract(addr.clone());
+    let requirements = vec![
+        Total {
+            denom: CheckedDenom::Native(NATIVE_DENOM.to_string()),
+            amount: Uint128::new(5),
+        },
+        Total {
+            denom: CheckedDenom::Cw20(cw20_addr.clone()),
+            amount: Uint128::new(2),
+        },
+    ];
+
+    // Ensure nothing paid yet.
+    let res = receipt
+        .has_paid(&app.wrap(), RECEIPT_ID, PAYER, requirements.clone())
+        .unwrap();
+    assert_eq!(
+        res,
+        HasPaidResponse {
+            paid: false,
+            missing: requirements.clone(),
+        }
+    );
+
+    // Pay part of the native requirement and all of the cw20 requirement.
+    app.execute_contract(
+        Addr::unchecked(PAYER),
+        addr.clone(),
+        &ExecuteMsg::Pay {
+            id: RECEIPT_ID.to_string(),
+            idempotency_key: None,
+        },
+        &coins(3, NATIVE_DENOM),
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(PAYER),
+        cw20_addr,
+        &cw20::Cw20ExecuteMsg::Send {
+            contract: addr.to_string(),
+            amount: Uint128::new(2),
+            msg: to_binary(&Cw20ReceiverMsg::Pay {
+                id: RECEIPT_ID.to_string(),
+                idempotency_key: None,
+            })
+            .unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res = receipt
+        .has_paid(&app.wrap(), RECEIPT_ID, PAYER, requirements.clone())
+        .unwrap();
+    assert_eq!(
+        res,
+        HasPaidResponse {
+            paid: false,
+            missing: vec![Total {
+                denom: CheckedDenom::Native(NATIVE_DENOM.to_string()),
+                amount: Uint128::new(2),
+            }],
+        }
+    );
+
+    // Pay the rest.
+    app.execute_contract(
+        Addr::unchecked(PAYER),
+        addr.clone(),
+        &ExecuteMsg::Pay {
+            id: RECEIPT_ID.to_string(),
+            idempotency_key: None,
+        },
+        &coins(2, NATIVE_DENOM),
+    )
+    .unwrap();
+    let res = receipt
+        .has_paid(&app.wrap(), RECEIPT_ID, PAYER, requirements.clone())
+        .unwrap();
+    assert_eq!(
+        res,
+        HasPaidResponse {
+            paid: true,
+            missing: vec![],
+        }
+    );
+
+    // Ensure other payers do not get credit for the receipt.
+    let res = receipt
+        .has_paid(&app.wrap(), RECEIPT_ID, OTHER_PAYER, requirements.clone())
+        .unwrap();
+    assert_eq!(
+        res,
+        HasPaidResponse {
+            paid: false,
+            missing: requirements.clone(),
+        }
+    );
+
+    // Once IDs are bound to payers, the ID is checked as the payer paid it.
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        addr.clone(),
+        &ExecuteMsg::UpdateIdRules {
+            id_rules: Some(IdRules {
+                min_length: None,
+                max_length: None,
+                charset: None,
+                prefix: None,
+                case: None,
+                bind_to_payer: true,
+            }),
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(OTHER_PAYER),
+        addr,
+        &ExecuteMsg::Pay {
+            id: RECEIPT_ID.to_string(),
+            idempotency_key: None,
+        },
+        &coins(5, NATIVE_DENOM),
+    )
+    .unwrap();
+    let native_requirement = vec![requirements[0].clone()];
+    for payer in [PAYER, OTHER_PAYER] {
+        let res = receipt
+            .has_paid(&app.wrap(), RECEIPT_ID, payer, native_requirement.clone())
+            .unwrap();
+        assert_eq!(
+            res,
+            HasPaidResponse {
+                paid: true,
+                missing: vec![],
+            }
+        );
+    }
+}
+
+#[test]
+pub fn test_embedded_ledger() {
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+    use cosmwasm_std::{BankMsg, CosmosMsg};
+
+    use crate::ledger::{LedgerNamespaces, ReceiptLedger};
+    use crate::state::{Void, LEDGER};
+
+    // A contract embedding the ledger next to the default one must not share
+    // any storage with it.
+    const OTHER_LEDGER: ReceiptLedger = ReceiptLedger::new(LedgerNamespaces {
+        denoms: "o_denoms",
+        denom_ids: "o_denom_ids",
+        denom_count: "o_denom_count",
+        payments: "o_payments",
+        payment_counts: "o_payment_counts",
+        receipt_totals: "o_receipt_totals",
+        receipt_totals_checkpoints: "o_receipt_totals__checkpoints",
+        receipt_totals_changelog: "o_receipt_totals__changelog",
+        receipt_payers: "o_receipt_payers",
+        payer_receipts: "o_payer_receipts",
+        payer_totals: "o_payer_totals",
+        payer_totals_checkpoints: "o_payer_totals__checkpoints",
+        payer_totals_changelog: "o_payer_totals__changelog",
+        current_period: "o_current_period",
+        periods: "o_periods",
+        period_totals: "o_period_totals",
+        period_payer_totals: "o_period_payer_totals",
+        period_voided: "o_period_voided",
+    });
+
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let payer = Addr::unchecked(PAYER);
+    let denom = CheckedDenom::Native(NATIVE_DENOM.to_string());
+
+    for amount in [100u128, 50] {
+        OTHER_LEDGER
+            .record(
+                deps.as_mut().storage,
+                &env.block,
+                RECEIPT_ID,
+                &payer,
+                &denom,
+                Uint128::new(amount),
+            )
+            .unwrap();
+    }
+
+    // Only the first payer may pay the receipt.
+    let err = OTHER_LEDGER
+        .record(
+            deps.as_mut().storage,
+            &env.block,
+            RECEIPT_ID,
+            &Addr::unchecked(OTHER_PAYER),
+            &denom,
+            Uint128::new(1),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::UnauthorizedPayer);
+
+    assert_eq!(
+        OTHER_LEDGER
+            .receipt_total(deps.as_ref().storage, RECEIPT_ID, &denom)
+            .unwrap(),
+        Uint128::new(150)
+    );
+    assert_eq!(
+        OTHER_LEDGER
+            .list_payer_totals(deps.as_ref().storage, &payer, None, None, None)
+            .unwrap(),
+        vec![Total {
+            denom: denom.clone(),
+            amount: Uint128::new(150)
+        }]
+    );
+    assert_eq!(
+        OTHER_LEDGER
+            .list_payer_receipts(deps.as_ref().storage, &payer, None, None)
+            .unwrap(),
+        vec![RECEIPT_ID.to_string()]
+    );
+    // Nothing leaked into the default ledger.
+    assert_eq!(
+        LEDGER
+            .list_payments(deps.as_ref().storage, None, None)
+            .unwrap(),
+        vec![]
+    );
+    assert_eq!(
+        LEDGER.payer(deps.as_ref().storage, RECEIPT_ID).unwrap(),
+        None
+    );
+
+    // Refunding voids the payment, removes it from the totals, and sends the
+    // amount back to the payer.
+    let msg = OTHER_LEDGER
+        .refund(
+            deps.as_mut().storage,
+            RECEIPT_ID,
+            1,
+            Void {
+                reason: "returned".to_string(),
+                voided_by: Addr::unchecked(OWNER),
+                height: env.block.height,
+                time: env.block.time,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: PAYER.to_string(),
+            amount: coins(50, NATIVE_DENOM),
+        })
+    );
+    assert_eq!(
+        OTHER_LEDGER
+            .list_receipt_totals(deps.as_ref().storage, RECEIPT_ID, None, None, None)
+            .unwrap(),
+        vec![Total {
+            denom,
+            amount: Uint128::new(100)
+        }]
+    );
+    let payments = OTHER_LEDGER
+        .list_receipt_payments(deps.as_ref().storage, RECEIPT_ID, None, None)
+        .unwrap();
+    assert_eq!(payments.len(), 2);
+    assert!(payments[0].payment.voided.is_none());
+    assert_eq!(
+        payments[1]
+            .payment
+            .voided
+            .as_ref()
+            .map(|v| v.reason.as_str()),
+        Some("returned")
+    );
+}
+
+#[test]
+pub fn test_harness() {
+    let mut harness = ReceiptAppBuilder::new()
+        .with_native_balance(PAYER, coins(10, NATIVE_DENOM))
+        .with_cw20(vec![cw20::Cw20Coin {
+            address: PAYER.to_string(),
+            amount: Uint128::new(10),
+        }])
+        .with_cw20(vec![cw20::Cw20Coin {
+            address: OTHER_PAYER.to_string(),
+            amount: Uint128::new(10),
+        }])
+        .build();
+    let cw20 = harness.cw20s[0].clone();
+    let other_cw20 = harness.cw20s[1].clone();
+
+    harness
+        .pay_native(PAYER, RECEIPT_ID, &coins(4, NATIVE_DENOM))
+        .unwrap();
+    harness.pay_cw20(PAYER, &cw20, RECEIPT_ID, 6).unwrap();
+    // Only the first payer may pay the receipt.
+    let err: ContractError = harness
+        .pay_cw20(OTHER_PAYER, &other_cw20, RECEIPT_ID, 1)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::UnauthorizedPayer);
+
+    let receipt = harness.query_receipt(RECEIPT_ID);
+    assert_eq!(receipt.payer, Some(Addr::unchecked(PAYER)));
+    assert_eq!(receipt.payment_count, 2);
+    harness.assert_receipt_totals(
+        RECEIPT_ID,
+        &[native_total(NATIVE_DENOM, 4), cw20_total(&cw20, 6)],
+    );
+    harness.assert_payer_totals(
+        PAYER,
+        &[native_total(NATIVE_DENOM, 4), cw20_total(&cw20, 6)],
+    );
+    harness.assert_payer_totals(OTHER_PAYER, &[]);
+
+    // Funds were forwarded to the output.
+    assert_eq!(
+        harness
+            .app
+            .wrap()
+            .query_balance(OUTPUT, NATIVE_DENOM)
+            .unwrap()
+            .amount,
+        Uint128::new(4)
+    );
+}
+
+#[test]
+pub fn test_pay_response_data() {
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coin, from_binary, ContractResult, SystemResult};
+
+    let mut harness = ReceiptAppBuilder::new()
+        .with_native_balance(PAYER, vec![coin(10, NATIVE_DENOM), coin(10, "uother")])
+        .build();
+
+    harness
+        .pay_native(PAYER, RECEIPT_ID, &coins(3, NATIVE_DENOM))
+        .unwrap();
+    let res = harness
+        .pay_native(
+            PAYER,
+            RECEIPT_ID,
+            &[coin(2, NATIVE_DENOM), coin(5, "uother")],
+        )
+        .unwrap();
+    let data: PayResponse = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(
+        data,
+        PayResponse {
+            payments: vec![
+                RecordedPayment {
+                    receipt_id: RECEIPT_ID.to_string(),
+                    receipt_payment_id: 1,
+                    denom: CheckedDenom::Native(NATIVE_DENOM.to_string()),
+                    amount: Uint128::new(2),
+                },
+                RecordedPayment {
+                    receipt_id: RECEIPT_ID.to_string(),
+                    receipt_payment_id: 2,
+                    denom: CheckedDenom::Native("uother".to_string()),
+                    amount: Uint128::new(5),
+                },
+            ],
+            totals: vec![native_total(NATIVE_DENOM, 5), native_total("uother", 5)],
+        }
+    );
+
+    // The cw20 token is the caller of `Receive`, so check its data directly.
+    let mut deps = mock_dependencies();
+    deps.querier.update_wasm(|_| {
+        SystemResult::Ok(ContractResult::Ok(
+            to_binary(&cw20::TokenInfoResponse {
+                name: "Test".to_string(),
+                symbol: "TEST".to_string(),
+                decimals: 6,
+                total_supply: Uint128::new(10),
+            })
+            .unwrap(),
+        ))
+    });
+    crate::contract::instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        crate::msg::InstantiateMsg {
+            owner: Some(OWNER.to_string()),
+            output: OUTPUT.to_string(),
+            id_rules: None,
+            forwarding: None,
+        },
+    )
+    .unwrap();
+    let res = crate::contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("cw20", &[]),
+        ExecuteMsg::Receive(cw20::Cw20ReceiveMsg {
+            sender: PAYER.to_string(),
+            amount: Uint128::new(7),
+            msg: to_binary(&Cw20ReceiverMsg::Pay {
+                id: RECEIPT_ID.to_string(),
+                idempotency_key: None,
+            })
+            .unwrap(),
+        }),
+    )
+    .unwrap();
+    let data: PayResponse = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(
+        data,
+        PayResponse {
+            payments: vec![RecordedPayment {
+                receipt_id: RECEIPT_ID.to_string(),
+                receipt_payment_id: 0,
+                denom: CheckedDenom::Cw20(Addr::unchecked("cw20")),
+                amount: Uint128::new(7),
+            }],
+            totals: vec![cw20_total(&Addr::unchecked("cw20"), 7)],
+        }
+    );
+}
+
+#[test]
+pub fn test_generated_ids() {
+    use cosmwasm_std::from_binary;
+
+    let mut harness = ReceiptAppBuilder::new()
+        .with_id_rules(IdRules {
+            min_length: None,
+            max_length: None,
+            charset: Some(IdCharset::Alphanumeric),
+            prefix: None,
+            case: None,
+            bind_to_payer: true,
+        })
+        .with_native_balance(PAYER, coins(10, NATIVE_DENOM))
+        .with_native_balance(OTHER_PAYER, coins(10, NATIVE_DENOM))
+        .with_cw20(vec![cw20::Cw20Coin {
+            address: PAYER.to_string(),
+            amount: Uint128::new(10),
+        }])
+        .build();
+    let cw20 = harness.cw20s[0].clone();
+
+    // Users cannot claim IDs with the generated prefix.
+    let err: ContractError = harness
+        .pay_native(PAYER, "#0", &coins(1, NATIVE_DENOM))
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::InvalidReceiptId {
+            reason: "prefix # is reserved for generated IDs".to_string()
+        }
+    );
+    let err: ContractError = harness
+        .execute(
+            OWNER,
+            &ExecuteMsg::AssignPayer {
+                id: "#0".to_string(),
+                payer: Some(PAYER.to_string()),
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert!(matches!(err, ContractError::InvalidReceiptId { .. }));
+
+    // Create a receipt. Its ID is exempt from the ID rules and not bound to
+    // the payer, so it is reserved for its creator instead.
+    let res = harness
+        .execute(
+            PAYER,
+            &ExecuteMsg::CreateReceipt {
+                metadata: Some("order".to_string()),
+            },
+            &[],
+        )
+        .unwrap();
+    let created: CreateReceiptResponse = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(created.id, "#0");
+    assert!(res.events.iter().any(|event| event
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "id" && attr.value == "#0")));
+
+    let err: ContractError = harness
+        .pay_native(OTHER_PAYER, "#0", &coins(2, NATIVE_DENOM))
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::UnauthorizedPayer);
+    harness
+        .pay_native(PAYER, "#0", &coins(2, NATIVE_DENOM))
+        .unwrap();
+    let receipt = harness.query_receipt("#0");
+    assert_eq!(receipt.payer, Some(Addr::unchecked(PAYER)));
+    assert_eq!(receipt.payment_count, 1);
+    let creation = receipt.creation.unwrap();
+    assert_eq!(creation.creator, Addr::unchecked(PAYER));
+    assert_eq!(creation.metadata, Some("order".to_string()));
+
+    // Allocate and pay in one call.
+    let res = harness
+        .execute(PAYER, &ExecuteMsg::PayNew {}, &coins(3, NATIVE_DENOM))
+        .unwrap();
+    let data: PayResponse = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(data.payments[0].receipt_id, "#1");
+    harness.assert_receipt_totals("#1", &[native_total(NATIVE_DENOM, 3)]);
+
+    // Paying nothing allocates nothing.
+    let err: ContractError = harness
+        .execute(PAYER, &ExecuteMsg::PayNew {}, &[])
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::MissingPayment);
+
+    // And with cw20 tokens.
+    harness
+        .app
+        .execute_contract(
+            Addr::unchecked(PAYER),
+            cw20.clone(),
+            &cw20::Cw20ExecuteMsg::Send {
+                contract: harness.receipt.to_string(),
+                amount: Uint128::new(4),
+                msg: to_binary(&Cw20ReceiverMsg::PayNew {}).unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+    let receipt = harness.query_receipt("#2");
+    assert_eq!(receipt.payer, Some(Addr::unchecked(PAYER)));
+    assert_eq!(receipt.creation.unwrap().metadata, None);
+    harness.assert_receipt_totals("#2", &[cw20_total(&cw20, 4)]);
+}
+
+const BLOCKED_OUTPUT: &str = "blocked_output";
+
+// A cw20 token that refuses transfers to `BLOCKED_OUTPUT`.
+fn blocking_cw20_execute(
+    deps: cosmwasm_std::DepsMut,
+    env: cosmwasm_std::Env,
+    info: cosmwasm_std::MessageInfo,
+    msg: cw20_base::msg::ExecuteMsg,
+) -> Result<cosmwasm_std::Response, cw20_base::ContractError> {
+    if let cw20_base::msg::ExecuteMsg::Transfer { recipient, .. } = &msg {
+        if recipient == BLOCKED_OUTPUT {
+            return Err(cw20_base::ContractError::Unauthorized {});
+        }
+    }
+    cw20_base::contract::execute(deps, env, info, msg)
+}
+
+// Instantiate a blocking cw20 token, giving PAYER a balance of 10.
+fn instantiate_blocking_cw20(harness: &mut ReceiptApp) -> Addr {
+    use cw_multi_test::ContractWrapper;
+
+    let code_id = harness.app.store_code(Box::new(ContractWrapper::new(
+        blocking_cw20_execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    )));
+    harness
+        .app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked(OWNER),
+            &cw20_base::msg::InstantiateMsg {
+                name: "Test".to_string(),
+                symbol: "TEST".to_string(),
+                decimals: 6,
+                initial_balances: vec![cw20::Cw20Coin {
+                    address: PAYER.to_string(),
+                    amount: Uint128::new(10),
+                }],
+                mint: None,
+                marketing: None,
+            },
+            &[],
+            "cw20",
+            None,
+        )
+        .unwrap()
+}
+
+fn cw20_balance(app: &App, cw20: &Addr, address: &str) -> Uint128 {
+    app.wrap()
+        .query_wasm_smart::<cw20::BalanceResponse>(
+            cw20,
+            &cw20::Cw20QueryMsg::Balance {
+                address: address.to_string(),
+            },
+        )
+        .unwrap()
+        .balance
+}
+
+fn native_balance(app: &App, address: &str) -> u128 {
+    app.wrap()
+        .query_balance(address, NATIVE_DENOM)
+        .unwrap()
+        .amount
+        .u128()
+}
+
+fn execute_err(harness: &mut ReceiptApp, sender: &str, msg: ExecuteMsg) -> ContractError {
+    harness
+        .execute(sender, &msg, &[])
+        .unwrap_err()
+        .downcast()
+        .unwrap()
+}
+
+fn list_unforwarded(harness: &ReceiptApp) -> Vec<Total> {
+    harness
+        .app
+        .wrap()
+        .query_wasm_smart::<ListUnforwardedResponse>(
+            &harness.receipt,
+            &QueryMsg::ListUnforwarded {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap()
+        .unforwarded
+}
+
+#[test]
+pub fn test_resilient_forwarding() {
+    let mut harness = ReceiptAppBuilder::new()
+        .with_output(BLOCKED_OUTPUT)
+        .with_native_balance(PAYER, coins(10, NATIVE_DENOM))
+        .build();
+    let cw20 = instantiate_blocking_cw20(&mut harness);
+
+    // By default, a failed forward reverts the payment.
+    harness.pay_cw20(PAYER, &cw20, RECEIPT_ID, 5).unwrap_err();
+    harness.assert_receipt_totals(RECEIPT_ID, &[]);
+
+    // Only the owner can change forwarding.
+    let err: ContractError = harness
+        .execute(
+            PAYER,
+            &ExecuteMsg::UpdateForwarding {
+                forwarding: ForwardingConfig {
+                    resilient: true,
+                    settlement: None,
+                    batch: None,
+                },
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::Ownable(cw_ownable::OwnershipError::NotOwner)
+    );
+    harness
+        .execute(
+            OWNER,
+            &ExecuteMsg::UpdateForwarding {
+                forwarding: ForwardingConfig {
+                    resilient: true,
+                    settlement: None,
+                    batch: None,
+                },
+            },
+            &[],
+        )
+        .unwrap();
+    let res: ForwardingResponse = harness
+        .app
+        .wrap()
+        .query_wasm_smart(&harness.receipt, &QueryMsg::Forwarding {})
+        .unwrap();
+    assert!(res.forwarding.resilient);
+
+    // Now the payment is recorded and the funds are held as unforwarded.
+    harness.pay_cw20(PAYER, &cw20, RECEIPT_ID, 5).unwrap();
+    harness.pay_cw20(PAYER, &cw20, RECEIPT_ID, 2).unwrap();
+    harness.assert_receipt_totals(RECEIPT_ID, &[cw20_total(&cw20, 7)]);
+    assert_eq!(
+        cw20_balance(&harness.app, &cw20, harness.receipt.as_str()),
+        Uint128::new(7)
+    );
+    assert_eq!(list_unforwarded(&harness), vec![cw20_total(&cw20, 7)]);
+
+    // Forwards that succeed are not held.
+    harness
+        .pay_native(PAYER, RECEIPT_ID, &coins(3, NATIVE_DENOM))
+        .unwrap();
+    assert_eq!(
+        harness
+            .app
+            .wrap()
+            .query_balance(BLOCKED_OUTPUT, NATIVE_DENOM)
+            .unwrap()
+            .amount,
+        Uint128::new(3)
+    );
+    assert_eq!(list_unforwarded(&harness), vec![cw20_total(&cw20, 7)]);
+
+    // Only the owner can flush.
+    let err: ContractError = harness
+        .execute(PAYER, &ExecuteMsg::FlushUnforwarded {}, &[])
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::Ownable(cw_ownable::OwnershipError::NotOwner)
+    );
+
+    // Flushing to an output that still fails reverts and keeps the balances.
+    harness
+        .execute(OWNER, &ExecuteMsg::FlushUnforwarded {}, &[])
+        .unwrap_err();
+    assert_eq!(list_unforwarded(&harness), vec![cw20_total(&cw20, 7)]);
+
+    harness
+        .execute(
+            OWNER,
+            &ExecuteMsg::UpdateOutput {
+                output: OUTPUT.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    harness
+        .execute(OWNER, &ExecuteMsg::FlushUnforwarded {}, &[])
+        .unwrap();
+    assert_eq!(cw20_balance(&harness.app, &cw20, OUTPUT), Uint128::new(7));
+    assert_eq!(
+        cw20_balance(&harness.app, &cw20, harness.receipt.as_str()),
+        Uint128::zero()
+    );
+    assert_eq!(list_unforwarded(&harness), vec![]);
+
+    let res: ListAdminActionsResponse = harness
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &harness.receipt,
+            &QueryMsg::ListAdminActions {
+                start_after: Some(1),
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res.actions[0].entry.action,
+        AdminAction::FlushUnforwarded {
+            amounts: vec![cw20_total(&cw20, 7)]
+        }
+    );
+}
+
+#[test]
+pub fn test_sweep() {
+    use cosmwasm_std::BankMsg;
+    use cw_denom::UncheckedDenom;
+
+    let mut harness = ReceiptAppBuilder::new()
+        .with_output(BLOCKED_OUTPUT)
+        .with_forwarding(ForwardingConfig {
+            resilient: true,
+            settlement: None,
+            batch: None,
+        })
+        .with_native_balance(PAYER, coins(10, NATIVE_DENOM))
+        .build();
+    let cw20 = instantiate_blocking_cw20(&mut harness);
+    let receipt = harness.receipt.clone();
+    let query_sweepable = |harness: &ReceiptApp, denom: UncheckedDenom| -> SweepableResponse {
+        harness
+            .app
+            .wrap()
+            .query_wasm_smart(&harness.receipt, &QueryMsg::Sweepable { denom })
+            .unwrap()
+    };
+
+    // Hold 5 as unforwarded, then send 3 cw20 and 4 native straight to the
+    // contract.
+    harness.pay_cw20(PAYER, &cw20, RECEIPT_ID, 5).unwrap();
+    harness
+        .app
+        .execute_contract(
+            Addr::unchecked(PAYER),
+            cw20.clone(),
+            &cw20::Cw20ExecuteMsg::Transfer {
+                recipient: receipt.to_string(),
+                amount: Uint128::new(3),
+            },
+            &[],
+        )
+        .unwrap();
+    harness
+        .app
+        .execute(
+            Addr::unchecked(PAYER),
+            BankMsg::Send {
+                to_address: receipt.to_string(),
+                amount: coins(4, NATIVE_DENOM),
+            }
+            .into(),
+        )
+        .unwrap();
+
+    assert_eq!(
+        query_sweepable(&harness, UncheckedDenom::Cw20(cw20.to_string())),
+        SweepableResponse {
+            denom: CheckedDenom::Cw20(cw20.clone()),
+            balance: Uint128::new(8),
+            owed: Uint128::new(5),
+            sweepable: Uint128::new(3),
+        }
+    );
+    assert_eq!(
+        query_sweepable(&harness, UncheckedDenom::Native(NATIVE_DENOM.to_string())).sweepable,
+        Uint128::new(4)
+    );
+
+    // Only the owner can sweep.
+    let err: ContractError = harness
+        .execute(
+            PAYER,
+            &ExecuteMsg::Sweep {
+                denom: UncheckedDenom::Cw20(cw20.to_string()),
+                amount: None,
+                recipient: PAYER.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::Ownable(cw_ownable::OwnershipError::NotOwner)
+    );
+
+    // Obligations cannot be swept.
+    let err: ContractError = harness
+        .execute(
+            OWNER,
+            &ExecuteMsg::Sweep {
+                denom: UncheckedDenom::Cw20(cw20.to_string()),
+                amount: Some(Uint128::new(4)),
+                recipient: PAYER.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::InsufficientSweepable {
+            denom: cw20.to_string(),
+            sweepable: Uint128::new(3),
+        }
+    );
+
+    harness
+        .execute(
+            OWNER,
+            &ExecuteMsg::Sweep {
+                denom: UncheckedDenom::Cw20(cw20.to_string()),
+                amount: None,
+                recipient: PAYER.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(cw20_balance(&harness.app, &cw20, PAYER), Uint128::new(5));
+    assert_eq!(
+        cw20_balance(&harness.app, &cw20, receipt.as_str()),
+        Uint128::new(5)
+    );
+    assert_eq!(list_unforwarded(&harness), vec![cw20_total(&cw20, 5)]);
+
+    harness
+        .execute(
+            OWNER,
+            &ExecuteMsg::Sweep {
+                denom: UncheckedDenom::Native(NATIVE_DENOM.to_string()),
+                amount: Some(Uint128::new(1)),
+                recipient: OTHER_PAYER.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        harness
+            .app
+            .wrap()
+            .query_balance(OTHER_PAYER, NATIVE_DENOM)
+            .unwrap()
+            .amount,
+        Uint128::new(1)
+    );
+    assert_eq!(
+        query_sweepable(&harness, UncheckedDenom::Native(NATIVE_DENOM.to_string())).sweepable,
+        Uint128::new(3)
+    );
+
+    // Nothing left to sweep.
+    let err: ContractError = harness
+        .execute(
+            OWNER,
+            &ExecuteMsg::Sweep {
+                denom: UncheckedDenom::Cw20(cw20.to_string()),
+                amount: None,
+                recipient: PAYER.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::InsufficientSweepable {
+            denom: cw20.to_string(),
+            sweepable: Uint128::zero(),
+        }
+    );
+}
+
+#[test]
+pub fn test_periods() {
+    let (app, addr, cw20_addr) = instantiate();
+    let mut harness = ReceiptApp {
+        app,
+        receipt: addr,
+        cw20s: vec![cw20_addr.clone()],
+        owner: Addr::unchecked(OWNER),
+    };
+
+    harness
+        .pay_native(PAYER, RECEIPT_ID, &coins(3, NATIVE_DENOM))
+        .unwrap();
+    harness
+        .pay_native(OTHER_PAYER, "other_receipt", &coins(2, NATIVE_DENOM))
+        .unwrap();
+    harness.pay_cw20(PAYER, &cw20_addr, RECEIPT_ID, 4).unwrap();
+
+    // Only the owner can close a period.
+    let err: ContractError = harness
+        .execute(PAYER, &ExecuteMsg::ClosePeriod {}, &[])
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::Ownable(cw_ownable::OwnershipError::NotOwner)
+    );
+    harness
+        .execute(OWNER, &ExecuteMsg::ClosePeriod {}, &[])
+        .unwrap();
+    let block = harness.app.block_info();
+
+    // Pay into the next period, and void a payment from the closed one.
+    harness
+        .pay_native(PAYER, RECEIPT_ID, &coins(1, NATIVE_DENOM))
+        .unwrap();
+    harness
+        .execute(
+            OWNER,
+            &ExecuteMsg::VoidPayment {
+                id: RECEIPT_ID.to_string(),
+                payment_index: 0,
+                reason: "duplicate".to_string(),
+                restock: false,
+            },
+            &[],
+        )
+        .unwrap();
+
+    let statement = |harness: &ReceiptApp, period: u64| -> PeriodStatementResponse {
+        harness
+            .app
+            .wrap()
+            .query_wasm_smart(&harness.receipt, &QueryMsg::GetPeriodStatement { period })
+            .unwrap()
+    };
+
+    // The closed statement is not affected by the void.
+    assert_eq!(
+        statement(&harness, 0),
+        PeriodStatementResponse {
+            period: 0,
+            closed: Some(Period {
+                closed_by: Addr::unchecked(OWNER),
+                height: block.height,
+                time: block.time,
+            }),
+            totals: vec![native_total(NATIVE_DENOM, 5), cw20_total(&cw20_addr, 4)],
+            voided: vec![],
+        }
+    );
+    assert_eq!(
+        statement(&harness, 1),
+        PeriodStatementResponse {
+            period: 1,
+            closed: None,
+            totals: vec![native_total(NATIVE_DENOM, 1)],
+            voided: vec![native_total(NATIVE_DENOM, 3)],
+        }
+    );
+
+    let list_payer_totals = |start_after: Option<&str>, limit: Option<u32>| -> Vec<PayerTotals> {
+        harness
+            .app
+            .wrap()
+            .query_wasm_smart::<ListPeriodPayerTotalsResponse>(
+                &harness.receipt,
+                &QueryMsg::ListPeriodPayerTotals {
+                    period: 0,
+                    start_after: start_after.map(str::to_string),
+                    limit,
+                },
+            )
+            .unwrap()
+            .payers
+    };
+    assert_eq!(
+        list_payer_totals(None, None),
+        vec![
+            PayerTotals {
+                payer: Addr::unchecked(PAYER),
+                totals: vec![native_total(NATIVE_DENOM, 3), cw20_total(&cw20_addr, 4)],
+            },
+            PayerTotals {
+                payer: Addr::unchecked(OTHER_PAYER),
+                totals: vec![native_total(NATIVE_DENOM, 2)],
+            },
+        ]
+    );
+    assert_eq!(list_payer_totals(None, Some(1)).len(), 1);
+    assert_eq!(
+        list_payer_totals(Some(PAYER), None)[0].payer,
+        Addr::unchecked(OTHER_PAYER)
+    );
+
+    let res: ListPeriodsResponse = harness
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &harness.receipt,
+            &QueryMsg::ListPeriods {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.current_period, 1);
+    assert_eq!(res.periods.len(), 1);
+    assert_eq!(res.periods[0].period, 0);
+
+    // Payments carry their period.
+    let res: ListPaymentsToIdResponse = harness
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &harness.receipt,
+            &QueryMsg::ListPaymentsToId {
+                id: RECEIPT_ID.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res.payments
+            .iter()
+            .map(|payment| payment.payment.period)
+            .collect::<Vec<_>>(),
+        vec![0, 0, 1]
+    );
+}
+
+#[test]
+pub fn test_historical_totals() {
+    use cw_multi_test::next_block;
+
+    let mut harness = ReceiptAppBuilder::new()
+        .with_native_balance(PAYER, coins(10, NATIVE_DENOM))
+        .build();
+    let start = harness.app.block_info().height;
+
+    harness
+        .pay_native(PAYER, RECEIPT_ID, &coins(3, NATIVE_DENOM))
+        .unwrap();
+    harness.app.update_block(next_block);
+    harness
+        .pay_native(PAYER, RECEIPT_ID, &coins(2, NATIVE_DENOM))
+        .unwrap();
+    harness.app.update_block(next_block);
+    // Void both payments, bringing the totals back to zero.
+    for payment_index in 0..2 {
+        harness
+            .execute(
+                OWNER,
+                &ExecuteMsg::VoidPayment {
+                    id: RECEIPT_ID.to_string(),
+                    payment_index,
+                    reason: "test".to_string(),
+                    restock: false,
+                },
+                &[],
+            )
+            .unwrap();
+    }
+    harness.app.update_block(next_block);
+
+    let totals_at = |height: Option<u64>| -> (Vec<Total>, Vec<Total>) {
+        let to_id: ListTotalsPaidToIdResponse = harness
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &harness.receipt,
+                &QueryMsg::ListTotalsPaidToId {
+                    id: RECEIPT_ID.to_string(),
+                    start_after: None,
+                    limit: None,
+                    height,
+                },
+            )
+            .unwrap();
+        let by_payer: ListTotalsPaidByPayerResponse = harness
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &harness.receipt,
+                &QueryMsg::ListTotalsPaidByPayer {
+                    payer: PAYER.to_string(),
+                    start_after: None,
+                    limit: None,
+                    height,
+                },
+            )
+            .unwrap();
+        (to_id.totals, by_payer.totals)
+    };
+
+    // Heights are read at the start of the block.
+    assert_eq!(totals_at(Some(start)), (vec![], vec![]));
+    assert_eq!(
+        totals_at(Some(start + 1)),
+        (
+            vec![native_total(NATIVE_DENOM, 3)],
+            vec![native_total(NATIVE_DENOM, 3)]
+        )
+    );
+    assert_eq!(
+        totals_at(Some(start + 2)),
+        (
+            vec![native_total(NATIVE_DENOM, 5)],
+            vec![native_total(NATIVE_DENOM, 5)]
+        )
+    );
+    assert_eq!(totals_at(Some(start + 3)), (vec![], vec![]));
+    assert_eq!(totals_at(None), (vec![], vec![]));
+}
+
+#[test]
+pub fn test_list_receipt_ids() {
+    let mut harness = ReceiptAppBuilder::new()
+        .with_native_balance(PAYER, coins(10, NATIVE_DENOM))
+        .build();
+    for id in [
+        "order-2026-09-1",
+        "order-2026-10-1",
+        "order-2026-10-2",
+        "order-2026-10-2",
+        "order-2026-11-1",
+        "other",
+    ] {
+        harness
+            .pay_native(PAYER, id, &coins(1, NATIVE_DENOM))
+            .unwrap();
+    }
+
+    let list = |prefix: Option<&str>, start_after: Option<&str>, limit: Option<u32>| {
+        harness
+            .app
+            .wrap()
+            .query_wasm_smart::<ListReceiptIdsResponse>(
+                &harness.receipt,
+                &QueryMsg::ListReceiptIds {
+                    prefix: prefix.map(str::to_string),
+                    start_after: start_after.map(str::to_string),
+                    limit,
+                },
+            )
+            .unwrap()
+            .receipts
+            .into_iter()
+            .map(|item| (item.id, item.payment_count))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        list(Some("order-2026-10-"), None, None),
+        vec![
+            ("order-2026-10-1".to_string(), 1),
+            ("order-2026-10-2".to_string(), 2)
+        ]
+    );
+    assert_eq!(
+        list(Some("order-2026-10-"), None, Some(1)),
+        vec![("order-2026-10-1".to_string(), 1)]
+    );
+    assert_eq!(
+        list(Some("order-2026-10-"), Some("order-2026-10-1"), None),
+        vec![("order-2026-10-2".to_string(), 2)]
+    );
+    // Starting before the prefix starts at the prefix.
+    assert_eq!(list(Some("order-2026-10-"), Some("a"), None).len(), 2);
+    assert_eq!(list(Some("order-2026-1"), None, None).len(), 3);
+    assert_eq!(list(Some("zzz"), None, None), vec![]);
+    assert_eq!(list(None, None, None).len(), 5);
+    assert_eq!(
+        list(None, Some("order-2026-11-1"), None),
+        vec![("other".to_string(), 1)]
+    );
+}
+
+fn sign_payment_request(
+    key: &k256::ecdsa::SigningKey,
+    contract: &Addr,
+    request: &PaymentRequest,
+) -> Binary {
+    use k256::ecdsa::{signature::Signer, Signature};
+
+    let payload = cosmwasm_std::to_vec(&SignedPaymentRequest {
+        contract: contract.to_string(),
+        request: request.clone(),
+    })
+    .unwrap();
+    // Hashes the payload with SHA-256 before signing.
+    let signature: Signature = key.sign(&payload);
+    Binary::from(signature.as_ref())
+}
+
+#[test]
+pub fn test_signed_payment_request() {
+    use cw_utils::Expiration;
+    use k256::ecdsa::SigningKey;
+
+    let mut harness = ReceiptAppBuilder::new()
+        .with_native_balance(PAYER, coins(100, NATIVE_DENOM))
+        .with_native_balance(OTHER_PAYER, coins(100, NATIVE_DENOM))
+        .with_cw20(vec![cw20::Cw20Coin {
+            address: PAYER.to_string(),
+            amount: Uint128::new(100),
+        }])
+        .build();
+    let cw20 = harness.cw20s[0].clone();
+    let receipt = harness.receipt.clone();
+
+    let key = SigningKey::from_bytes(&[1; 32]).unwrap();
+    let public_key = Binary::from(key.verifying_key().to_bytes().as_slice());
+    let height = harness.app.block_info().height;
+
+    let request = PaymentRequest {
+        id: RECEIPT_ID.to_string(),
+        amounts: vec![native_total(NATIVE_DENOM, 10)],
+        payer: PAYER.to_string(),
+        expires: Expiration::AtHeight(height + 10),
+        nonce: 1,
+    };
+    let pay = |harness: &mut ReceiptApp,
+               sender: &str,
+               request: &PaymentRequest,
+               signature: Binary,
+               funds: &[cosmwasm_std::Coin]|
+     -> ContractError {
+        harness
+            .execute(
+                sender,
+                &ExecuteMsg::PayWithSignedRequest {
+                    request: request.clone(),
+                    signature,
+                    public_key: public_key.clone(),
+                },
+                funds,
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap()
+    };
+
+    // Only the owner can manage signer keys, and keys must be well formed.
+    let err: ContractError = harness
+        .execute(
+            PAYER,
+            &ExecuteMsg::AddSignerKey {
+                public_key: public_key.clone(),
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::Ownable(cw_ownable::OwnershipError::NotOwner)
+    );
+    let err: ContractError = harness
+        .execute(
+            OWNER,
+            &ExecuteMsg::AddSignerKey {
+                public_key: Binary::from(vec![2; 32]),
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::InvalidSignerKey);
+
+    // Unregistered keys are rejected.
+    let signature = sign_payment_request(&key, &receipt, &request);
+    assert_eq!(
+        pay(
+            &mut harness,
+            PAYER,
+            &request,
+            signature.clone(),
+            &coins(10, NATIVE_DENOM)
+        ),
+        ContractError::UnknownSignerKey
+    );
+
+    harness
+        .execute(
+            OWNER,
+            &ExecuteMsg::AddSignerKey {
+                public_key: public_key.clone(),
+            },
+            &[],
+        )
+        .unwrap();
+    let keys: ListSignerKeysResponse = harness
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &receipt,
+            &QueryMsg::ListSignerKeys {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(keys.public_keys, vec![public_key.clone()]);
+
+    // Signatures over a different request or contract are rejected.
+    let tampered = PaymentRequest {
+        amounts: vec![native_total(NATIVE_DENOM, 1)],
+        ..request.clone()
+    };
+    assert_eq!(
+        pay(
+            &mut harness,
+            PAYER,
+            &tampered,
+            signature.clone(),
+            &coins(1, NATIVE_DENOM)
+        ),
+        ContractError::InvalidSignature
+    );
+    assert_eq!(
+        pay(
+            &mut harness,
+            PAYER,
+            &request,
+            sign_payment_request(&key, &Addr::unchecked("other"), &request),
+            &coins(10, NATIVE_DENOM)
+        ),
+        ContractError::InvalidSignature
+    );
+
+    // Only the requested payer can pay, and only the exact amounts.
+    assert_eq!(
+        pay(
+            &mut harness,
+            OTHER_PAYER,
+            &request,
+            signature.clone(),
+            &coins(10, NATIVE_DENOM)
+        ),
+        ContractError::UnauthorizedPayer
+    );
+    assert_eq!(
+        pay(
+            &mut harness,
+            PAYER,
+            &request,
+            signature.clone(),
+            &coins(9, NATIVE_DENOM)
+        ),
+        ContractError::PaymentRequestMismatch
+    );
+
+    // Expired requests are rejected.
+    let expired = PaymentRequest {
+        expires: Expiration::AtHeight(height),
+        ..request.clone()
+    };
+    assert_eq!(
+        pay(
+            &mut harness,
+            PAYER,
+            &expired,
+            sign_payment_request(&key, &receipt, &expired),
+            &coins(10, NATIVE_DENOM)
+        ),
+        ContractError::PaymentRequestExpired
+    );
+
+    // Pay the request.
+    harness
+        .execute(
+            PAYER,
+            &ExecuteMsg::PayWithSignedRequest {
+                request: request.clone(),
+                signature: signature.clone(),
+                public_key: public_key.clone(),
+            },
+            &coins(10, NATIVE_DENOM),
+        )
+        .unwrap();
+    harness.assert_receipt_totals(RECEIPT_ID, &[native_total(NATIVE_DENOM, 10)]);
+
+    // The nonce cannot be used again.
+    assert_eq!(
+        pay(
+            &mut harness,
+            PAYER,
+            &request,
+            signature,
+            &coins(10, NATIVE_DENOM)
+        ),
+        ContractError::NonceUsed { nonce: 1 }
+    );
+
+    // Pay a cw20 request with the next nonce.
+    let cw20_request = PaymentRequest {
+        amounts: vec![cw20_total(&cw20, 5)],
+        nonce: 2,
+        ..request.clone()
+    };
+    harness
+        .app
+        .execute_contract(
+            Addr::unchecked(PAYER),
+            cw20.clone(),
+            &cw20::Cw20ExecuteMsg::Send {
+                contract: receipt.to_string(),
+                amount: Uint128::new(5),
+                msg: to_binary(&Cw20ReceiverMsg::PayWithSignedRequest {
+                    request: cw20_request.clone(),
+                    signature: sign_payment_request(&key, &receipt, &cw20_request),
+                    public_key: public_key.clone(),
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+    harness.assert_receipt_totals(
+        RECEIPT_ID,
+        &[native_total(NATIVE_DENOM, 10), cw20_total(&cw20, 5)],
+    );
+
+    // Removed keys can no longer sign.
+    harness
+        .execute(
+            OWNER,
+            &ExecuteMsg::RemoveSignerKey {
+                public_key: public_key.clone(),
+            },
+            &[],
+        )
+        .unwrap();
+    let next = PaymentRequest {
+        nonce: 3,
+        ..request.clone()
+    };
+    assert_eq!(
+        pay(
+            &mut harness,
+            PAYER,
+            &next,
+            sign_payment_request(&key, &receipt, &next),
+            &coins(10, NATIVE_DENOM)
+        ),
+        ContractError::UnknownSignerKey
+    );
+}
+
+#[test]
+pub fn test_idempotency_keys() {
+    let mut harness = ReceiptAppBuilder::new()
+        .with_native_balance(PAYER, coins(100, NATIVE_DENOM))
+        .with_native_balance(OTHER_PAYER, coins(100, NATIVE_DENOM))
+        .with_cw20(vec![cw20::Cw20Coin {
+            address: PAYER.to_string(),
+            amount: Uint128::new(100),
+        }])
+        .build();
+    let cw20 = harness.cw20s[0].clone();
+    let receipt = harness.receipt.clone();
+    let pay = |harness: &mut ReceiptApp, payer: &str, id: &str, key: &str| {
+        harness.execute(
+            payer,
+            &ExecuteMsg::Pay {
+                id: id.to_string(),
+                idempotency_key: Some(key.to_string()),
+            },
+            &coins(10, NATIVE_DENOM),
+        )
+    };
+
+    pay(&mut harness, PAYER, RECEIPT_ID, "key").unwrap();
+
+    // Paying again with the same key is rejected, in any denom.
+    let err: ContractError = pay(&mut harness, PAYER, RECEIPT_ID, "key")
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::DuplicatePayment {
+            key: "key".to_string()
+        }
+    );
+    let err: ContractError = harness
+        .app
+        .execute_contract(
+            Addr::unchecked(PAYER),
+            cw20.clone(),
+            &cw20::Cw20ExecuteMsg::Send {
+                contract: receipt.to_string(),
+                amount: Uint128::new(5),
+                msg: to_binary(&Cw20ReceiverMsg::Pay {
+                    id: RECEIPT_ID.to_string(),
+                    idempotency_key: Some("key".to_string()),
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::DuplicatePayment {
+            key: "key".to_string()
+        }
+    );
+
+    // Keys are scoped to the payer and receipt ID.
+    pay(&mut harness, PAYER, RECEIPT_ID, "other_key").unwrap();
+    pay(&mut harness, PAYER, "other_id", "key").unwrap();
+    pay(&mut harness, OTHER_PAYER, "other_payer_id", "key").unwrap();
+    harness.assert_receipt_totals(RECEIPT_ID, &[native_total(NATIVE_DENOM, 20)]);
+
+    // Keys cannot be empty or too long.
+    for key in ["".to_string(), "a".repeat(IDEMPOTENCY_KEY_MAX_LENGTH + 1)] {
+        let err: ContractError = pay(&mut harness, PAYER, RECEIPT_ID, &key)
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::InvalidIdempotencyKey {
+                max_length: IDEMPOTENCY_KEY_MAX_LENGTH
+            }
+        );
+    }
+
+    // Keys can be reused once expired, and expired keys are pruned.
+    harness
+        .app
+        .update_block(|block| block.time = block.time.plus_seconds(IDEMPOTENCY_KEY_TTL_SECONDS));
+    pay(&mut harness, PAYER, RECEIPT_ID, "key").unwrap();
+    harness.assert_receipt_totals(RECEIPT_ID, &[native_total(NATIVE_DENOM, 30)]);
+
+    let stored = |payer: &str, id: &str, key: &str| {
+        harness
+            .app
+            .wrap()
+            .query_wasm_raw(
+                &receipt,
+                IDEMPOTENCY_KEYS
+                    .key(&idempotency_key_hash(&Addr::unchecked(payer), id, key))
+                    .to_vec(),
+            )
+            .unwrap()
+            .is_some()
+    };
+    assert!(stored(PAYER, RECEIPT_ID, "key"));
+    assert!(!stored(PAYER, RECEIPT_ID, "other_key"));
+    assert!(!stored(PAYER, "other_id", "key"));
+    assert!(!stored(OTHER_PAYER, "other_payer_id", "key"));
+}
+
+#[test]
+pub fn test_catalog() {
+    use cosmwasm_std::Coin;
+    use cw_denom::UncheckedDenom;
+
+    let mut harness = ReceiptAppBuilder::new()
+        .with_native_balance(
+            PAYER,
+            vec![Coin::new(100, NATIVE_DENOM), Coin::new(100, "other")],
+        )
+        .with_cw20(vec![cw20::Cw20Coin {
+            address: PAYER.to_string(),
+            amount: Uint128::new(100),
+        }])
+        .build();
+    let cw20 = harness.cw20s[0].clone();
+    let receipt = harness.receipt.clone();
+
+    let set_product = |harness: &mut ReceiptApp,
+                       sender: &str,
+                       sku: &str,
+                       prices: Vec<UncheckedPrice>,
+                       active: bool| {
+        harness.execute(
+            sender,
+            &ExecuteMsg::SetProduct {
+                sku: sku.to_string(),
+                name: sku.to_uppercase(),
+                prices,
+                active,
+            },
+            &[],
+        )
+    };
+    let native_price = |amount: u128| UncheckedPrice {
+        denom: UncheckedDenom::Native(NATIVE_DENOM.to_string()),
+        amount: Uint128::new(amount),
+    };
+    let pay_for_items = |harness: &mut ReceiptApp, items: &[(&str, u32)], funds: &[Coin]| {
+        harness.execute(
+            PAYER,
+            &ExecuteMsg::PayForItems {
+                id: RECEIPT_ID.to_string(),
+                items: items
+                    .iter()
+                    .map(|(sku, quantity)| (sku.to_string(), *quantity))
+                    .collect(),
+            },
+            funds,
+        )
+    };
+
+    // Only the owner can manage products, and prices must be valid.
+    let err: ContractError = set_product(&mut harness, PAYER, "tea", vec![native_price(3)], true)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::Ownable(cw_ownable::OwnershipError::NotOwner)
+    );
+    let err: ContractError = set_product(
+        &mut harness,
+        OWNER,
+        "tea",
+        vec![native_price(3), native_price(4)],
+        true,
+    )
+    .unwrap_err()
+    .downcast()
+    .unwrap();
+    assert_eq!(
+        err,
+        ContractError::InvalidProduct {
+            reason: "duplicate price denom".to_string()
+        }
+    );
+
+    set_product(
+        &mut harness,
+        OWNER,
+        "tea",
+        vec![
+            native_price(3),
+            UncheckedPrice {
+                denom: UncheckedDenom::Cw20(cw20.to_string()),
+                amount: Uint128::new(2),
+            },
+        ],
+        true,
+    )
+    .unwrap();
+    set_product(&mut harness, OWNER, "cake", vec![native_price(5)], true).unwrap();
+    set_product(&mut harness, OWNER, "pie", vec![native_price(7)], false).unwrap();
+
+    let products: ListProductsResponse = harness
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &receipt,
+            &QueryMsg::ListProducts {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        products
+            .products
+            .iter()
+            .map(|item| item.sku.as_str())
+            .collect::<Vec<_>>(),
+        vec!["cake", "pie", "tea"]
+    );
+    let tea: ProductResponse = harness
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &receipt,
+            &QueryMsg::Product {
+                sku: "tea".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        tea.product,
+        Some(Product {
+            name: "TEA".to_string(),
+            prices: vec![native_total(NATIVE_DENOM, 3), cw20_total(&cw20, 2)],
+            active: true,
+        })
+    );
+
+    // Funds must match the items' cost exactly, in a single denom.
+    for (items, funds, expected) in [
+        (
+            vec![("tea", 2), ("cake", 1)],
+            coins(10, NATIVE_DENOM),
+            ContractError::ItemsCostMismatch {
+                expected: Uint128::new(11),
+                denom: NATIVE_DENOM.to_string(),
+            },
+        ),
+        (
+            vec![("tea", 1)],
+            vec![Coin::new(3, NATIVE_DENOM), Coin::new(3, "other")],
+            ContractError::InvalidItems {
+                reason: "items must be paid in a single denom".to_string(),
+            },
+        ),
+        (
+            vec![("tea", 0)],
+            coins(3, NATIVE_DENOM),
+            ContractError::InvalidItems {
+                reason: "quantity of tea is zero".to_string(),
+            },
+        ),
+        (
+            vec![("coffee", 1)],
+            coins(3, NATIVE_DENOM),
+            ContractError::ProductNotFound {
+                sku: "coffee".to_string(),
+            },
+        ),
+        (
+            vec![("pie", 1)],
+            coins(7, NATIVE_DENOM),
+            ContractError::ProductInactive {
+                sku: "pie".to_string(),
+            },
+        ),
+        (
+            vec![("tea", 1)],
+            coins(3, "other"),
+            ContractError::ProductNotPriced {
+                sku: "tea".to_string(),
+                denom: "other".to_string(),
+            },
+        ),
+    ] {
+        let err: ContractError = pay_for_items(&mut harness, &items, &funds)
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(err, expected);
+    }
+
+    // Pay natively, then in cw20.
+    pay_for_items(
+        &mut harness,
+        &[("tea", 2), ("cake", 1)],
+        &coins(11, NATIVE_DENOM),
+    )
+    .unwrap();
+    harness
+        .app
+        .execute_contract(
+            Addr::unchecked(PAYER),
+            cw20.clone(),
+            &cw20::Cw20ExecuteMsg::Send {
+                contract: receipt.to_string(),
+                amount: Uint128::new(6),
+                msg: to_binary(&Cw20ReceiverMsg::PayForItems {
+                    id: RECEIPT_ID.to_string(),
+                    items: vec![("tea".to_string(), 3)],
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+    harness.assert_receipt_totals(
+        RECEIPT_ID,
+        &[native_total(NATIVE_DENOM, 11), cw20_total(&cw20, 6)],
+    );
+
+    // Line items are stored with the payments.
+    let payments: ListPaymentsToIdResponse = harness
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &receipt,
+            &QueryMsg::ListPaymentsToId {
+                id: RECEIPT_ID.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        payments
+            .payments
+            .into_iter()
+            .map(|payment| payment.payment.items)
+            .collect::<Vec<_>>(),
+        vec![
+            vec![
+                LineItem {
+                    sku: "tea".to_string(),
+                    quantity: 2,
+                    unit_price: Uint128::new(3),
+                },
+                LineItem {
+                    sku: "cake".to_string(),
+                    quantity: 1,
+                    unit_price: Uint128::new(5),
+                },
+            ],
+            vec![LineItem {
+                sku: "tea".to_string(),
+                quantity: 3,
+                unit_price: Uint128::new(2),
+            }],
+        ]
+    );
+}
+
+#[test]
+pub fn test_product_stock() {
+    use cosmwasm_std::Coin;
+    use cw_denom::UncheckedDenom;
+
+    let mut harness = ReceiptAppBuilder::new()
+        .with_native_balance(PAYER, coins(100, NATIVE_DENOM))
+        .build();
+    let receipt = harness.receipt.clone();
+
+    harness
+        .execute(
+            OWNER,
+            &ExecuteMsg::SetProduct {
+                sku: "ticket".to_string(),
+                name: "Ticket".to_string(),
+                prices: vec![UncheckedPrice {
+                    denom: UncheckedDenom::Native(NATIVE_DENOM.to_string()),
+                    amount: Uint128::new(10),
+                }],
+                active: true,
+            },
+            &[],
+        )
+        .unwrap();
+
+    let set_stock = |harness: &mut ReceiptApp, sender: &str, sku: &str, stock: Option<u32>| {
+        harness.execute(
+            sender,
+            &ExecuteMsg::SetProductStock {
+                sku: sku.to_string(),
+                stock,
+            },
+            &[],
+        )
+    };
+    let query_stock = |harness: &ReceiptApp| -> Option<u32> {
+        harness
+            .app
+            .wrap()
+            .query_wasm_smart::<ProductStockResponse>(
+                &receipt,
+                &QueryMsg::ProductStock {
+                    sku: "ticket".to_string(),
+                },
+            )
+            .unwrap()
+            .stock
+    };
+    let buy = |harness: &mut ReceiptApp, quantity: u32| {
+        harness.execute(
+            PAYER,
+            &ExecuteMsg::PayForItems {
+                id: RECEIPT_ID.to_string(),
+                items: vec![("ticket".to_string(), quantity)],
+            },
+            &[Coin::new(10 * quantity as u128, NATIVE_DENOM)],
+        )
+    };
+    let void = |harness: &mut ReceiptApp, payment_index: u64, restock: bool| {
+        harness
+            .execute(
+                OWNER,
+                &ExecuteMsg::VoidPayment {
+                    id: RECEIPT_ID.to_string(),
+                    payment_index,
+                    reason: "cancelled".to_string(),
+                    restock,
+                },
+                &[],
+            )
+            .unwrap()
+    };
+
+    // Only the owner can set stock, and only for existing products.
+    let err: ContractError = set_stock(&mut harness, PAYER, "ticket", Some(3))
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::Ownable(cw_ownable::OwnershipError::NotOwner)
+    );
+    let err: ContractError = set_stock(&mut harness, OWNER, "other", Some(3))
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::ProductNotFound {
+            sku: "other".to_string()
+        }
+    );
+
+    // Stock is untracked until set.
+    assert_eq!(query_stock(&harness), None);
+    set_stock(&mut harness, OWNER, "ticket", Some(3)).unwrap();
+    assert_eq!(query_stock(&harness), Some(3));
+
+    // Sales take from stock until it runs out.
+    buy(&mut harness, 2).unwrap();
+    assert_eq!(query_stock(&harness), Some(1));
+    let err: ContractError = buy(&mut harness, 2).unwrap_err().downcast().unwrap();
+    assert_eq!(
+        err,
+        ContractError::SoldOut {
+            sku: "ticket".to_string(),
+            remaining: 1
+        }
+    );
+    buy(&mut harness, 1).unwrap();
+    assert_eq!(query_stock(&harness), Some(0));
+    let err: ContractError = buy(&mut harness, 1).unwrap_err().downcast().unwrap();
+    assert_eq!(
+        err,
+        ContractError::SoldOut {
+            sku: "ticket".to_string(),
+            remaining: 0
+        }
+    );
+
+    // Voiding only returns stock if asked to.
+    void(&mut harness, 1, false);
+    assert_eq!(query_stock(&harness), Some(0));
+    void(&mut harness, 0, true);
+    assert_eq!(query_stock(&harness), Some(2));
+    harness.assert_receipt_totals(RECEIPT_ID, &[]);
+
+    // Unset stock to stop tracking it.
+    set_stock(&mut harness, OWNER, "ticket", None).unwrap();
+    assert_eq!(query_stock(&harness), None);
+    buy(&mut harness, 5).unwrap();
+    assert_eq!(query_stock(&harness), None);
+}
+
+#[test]
+pub fn test_settlement() {
+    use cosmwasm_std::{Coin, Decimal};
+
+    const SETTLEMENT_DENOM: &str = "uusdc";
+
+    let mut harness = ReceiptAppBuilder::new()
+        .with_native_balance(
+            PAYER,
+            vec![
+                Coin::new(100, NATIVE_DENOM),
+                Coin::new(100, SETTLEMENT_DENOM),
+            ],
+        )
+        .with_cw20(vec![cw20::Cw20Coin {
+            address: PAYER.to_string(),
+            amount: Uint128::new(100),
+        }])
+        .build();
+    let cw20 = harness.cw20s[0].clone();
+    let receipt = harness.receipt.clone();
+
+    // Swaps at 2 uusdc per unit, the reference price of both denoms.
+    let router = harness.instantiate_mock_router(
+        MockRouterInstantiateMsg {
+            rate: Decimal::percent(200),
+        },
+        coins(1000, SETTLEMENT_DENOM),
+    );
+    let reference_prices = vec![
+        ReferencePrice {
+            denom: CheckedDenom::Native(NATIVE_DENOM.to_string()),
+            price: Decimal::percent(200),
+        },
+        ReferencePrice {
+            denom: CheckedDenom::Cw20(cw20.clone()),
+            price: Decimal::percent(200),
+        },
+    ];
+    let update_settlement = |harness: &mut ReceiptApp,
+                             router: &Addr,
+                             reference_prices: Vec<ReferencePrice>,
+                             max_slippage: Decimal,
+                             resilient: bool| {
+        harness.execute(
+            OWNER,
+            &ExecuteMsg::UpdateForwarding {
+                forwarding: ForwardingConfig {
+                    resilient,
+                    settlement: Some(SettlementConfig {
+                        router: router.clone(),
+                        denom: CheckedDenom::Native(SETTLEMENT_DENOM.to_string()),
+                        reference_prices,
+                        max_slippage,
+                    }),
+                    batch: None,
+                },
+            },
+            &[],
+        )
+    };
+    let output_balance = |harness: &ReceiptApp| {
+        harness
+            .app
+            .wrap()
+            .query_balance(OUTPUT, SETTLEMENT_DENOM)
+            .unwrap()
+            .amount
+            .u128()
+    };
+    let settled = |harness: &ReceiptApp| -> Vec<Option<Total>> {
+        harness
+            .app
+            .wrap()
+            .query_wasm_smart::<ListPaymentsToIdResponse>(
+                &receipt,
+                &QueryMsg::ListPaymentsToId {
+                    id: RECEIPT_ID.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap()
+            .payments
+            .into_iter()
+            .map(|payment| payment.payment.settled)
+            .collect()
+    };
+
+    // Slippage must be a fraction, and reference prices positive and unique.
+    for (reference_prices, max_slippage, reason) in [
+        (
+            reference_prices.clone(),
+            Decimal::percent(101),
+            "max slippage is above 1".to_string(),
+        ),
+        (
+            vec![ReferencePrice {
+                denom: CheckedDenom::Native(NATIVE_DENOM.to_string()),
+                price: Decimal::zero(),
+            }],
+            Decimal::percent(5),
+            format!("reference price for {} is zero", NATIVE_DENOM),
+        ),
+        (
+            vec![reference_prices[1].clone(), reference_prices[1].clone()],
+            Decimal::percent(5),
+            format!("duplicate reference price for {}", cw20),
+        ),
+    ] {
+        let err: ContractError =
+            update_settlement(&mut harness, &router, reference_prices, max_slippage, false)
+                .unwrap_err()
+                .downcast()
+                .unwrap();
+        assert_eq!(err, ContractError::InvalidSettlement { reason });
+    }
+    update_settlement(
+        &mut harness,
+        &router,
+        reference_prices.clone(),
+        Decimal::percent(5),
+        false,
+    )
+    .unwrap();
+
+    // Native and cw20 payments are swapped, and settlement denom payments are
+    // forwarded as is.
+    harness
+        .pay_native(PAYER, RECEIPT_ID, &coins(10, NATIVE_DENOM))
+        .unwrap();
+    assert_eq!(output_balance(&harness), 20);
+    harness.pay_cw20(PAYER, &cw20, RECEIPT_ID, 5).unwrap();
+    assert_eq!(output_balance(&harness), 30);
+    assert_eq!(
+        cw20_balance(&harness.app, &cw20, router.as_str()),
+        Uint128::new(5)
+    );
+    harness
+        .pay_native(PAYER, RECEIPT_ID, &coins(7, SETTLEMENT_DENOM))
+        .unwrap();
+    assert_eq!(output_balance(&harness), 37);
+
+    // Totals keep the paid amounts, and payments record what was settled. The
+    // settlement denom was first seen when settling the first payment.
+    harness.assert_receipt_totals(
+        RECEIPT_ID,
+        &[
+            native_total(NATIVE_DENOM, 10),
+            native_total(SETTLEMENT_DENOM, 7),
+            cw20_total(&cw20, 5),
+        ],
+    );
+    assert_eq!(
+        settled(&harness),
+        vec![
+            Some(native_total(SETTLEMENT_DENOM, 20)),
+            Some(native_total(SETTLEMENT_DENOM, 10)),
+            Some(native_total(SETTLEMENT_DENOM, 7)),
+        ]
+    );
+
+    // Denoms without a reference price are not swapped.
+    update_settlement(
+        &mut harness,
+        &router,
+        reference_prices[..1].to_vec(),
+        Decimal::percent(5),
+        false,
+    )
+    .unwrap();
+    let err: ContractError = harness
+        .pay_cw20(PAYER, &cw20, RECEIPT_ID, 5)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::NoReferencePrice {
+            denom: cw20.to_string()
+        }
+    );
+
+    // Swapping 10% below the reference price is beyond the maximum slippage,
+    // whatever rate the router offers.
+    let slipping_router = harness.instantiate_mock_router(
+        MockRouterInstantiateMsg {
+            rate: Decimal::percent(180),
+        },
+        coins(1000, SETTLEMENT_DENOM),
+    );
+    update_settlement(
+        &mut harness,
+        &slipping_router,
+        reference_prices.clone(),
+        Decimal::percent(5),
+        false,
+    )
+    .unwrap();
+    harness
+        .pay_native(PAYER, RECEIPT_ID, &coins(10, NATIVE_DENOM))
+        .unwrap_err();
+    assert_eq!(settled(&harness).len(), 3);
+
+    // Within the maximum slippage, the swap goes through.
+    update_settlement(
+        &mut harness,
+        &slipping_router,
+        reference_prices.clone(),
+        Decimal::percent(10),
+        false,
+    )
+    .unwrap();
+    harness
+        .pay_native(PAYER, RECEIPT_ID, &coins(10, NATIVE_DENOM))
+        .unwrap();
+    assert_eq!(output_balance(&harness), 55);
+    assert_eq!(
+        settled(&harness)[3],
+        Some(native_total(SETTLEMENT_DENOM, 18))
+    );
+
+    // When resilient, failed swaps are held as unforwarded, and unused cw20
+    // allowances are taken back.
+    update_settlement(
+        &mut harness,
+        &slipping_router,
+        reference_prices,
+        Decimal::percent(5),
+        true,
+    )
+    .unwrap();
+    harness
+        .pay_native(PAYER, RECEIPT_ID, &coins(10, NATIVE_DENOM))
+        .unwrap();
+    harness.pay_cw20(PAYER, &cw20, RECEIPT_ID, 10).unwrap();
+    assert_eq!(output_balance(&harness), 55);
+    assert_eq!(
+        list_unforwarded(&harness),
+        vec![native_total(NATIVE_DENOM, 10), cw20_total(&cw20, 10)]
+    );
+    assert_eq!(settled(&harness)[4..], [None, None]);
+    let allowance: cw20::AllowanceResponse = harness
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &cw20,
+            &cw20::Cw20QueryMsg::Allowance {
+                owner: receipt.to_string(),
+                spender: slipping_router.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(allowance.allowance, Uint128::zero());
+}
+
+#[test]
+pub fn test_batch_settlement() {
+    use cosmwasm_std::Decimal;
+    use cw_denom::UncheckedDenom;
+
+    let mut harness = ReceiptAppBuilder::new()
+        .with_native_balance(PAYER, coins(100, NATIVE_DENOM))
+        .with_cw20(vec![cw20::Cw20Coin {
+            address: PAYER.to_string(),
+            amount: Uint128::new(100),
+        }])
+        .build();
+    let cw20 = harness.cw20s[0].clone();
+    let receipt = harness.receipt.clone();
+    let update_forwarding = |harness: &mut ReceiptApp, forwarding: ForwardingConfig| {
+        harness.execute(OWNER, &ExecuteMsg::UpdateForwarding { forwarding }, &[])
+    };
+    let list_owed = |harness: &ReceiptApp| -> Vec<Total> {
+        harness
+            .app
+            .wrap()
+            .query_wasm_smart::<ListOwedToOutputResponse>(
+                &harness.receipt,
+                &QueryMsg::ListOwedToOutput {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap()
+            .owed
+    };
+    let output_balance = |harness: &ReceiptApp| {
+        harness
+            .app
+            .wrap()
+            .query_balance(OUTPUT, NATIVE_DENOM)
+            .unwrap()
+            .amount
+            .u128()
+    };
+    let settle = |harness: &mut ReceiptApp, denoms: Vec<UncheckedDenom>| {
+        harness.execute(OTHER_PAYER, &ExecuteMsg::Settle { denoms }, &[])
+    };
+
+    // Batching cannot swap, and thresholds must be positive.
+    for (forwarding, reason) in [
+        (
+            ForwardingConfig {
+                resilient: false,
+                settlement: Some(SettlementConfig {
+                    router: receipt.clone(),
+                    denom: CheckedDenom::Native(NATIVE_DENOM.to_string()),
+                    reference_prices: vec![],
+                    max_slippage: Decimal::zero(),
+                }),
+                batch: Some(BatchConfig { thresholds: vec![] }),
+            },
+            "batching cannot be combined with swapping",
+        ),
+        (
+            ForwardingConfig {
+                resilient: false,
+                settlement: None,
+                batch: Some(BatchConfig {
+                    thresholds: vec![native_total(NATIVE_DENOM, 0)],
+                }),
+            },
+            "batch threshold is zero",
+        ),
+    ] {
+        let err: ContractError = update_forwarding(&mut harness, forwarding)
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::InvalidSettlement {
+                reason: reason.to_string()
+            }
+        );
+    }
+
+    update_forwarding(
+        &mut harness,
+        ForwardingConfig {
+            resilient: false,
+            settlement: None,
+            batch: Some(BatchConfig {
+                thresholds: vec![native_total(NATIVE_DENOM, 50)],
+            }),
+        },
+    )
+    .unwrap();
+
+    // Payments accumulate as owed to the output, and cannot be swept.
+    harness
+        .pay_native(PAYER, RECEIPT_ID, &coins(10, NATIVE_DENOM))
+        .unwrap();
+    harness
+        .pay_native(PAYER, RECEIPT_ID, &coins(10, NATIVE_DENOM))
+        .unwrap();
+    harness.pay_cw20(PAYER, &cw20, RECEIPT_ID, 5).unwrap();
+    assert_eq!(output_balance(&harness), 0);
+    assert_eq!(
+        list_owed(&harness),
+        vec![native_total(NATIVE_DENOM, 20), cw20_total(&cw20, 5)]
+    );
+    let sweepable: SweepableResponse = harness
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &receipt,
+            &QueryMsg::Sweepable {
+                denom: UncheckedDenom::Native(NATIVE_DENOM.to_string()),
+            },
+        )
+        .unwrap();
+    assert_eq!(sweepable.owed, Uint128::new(20));
+    assert_eq!(sweepable.sweepable, Uint128::zero());
+
+    // Anyone can settle some denoms, or all of them.
+    settle(
+        &mut harness,
+        vec![UncheckedDenom::Native(NATIVE_DENOM.to_string())],
+    )
+    .unwrap();
+    assert_eq!(output_balance(&harness), 20);
+    assert_eq!(list_owed(&harness), vec![cw20_total(&cw20, 5)]);
+    settle(&mut harness, vec![]).unwrap();
+    assert_eq!(cw20_balance(&harness.app, &cw20, OUTPUT), Uint128::new(5));
+    assert_eq!(list_owed(&harness), vec![]);
+
+    // Reaching a threshold settles the denom.
+    harness
+        .pay_native(PAYER, RECEIPT_ID, &coins(30, NATIVE_DENOM))
+        .unwrap();
+    assert_eq!(output_balance(&harness), 20);
+    harness
+        .pay_native(PAYER, RECEIPT_ID, &coins(25, NATIVE_DENOM))
+        .unwrap();
+    assert_eq!(output_balance(&harness), 75);
+    assert_eq!(list_owed(&harness), vec![]);
+    harness.assert_receipt_totals(
+        RECEIPT_ID,
+        &[native_total(NATIVE_DENOM, 75), cw20_total(&cw20, 5)],
+    );
+
+    // When resilient, a failed threshold settlement stays owed, while a
+    // failed Settle reverts.
+    let mut harness = ReceiptAppBuilder::new()
+        .with_output(BLOCKED_OUTPUT)
+        .with_forwarding(ForwardingConfig {
+            resilient: true,
+            settlement: None,
+            batch: Some(BatchConfig { thresholds: vec![] }),
+        })
+        .build();
+    let cw20 = instantiate_blocking_cw20(&mut harness);
+    update_forwarding(
+        &mut harness,
+        ForwardingConfig {
+            resilient: true,
+            settlement: None,
+            batch: Some(BatchConfig {
+                thresholds: vec![cw20_total(&cw20, 5)],
+            }),
+        },
+    )
+    .unwrap();
+    harness.pay_cw20(PAYER, &cw20, RECEIPT_ID, 5).unwrap();
+    assert_eq!(list_owed(&harness), vec![cw20_total(&cw20, 5)]);
+    settle(&mut harness, vec![]).unwrap_err();
+    assert_eq!(list_owed(&harness), vec![cw20_total(&cw20, 5)]);
+}
+
+#[test]
+pub fn test_campaigns() {
+    use cw_denom::UncheckedDenom;
+    use cw_utils::Expiration;
+
+    let mut harness = ReceiptAppBuilder::new()
+        .with_native_balance(PAYER, coins(100, NATIVE_DENOM))
+        .with_native_balance(OTHER_PAYER, coins(100, NATIVE_DENOM))
+        .with_cw20(vec![cw20::Cw20Coin {
+            address: PAYER.to_string(),
+            amount: Uint128::new(100),
+        }])
+        .build();
+    let cw20 = harness.cw20s[0].clone();
+    let receipt = harness.receipt.clone();
+    let height = harness.app.block_info().height;
+
+    let create_campaign =
+        |harness: &mut ReceiptApp, sender: &str, id: &str, goal: u128, deadline: Expiration| {
+            harness.execute(
+                sender,
+                &ExecuteMsg::CreateCampaign {
+                    id: id.to_string(),
+                    goal: vec![UncheckedPrice {
+                        denom: UncheckedDenom::Native(NATIVE_DENOM.to_string()),
+                        amount: Uint128::new(goal),
+                    }],
+                    deadline,
+                },
+                &[],
+            )
+        };
+    let query_campaign = |harness: &ReceiptApp, id: &str| -> CampaignResponse {
+        harness
+            .app
+            .wrap()
+            .query_wasm_smart(&receipt, &QueryMsg::Campaign { id: id.to_string() })
+            .unwrap()
+    };
+
+    // Only the owner can create campaigns, on unused IDs with future deadlines.
+    let err: ContractError = create_campaign(
+        &mut harness,
+        PAYER,
+        "funded",
+        30,
+        Expiration::AtHeight(height + 10),
+    )
+    .unwrap_err()
+    .downcast()
+    .unwrap();
+    assert_eq!(
+        err,
+        ContractError::Ownable(cw_ownable::OwnershipError::NotOwner)
+    );
+    harness
+        .pay_native(PAYER, RECEIPT_ID, &coins(1, NATIVE_DENOM))
+        .unwrap();
+    for (id, deadline, reason) in [
+        (
+            RECEIPT_ID,
+            Expiration::AtHeight(height + 10),
+            "receipt ID is in use",
+        ),
+        (
+            "funded",
+            Expiration::AtHeight(height),
+            "deadline has passed",
+        ),
+    ] {
+        let err: ContractError = create_campaign(&mut harness, OWNER, id, 30, deadline)
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::InvalidCampaign {
+                reason: reason.to_string()
+            }
+        );
+    }
+
+    create_campaign(
+        &mut harness,
+        OWNER,
+        "funded",
+        30,
+        Expiration::AtHeight(height + 10),
+    )
+    .unwrap();
+    create_campaign(
+        &mut harness,
+        OWNER,
+        "unfunded",
+        50,
+        Expiration::AtHeight(height + 10),
+    )
+    .unwrap();
+
+    // Anyone can contribute in the goal denoms, and funds are held.
+    harness
+        .pay_native(PAYER, "funded", &coins(20, NATIVE_DENOM))
+        .unwrap();
+    harness
+        .pay_native(OTHER_PAYER, "funded", &coins(15, NATIVE_DENOM))
+        .unwrap();
+    harness
+        .pay_native(PAYER, "unfunded", &coins(10, NATIVE_DENOM))
+        .unwrap();
+    harness
+        .pay_native(OTHER_PAYER, "unfunded", &coins(5, NATIVE_DENOM))
+        .unwrap();
+    let err: ContractError = harness
+        .pay_cw20(PAYER, &cw20, "funded", 5)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::CampaignDenomNotAccepted {
+            id: "funded".to_string(),
+            denom: cw20.to_string(),
+        }
+    );
+    assert_eq!(native_balance(&harness.app, OUTPUT), 1);
+    let sweepable: SweepableResponse = harness
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &receipt,
+            &QueryMsg::Sweepable {
+                denom: UncheckedDenom::Native(NATIVE_DENOM.to_string()),
+            },
+        )
+        .unwrap();
+    assert_eq!(sweepable.owed, Uint128::new(50));
+    assert_eq!(sweepable.sweepable, Uint128::zero());
+    let funded = query_campaign(&harness, "funded");
+    assert_eq!(funded.raised, vec![native_total(NATIVE_DENOM, 35)]);
+    assert!(funded.goal_met);
+    harness.assert_receipt_totals("funded", &[native_total(NATIVE_DENOM, 35)]);
+
+    // Refunds wait for the deadline, and unmet goals cannot be finalized.
+    assert_eq!(
+        execute_err(
+            &mut harness,
+            PAYER,
+            ExecuteMsg::ClaimRefund {
+                id: "unfunded".to_string()
+            }
+        ),
+        ContractError::CampaignRunning {
+            id: "unfunded".to_string()
+        }
+    );
+    assert_eq!(
+        execute_err(
+            &mut harness,
+            PAYER,
+            ExecuteMsg::Finalize {
+                id: "unfunded".to_string()
+            }
+        ),
+        ContractError::CampaignGoalNotMet {
+            id: "unfunded".to_string()
+        }
+    );
+
+    // Anyone can finalize a funded campaign, ending it.
+    harness
+        .execute(
+            OTHER_PAYER,
+            &ExecuteMsg::Finalize {
+                id: "funded".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(native_balance(&harness.app, OUTPUT), 36);
+    assert!(query_campaign(&harness, "funded").campaign.finalized);
+    let err: ContractError = harness
+        .pay_native(PAYER, "funded", &coins(1, NATIVE_DENOM))
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::CampaignEnded {
+            id: "funded".to_string()
+        }
+    );
+
+    // After the deadline, contributors to an unfunded campaign claim refunds.
+    harness.app.update_block(|block| block.height += 10);
+    let err: ContractError = harness
+        .pay_native(PAYER, "unfunded", &coins(1, NATIVE_DENOM))
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::CampaignEnded {
+            id: "unfunded".to_string()
+        }
+    );
+    assert_eq!(
+        execute_err(
+            &mut harness,
+            PAYER,
+            ExecuteMsg::ClaimRefund {
+                id: "funded".to_string()
+            }
+        ),
+        ContractError::CampaignEnded {
+            id: "funded".to_string()
+        }
+    );
+
+    let balance = native_balance(&harness.app, PAYER);
+    harness
+        .execute(
+            PAYER,
+            &ExecuteMsg::ClaimRefund {
+                id: "unfunded".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(native_balance(&harness.app, PAYER), balance + 10);
+
+    // Refunded payments are voided, so they leave the totals.
+    harness.assert_receipt_totals("unfunded", &[native_total(NATIVE_DENOM, 5)]);
+    harness.assert_payer_totals(PAYER, &[native_total(NATIVE_DENOM, 21)]);
+    assert_eq!(
+        execute_err(
+            &mut harness,
+            PAYER,
+            ExecuteMsg::ClaimRefund {
+                id: "unfunded".to_string()
+            }
+        ),
+        ContractError::NothingToRefund
+    );
+    let contributions: CampaignContributionsResponse = harness
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &receipt,
+            &QueryMsg::CampaignContributions {
+                id: "unfunded".to_string(),
+                payer: OTHER_PAYER.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        contributions.contributions,
+        vec![native_total(NATIVE_DENOM, 5)]
+    );
+    harness
+        .execute(
+            OTHER_PAYER,
+            &ExecuteMsg::ClaimRefund {
+                id: "unfunded".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(query_campaign(&harness, "unfunded").raised, vec![]);
+    assert_eq!(native_balance(&harness.app, receipt.as_str()), 0);
+    harness.assert_receipt_totals("unfunded", &[]);
+    let statement: PeriodStatementResponse = harness
+        .app
+        .wrap()
+        .query_wasm_smart(&receipt, &QueryMsg::GetPeriodStatement { period: 0 })
+        .unwrap();
+    assert_eq!(statement.voided, vec![native_total(NATIVE_DENOM, 15)]);
+}
+
+#[test]
+pub fn test_campaigns_with_bound_ids() {
+    use cw_denom::UncheckedDenom;
+    use cw_utils::Expiration;
+
+    let mut harness = ReceiptAppBuilder::new()
+        .with_id_rules(IdRules {
+            min_length: None,
+            max_length: None,
+            charset: None,
+            prefix: None,
+            case: Some(IdCase::Upper),
+            bind_to_payer: true,
+        })
+        .with_native_balance(PAYER, coins(100, NATIVE_DENOM))
+        .with_native_balance(OTHER_PAYER, coins(100, NATIVE_DENOM))
+        .build();
+    let receipt = harness.receipt.clone();
+    let height = harness.app.block_info().height;
+
+    harness
+        .execute(
+            OWNER,
+            &ExecuteMsg::CreateCampaign {
+                id: "drive".to_string(),
+                goal: vec![UncheckedPrice {
+                    denom: UncheckedDenom::Native(NATIVE_DENOM.to_string()),
+                    amount: Uint128::new(30),
+                }],
+                deadline: Expiration::AtHeight(height + 10),
+            },
+            &[],
+        )
+        .unwrap();
+
+    // Contributions are not bound to their payer, so they are all held for
+    // the campaign.
+    harness
+        .pay_native(PAYER, "drive", &coins(20, NATIVE_DENOM))
+        .unwrap();
+    harness
+        .pay_native(OTHER_PAYER, "Drive", &coins(15, NATIVE_DENOM))
+        .unwrap();
+    assert_eq!(native_balance(&harness.app, OUTPUT), 0);
+    assert_eq!(native_balance(&harness.app, receipt.as_str()), 35);
+    let campaign: CampaignResponse = harness
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &receipt,
+            &QueryMsg::Campaign {
+                id: "drive".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(campaign.raised, vec![native_total(NATIVE_DENOM, 35)]);
+    harness.assert_receipt_totals("DRIVE", &[native_total(NATIVE_DENOM, 35)]);
+
+    harness
+        .execute(
+            PAYER,
+            &ExecuteMsg::Finalize {
+                id: "drive".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(native_balance(&harness.app, OUTPUT), 35);
+
+    // Other receipts are still bound.
+    harness
+        .pay_native(PAYER, "order", &coins(1, NATIVE_DENOM))
+        .unwrap();
+    let res: ListIdsForPayerResponse = harness
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &receipt,
+            &QueryMsg::ListIdsForPayer {
+                payer: PAYER.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.ids.len(), 1);
+    assert_ne!(res.ids[0], "ORDER");
+}
+
+#[test]
+pub fn test_escrow_disputes() {
+    use cw_denom::UncheckedDenom;
+    use cw_utils::{Duration, Expiration};
+
+    const ARBITER: &str = "arbiter";
+
+    let mut harness = ReceiptAppBuilder::new()
+        .with_native_balance(PAYER, coins(100, NATIVE_DENOM))
+        .with_native_balance(OTHER_PAYER, coins(100, NATIVE_DENOM))
+        .build();
+    let receipt = harness.receipt.clone();
+    let height = harness.app.block_info().height;
+
+    let assign_arbiter = |harness: &mut ReceiptApp, sender: &str, id: &str| {
+        harness.execute(
+            sender,
+            &ExecuteMsg::AssignArbiter {
+                id: id.to_string(),
+                arbiter: ARBITER.to_string(),
+                dispute_window: Duration::Height(10),
+            },
+            &[],
+        )
+    };
+
+    // Only the owner can assign arbiters, and only before the first payment.
+    let err: ContractError = assign_arbiter(&mut harness, PAYER, "order-1")
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::Ownable(cw_ownable::OwnershipError::NotOwner)
+    );
+    harness
+        .pay_native(PAYER, RECEIPT_ID, &coins(1, NATIVE_DENOM))
+        .unwrap();
+    let err: ContractError = assign_arbiter(&mut harness, OWNER, RECEIPT_ID)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::ReceiptAlreadyPaid);
+    assign_arbiter(&mut harness, OWNER, "order-1").unwrap();
+    assign_arbiter(&mut harness, OWNER, "order-2").unwrap();
+
+    // Escrowed payments are held instead of forwarded.
+    harness
+        .pay_native(PAYER, "order-1", &coins(30, NATIVE_DENOM))
+        .unwrap();
+    harness
+        .pay_native(OTHER_PAYER, "order-2", &coins(20, NATIVE_DENOM))
+        .unwrap();
+    assert_eq!(native_balance(&harness.app, OUTPUT), 1);
+    let escrow = harness.query_receipt("order-1").escrow.unwrap();
+    assert_eq!(escrow.held, vec![native_total(NATIVE_DENOM, 30)]);
+    assert_eq!(
+        escrow.escrow.window_ends,
+        Some(Expiration::AtHeight(height + 10))
+    );
+    assert_eq!(escrow.escrow.dispute, None);
+    let sweepable: SweepableResponse = harness
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &receipt,
+            &QueryMsg::Sweepable {
+                denom: UncheckedDenom::Native(NATIVE_DENOM.to_string()),
+            },
+        )
+        .unwrap();
+    assert_eq!(sweepable.owed, Uint128::new(50));
+    assert_eq!(sweepable.sweepable, Uint128::zero());
+
+    // Only the payer can dispute, with a reason, and funds stay held until
+    // the window ends.
+    assert_eq!(
+        execute_err(
+            &mut harness,
+            OTHER_PAYER,
+            ExecuteMsg::OpenDispute {
+                id: "order-1".to_string(),
+                reason: "damaged".to_string(),
+            }
+        ),
+        ContractError::UnauthorizedPayer
+    );
+    assert_eq!(
+        execute_err(
+            &mut harness,
+            PAYER,
+            ExecuteMsg::OpenDispute {
+                id: "order-1".to_string(),
+                reason: " ".to_string(),
+            }
+        ),
+        ContractError::InvalidDispute {
+            reason: "no reason given".to_string()
+        }
+    );
+    assert_eq!(
+        execute_err(
+            &mut harness,
+            PAYER,
+            ExecuteMsg::ReleaseEscrow {
+                id: "order-1".to_string()
+            }
+        ),
+        ContractError::DisputeWindowOpen {
+            id: "order-1".to_string()
+        }
+    );
+    harness
+        .execute(
+            PAYER,
+            &ExecuteMsg::OpenDispute {
+                id: "order-1".to_string(),
+                reason: "damaged".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    let err: ContractError = harness
+        .pay_native(PAYER, "order-1", &coins(1, NATIVE_DENOM))
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::DisputeOpen {
+            id: "order-1".to_string()
+        }
+    );
+
+    // Later payments do not extend the dispute window.
+    harness.app.update_block(|block| block.height += 5);
+    harness
+        .pay_native(OTHER_PAYER, "order-2", &coins(1, NATIVE_DENOM))
+        .unwrap();
+    let escrow = harness.query_receipt("order-2").escrow.unwrap();
+    assert_eq!(escrow.held, vec![native_total(NATIVE_DENOM, 21)]);
+    assert_eq!(
+        escrow.escrow.window_ends,
+        Some(Expiration::AtHeight(height + 10))
+    );
+
+    // Disputed funds wait for the arbiter, even after the window.
+    harness.app.update_block(|block| block.height += 5);
+    assert_eq!(
+        execute_err(
+            &mut harness,
+            PAYER,
+            ExecuteMsg::ReleaseEscrow {
+                id: "order-1".to_string()
+            }
+        ),
+        ContractError::DisputeOpen {
+            id: "order-1".to_string()
+        }
+    );
+    assert_eq!(
+        execute_err(
+            &mut harness,
+            OWNER,
+            ExecuteMsg::ResolveDispute {
+                id: "order-1".to_string(),
+                release_bps: 2500,
+            }
+        ),
+        ContractError::Unauthorized
+    );
+    assert_eq!(
+        execute_err(
+            &mut harness,
+            ARBITER,
+            ExecuteMsg::ResolveDispute {
+                id: "order-1".to_string(),
+                release_bps: 10_001,
+            }
+        ),
+        ContractError::InvalidReleaseBps {
+            release_bps: 10_001
+        }
+    );
+    assert_eq!(
+        execute_err(
+            &mut harness,
+            ARBITER,
+            ExecuteMsg::ResolveDispute {
+                id: "order-2".to_string(),
+                release_bps: 2500,
+            }
+        ),
+        ContractError::NoOpenDispute {
+            id: "order-2".to_string()
+        }
+    );
+
+    // The arbiter splits the held funds between the output and the payer.
+    let balance = native_balance(&harness.app, PAYER);
+    harness
+        .execute(
+            ARBITER,
+            &ExecuteMsg::ResolveDispute {
+                id: "order-1".to_string(),
+                release_bps: 2500,
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(native_balance(&harness.app, OUTPUT), 8);
+    assert_eq!(native_balance(&harness.app, PAYER), balance + 23);
+
+    // The refunded share is voided, so it leaves the totals.
+    harness.assert_receipt_totals("order-1", &[native_total(NATIVE_DENOM, 7)]);
+    harness.assert_payer_totals(PAYER, &[native_total(NATIVE_DENOM, 8)]);
+    let statement: PeriodStatementResponse = harness
+        .app
+        .wrap()
+        .query_wasm_smart(&receipt, &QueryMsg::GetPeriodStatement { period: 0 })
+        .unwrap();
+    assert_eq!(statement.voided, vec![native_total(NATIVE_DENOM, 23)]);
+    let escrow = harness.query_receipt("order-1").escrow.unwrap();
+    assert!(escrow.escrow.released);
+    assert_eq!(escrow.held, vec![]);
+    let dispute = escrow.escrow.dispute.unwrap();
+    assert_eq!(dispute.reason, "damaged");
+    assert_eq!(dispute.release_bps, Some(2500));
+    assert_eq!(
+        execute_err(
+            &mut harness,
+            ARBITER,
+            ExecuteMsg::ResolveDispute {
+                id: "order-1".to_string(),
+                release_bps: 2500,
+            }
+        ),
+        ContractError::EscrowReleased {
+            id: "order-1".to_string()
+        }
+    );
+
+    // Undisputed funds can no longer be disputed after the window, and
+    // anyone can release them.
+    assert_eq!(
+        execute_err(
+            &mut harness,
+            OTHER_PAYER,
+            ExecuteMsg::OpenDispute {
+                id: "order-2".to_string(),
+                reason: "late".to_string(),
+            }
+        ),
+        ContractError::DisputeWindowEnded {
+            id: "order-2".to_string()
+        }
+    );
+    harness
+        .execute(
+            PAYER,
+            &ExecuteMsg::ReleaseEscrow {
+                id: "order-2".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(native_balance(&harness.app, OUTPUT), 29);
+    let err: ContractError = harness
+        .pay_native(OTHER_PAYER, "order-2", &coins(1, NATIVE_DENOM))
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::EscrowReleased {
+            id: "order-2".to_string()
+        }
+    );
+    assert_eq!(native_balance(&harness.app, receipt.as_str()), 0);
+
+    // Payments voided by the owner stay held, but only the rest of the
+    // refunded share is removed from the totals.
+    assign_arbiter(&mut harness, OWNER, "order-3").unwrap();
+    for _ in 0..2 {
+        harness
+            .pay_native(PAYER, "order-3", &coins(10, NATIVE_DENOM))
+            .unwrap();
+    }
+    harness
+        .execute(
+            OWNER,
+            &ExecuteMsg::VoidPayment {
+                id: "order-3".to_string(),
+                payment_index: 0,
+                reason: "duplicate".to_string(),
+                restock: false,
+            },
+            &[],
+        )
+        .unwrap();
+    harness
+        .execute(
+            PAYER,
+            &ExecuteMsg::OpenDispute {
+                id: "order-3".to_string(),
+                reason: "damaged".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    let balance = native_balance(&harness.app, PAYER);
+    harness
+        .execute(
+            ARBITER,
+            &ExecuteMsg::ResolveDispute {
+                id: "order-3".to_string(),
+                release_bps: 2500,
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(native_balance(&harness.app, OUTPUT), 34);
+    assert_eq!(native_balance(&harness.app, PAYER), balance + 15);
+    harness.assert_receipt_totals("order-3", &[]);
+    assert_eq!(native_balance(&harness.app, receipt.as_str()), 0);
+}
+
+#[test]
+pub fn test_escrow_with_bound_ids() {
+    use cw_utils::Duration;
+
+    let mut harness = ReceiptAppBuilder::new()
+        .with_id_rules(IdRules {
+            min_length: None,
+            max_length: None,
+            charset: None,
+            prefix: None,
+            case: None,
+            bind_to_payer: true,
+        })
+        .with_native_balance(PAYER, coins(100, NATIVE_DENOM))
+        .with_native_balance(OTHER_PAYER, coins(100, NATIVE_DENOM))
+        .build();
+    let receipt = harness.receipt.clone();
+
+    harness
+        .execute(
+            OWNER,
+            &ExecuteMsg::AssignArbiter {
+                id: "order-1".to_string(),
+                arbiter: "arbiter".to_string(),
+                dispute_window: Duration::Height(10),
+            },
+            &[],
+        )
+        .unwrap();
+
+    // The escrowed ID is not bound to its payer, so the payment is held.
+    harness
+        .pay_native(PAYER, "order-1", &coins(30, NATIVE_DENOM))
+        .unwrap();
+    assert_eq!(native_balance(&harness.app, OUTPUT), 0);
+    assert_eq!(native_balance(&harness.app, receipt.as_str()), 30);
+    let escrow = harness.query_receipt("order-1").escrow.unwrap();
+    assert_eq!(escrow.held, vec![native_total(NATIVE_DENOM, 30)]);
+
+    // Its first payer holds it, like any other receipt.
+    let err: ContractError = harness
+        .pay_native(OTHER_PAYER, "order-1", &coins(5, NATIVE_DENOM))
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::UnauthorizedPayer);
+
+    harness.app.update_block(|block| block.height += 10);
+    harness
+        .execute(
+            OTHER_PAYER,
+            &ExecuteMsg::ReleaseEscrow {
+                id: "order-1".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(native_balance(&harness.app, OUTPUT), 30);
+}
+
+// Cosmos SDK default KV store gas costs, used to estimate the gas saved by the
+// compact payment layout.
+const WRITE_COST_FLAT: u64 = 2000;
+const WRITE_COST_PER_BYTE: u64 = 30;
+const READ_COST_PER_BYTE: u64 = 3;
+
+fn storage_bytes(storage: &dyn cosmwasm_std::Storage) -> u64 {
+    storage
+        .range(None, None, cosmwasm_std::Order::Ascending)
+        .map(|(key, value)| (key.len() + value.len()) as u64)
+        .sum()
+}
+
+#[test]
+pub fn test_migrate_compact_payments() {
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+
+    use crate::msg::MigrateMsg;
+    use crate::state::{
+        LegacyPayment, LEDGER, LEGACY_PAYER_TOTALS, LEGACY_RECEIPT_PAYMENTS, LEGACY_RECEIPT_TOTALS,
+    };
+
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    cw2::set_contract_version(deps.as_mut().storage, "crates.io:cw-receipt", "0.2.0").unwrap();
+    crate::state::OUTPUT
+        .save(deps.as_mut().storage, &Addr::unchecked(OUTPUT))
+        .unwrap();
+
+    // Store payments in the legacy layout, alternating between a native and a
+    // cw20 denom.
+    let cw20_denom = CheckedDenom::Cw20(Addr::unchecked(
+        "juno1qwertyuiopasdfghjklzxcvbnm1234567890qwertyuiop",
+    ));
+    let native_denom = CheckedDenom::Native(NATIVE_DENOM.to_string());
+    let payment_count: u64 = 100;
+    for receipt_payment_id in 0..payment_count {
+        let denom = if receipt_payment_id % 2 == 0 {
+            native_denom.clone()
+        } else {
+            cw20_denom.clone()
+        };
+        LEGACY_RECEIPT_PAYMENTS
+            .save(
+                deps.as_mut().storage,
+                (RECEIPT_ID.to_string(), receipt_payment_id),
+                &LegacyPayment {
+                    payer: Addr::unchecked(PAYER),
+                    block: env.block.clone(),
+                    denom,
+                    amount: Uint128::new(1),
+                },
+            )
+            .unwrap();
+    }
+    LEDGER
+        .payer_receipts
+        .save(
+            deps.as_mut().storage,
+            (Addr::unchecked(PAYER), RECEIPT_ID.to_string()),
+            &Empty {},
+        )
+        .unwrap();
+    for string_denom in [format!("n{}", NATIVE_DENOM), format!("c{}", cw20_denom)] {
+        LEGACY_RECEIPT_TOTALS
+            .save(
+                deps.as_mut().storage,
+                (RECEIPT_ID.to_string(), string_denom.clone()),
+                &Uint128::new(payment_count as u128 / 2),
+            )
+            .unwrap();
+        LEGACY_PAYER_TOTALS
+            .save(
+                deps.as_mut().storage,
+                (Addr::unchecked(PAYER), string_denom),
+                &Uint128::new(payment_count as u128 / 2),
+            )
+            .unwrap();
+    }
+
+    let legacy_bytes = storage_bytes(deps.as_ref().storage);
+    let legacy_payment_bytes = cosmwasm_std::to_vec(
+        &LEGACY_RECEIPT_PAYMENTS
+            .load(deps.as_ref().storage, (RECEIPT_ID.to_string(), 1))
+            .unwrap(),
+    )
+    .unwrap()
+    .len() as u64;
+
+    // Migrate in batches of 30 entries: 4 legacy totals, 100 payments and 1
+    // receipt payer take 4 calls. Execution is paused until the last one.
+    for _ in 0..3 {
+        let res =
+            crate::contract::migrate(deps.as_mut(), env.clone(), MigrateMsg { limit: Some(30) })
+                .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "status" && a.value == "in_progress"));
+        let version = cw2::get_contract_version(deps.as_ref().storage).unwrap();
+        assert_eq!(version.version, "0.2.0");
+        let err = crate::contract::execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(PAYER, &coins(1, NATIVE_DENOM)),
+            ExecuteMsg::Pay {
+                id: RECEIPT_ID.to_string(),
+                idempotency_key: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::MigrationInProgress);
+    }
+    crate::contract::migrate(deps.as_mut(), env.clone(), MigrateMsg { limit: Some(30) }).unwrap();
+
+    let compact_bytes = storage_bytes(deps.as_ref().storage);
+    let compact_payment_bytes = cosmwasm_std::to_vec(
+        &LEDGER
+            .payments
+            .load(deps.as_ref().storage, (RECEIPT_ID.to_string(), 1))
+            .unwrap(),
+    )
+    .unwrap()
+    .len() as u64;
+
+    // Ensure payments were migrated.
+    let res: ListPaymentsToIdResponse = cosmwasm_std::from_binary(
+        &crate::contract::query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::ListPaymentsToId {
+                id: RECEIPT_ID.to_string(),
+                start_after: None,
+                limit: Some(2),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.payments
+            .into_iter()
+            .map(|p| p.payment)
+            .collect::<Vec<_>>(),
+        vec![
+            Payment {
+                payer: Addr::unchecked(PAYER),
+                block_height: env.block.height,
+                block_time: env.block.time,
+                denom: native_denom.clone(),
+                amount: Uint128::new(1),
+                period: 0,
+                items: vec![],
+                settled: None,
+                voided: None,
+            },
+            Payment {
+                payer: Addr::unchecked(PAYER),
+                block_height: env.block.height,
+                block_time: env.block.time,
+                denom: cw20_denom.clone(),
+                amount: Uint128::new(1),
+                period: 0,
+                items: vec![],
+                settled: None,
+                voided: None,
+            }
+        ]
+    );
+
+    // Ensure totals were migrated.
+    let res: ListTotalsPaidByPayerResponse = cosmwasm_std::from_binary(
+        &crate::contract::query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::ListTotalsPaidByPayer {
+                payer: PAYER.to_string(),
+                start_after: None,
+                limit: None,
+                height: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.totals,
+        vec![
+            Total {
+                denom: native_denom,
+                amount: Uint128::new(payment_count as u128 / 2),
+            },
+            Total {
+                denom: cw20_denom,
+                amount: Uint128::new(payment_count as u128 / 2),
+            },
+        ]
+    );
+
+    // Ensure receipt payer was indexed.
+    let res: ReceiptPayerResponse = cosmwasm_std::from_binary(
+        &crate::contract::query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::ReceiptPayer {
+                id: RECEIPT_ID.to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        ReceiptPayerResponse {
+            payer: Some(Addr::unchecked(PAYER))
+        }
+    );
+
+    // Ensure migrated payments fell into the first period.
+    let res: PeriodStatementResponse = cosmwasm_std::from_binary(
+        &crate::contract::query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetPeriodStatement { period: 0 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.totals.len(), 2);
+
+    // Ensure version was bumped.
+    let version = cw2::get_contract_version(deps.as_ref().storage).unwrap();
+    assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+
+    // A cw20 payment shrinks from 189 to 95 bytes, and the whole store shrinks
+    // even with the interned denom tables and the period 0 statement the
+    // migrated payments are added to.
+    assert_eq!(legacy_payment_bytes, 189);
+    assert_eq!(compact_payment_bytes, 95);
+    assert!(compact_bytes < legacy_bytes);
+
+    // Estimated gas to write and read back one cw20 payment.
+    let legacy_gas =
+        WRITE_COST_FLAT + (WRITE_COST_PER_BYTE + READ_COST_PER_BYTE) * legacy_payment_bytes;
+    let compact_gas =
+        WRITE_COST_FLAT + (WRITE_COST_PER_BYTE + READ_COST_PER_BYTE) * compact_payment_bytes;
+    assert_eq!(legacy_gas - compact_gas, 3102);
+
+    // Ensure migrating from another contract is rejected.
+    cw2::set_contract_version(deps.as_mut().storage, "crates.io:other", "0.1.0").unwrap();
+    let err = crate::contract::migrate(deps.as_mut(), env, MigrateMsg { limit: None }).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CannotMigrate {
+            previous_contract: "crates.io:other".to_string()
+        }
+    );
+}
+
+#[test]
+pub fn test_migrate_total_history() {
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+
+    use crate::msg::MigrateMsg;
+    use crate::state::{LegacyPayment, LEGACY_RECEIPT_PAYMENTS};
+
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    cw2::set_contract_version(deps.as_mut().storage, "crates.io:cw-receipt", "0.2.0").unwrap();
+
+    // The payer pays the first receipt at even heights, alternating denoms,
+    // and the second receipt in native tokens at odd heights, so their totals
+    // are replayed out of height order.
+    let cw20_denom = CheckedDenom::Cw20(Addr::unchecked("cw20"));
+    let native_denom = CheckedDenom::Native(NATIVE_DENOM.to_string());
+    let base = env.block.height;
+    let mut save_payment = |id: &str, payment_id: u64, height: u64, denom: &CheckedDenom| {
+        let mut block = env.block.clone();
+        block.height = height;
+        LEGACY_RECEIPT_PAYMENTS
+            .save(
+                deps.as_mut().storage,
+                (id.to_string(), payment_id),
+                &LegacyPayment {
+                    payer: Addr::unchecked(PAYER),
+                    block,
+                    denom: denom.clone(),
+                    amount: Uint128::new(1),
+                },
+            )
+            .unwrap();
+    };
+    for i in 0..10 {
+        let denom = if i % 2 == 0 {
+            &native_denom
+        } else {
+            &cw20_denom
+        };
+        save_payment(RECEIPT_ID, i, base + 2 * i, denom);
+        save_payment("receipt_2", i, base + 1 + 2 * i, &native_denom);
+    }
+
+    crate::contract::migrate(deps.as_mut(), env.clone(), MigrateMsg { limit: None }).unwrap();
+
+    let totals_paid_by_payer = |height: Option<u64>| {
+        let res: ListTotalsPaidByPayerResponse = cosmwasm_std::from_binary(
+            &crate::contract::query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::ListTotalsPaidByPayer {
+                    payer: PAYER.to_string(),
+                    start_after: None,
+                    limit: None,
+                    height,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        res.totals
+            .into_iter()
+            .map(|t| t.amount.u128())
+            .collect::<Vec<_>>()
+    };
+    let totals_paid_to_id = |height: Option<u64>| {
+        let res: ListTotalsPaidToIdResponse = cosmwasm_std::from_binary(
+            &crate::contract::query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::ListTotalsPaidToId {
+                    id: RECEIPT_ID.to_string(),
+                    start_after: None,
+                    limit: None,
+                    height,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        res.totals
+            .into_iter()
+            .map(|t| t.amount.u128())
+            .collect::<Vec<_>>()
+    };
+
+    // Totals are listed native first, then cw20.
+    assert_eq!(totals_paid_by_payer(None), vec![15, 5]);
+    assert_eq!(totals_paid_to_id(None), vec![5, 5]);
+
+    // Before height base + 10, payments 0 to 4 to the first receipt and 0 to
+    // 4 to the second were made.
+    assert_eq!(totals_paid_by_payer(Some(base + 10)), vec![8, 2]);
+    assert_eq!(totals_paid_to_id(Some(base + 10)), vec![3, 2]);
+    assert_eq!(totals_paid_by_payer(Some(base + 1)), vec![1]);
+    assert_eq!(totals_paid_by_payer(Some(base)), Vec::<u128>::new());
 }
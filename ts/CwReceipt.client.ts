@@ -6,10 +6,146 @@
 
 import { CosmWasmClient, SigningCosmWasmClient, ExecuteResult } from "@cosmjs/cosmwasm-stargate";
 import { Coin, StdFee } from "@cosmjs/amino";
-import { InstantiateMsg, ExecuteMsg, Uint128, Binary, Action, Expiration, Timestamp, Uint64, Cw20ReceiveMsg, QueryMsg, CheckedDenom, Addr, ListIdsForPayerResponse, ListPaymentsToIdResponse, PaymentWithId, Payment, BlockInfo, ListTotalsPaidByPayerResponse, Total, ListTotalsPaidToIdResponse, OutputResponse, OwnershipForString } from "./CwReceipt.types";
+import { Uint128, CheckedDenom, Addr, Decimal, IdCase, IdCharset, InstantiateMsg, ForwardingConfig, BatchConfig, Total, SettlementConfig, ReferencePrice, IdRules, ExecuteMsg, Binary, UncheckedDenom, Expiration, Timestamp, Uint64, Duration, Action, Cw20ReceiveMsg, PaymentRequest, UncheckedPrice, QueryMsg, MigrateMsg, CampaignResponse, Campaign, CampaignContributionsResponse, CanonicalIdResponse, ForwardingResponse, PeriodStatementResponse, Period, HasPaidResponse, IdRulesResponse, AdminAction, ListAdminActionsResponse, AdminLogItem, AdminLogEntry, OwnershipForAddr, Closure, Product, Escrow, Dispute, ListIdsForPayerResponse, ListOwedToOutputResponse, ListPaymentsResponse, ReceiptPayment, Payment, LineItem, Void, ListPaymentsToIdResponse, ReceiptPaymentWithoutId, ListPeriodPayerTotalsResponse, PayerTotals, ListPeriodsResponse, PeriodItem, ListProductsResponse, ProductItem, ListReceiptIdsResponse, ReceiptIdItem, ListReceiptTransfersResponse, ReceiptTransfer, Transfer, ListSignerKeysResponse, ListTotalsPaidByPayerResponse, ListTotalsPaidToIdResponse, ListUnforwardedResponse, OutputResponse, OwnershipForString, ProductResponse, ProductStockResponse, ReceiptResponse, Creation, ReceiptEscrow, ReceiptPayerResponse, SweepableResponse } from "./CwReceipt.types";
 export interface CwReceiptReadOnlyInterface {
   contractAddress: string;
   output: () => Promise<OutputResponse>;
+  idRules: () => Promise<IdRulesResponse>;
+  receipt: ({
+    id
+  }: {
+    id: string;
+  }) => Promise<ReceiptResponse>;
+  receiptPayer: ({
+    id
+  }: {
+    id: string;
+  }) => Promise<ReceiptPayerResponse>;
+  listReceiptTransfers: ({
+    id,
+    limit,
+    startAfter
+  }: {
+    id: string;
+    limit?: number;
+    startAfter?: number;
+  }) => Promise<ListReceiptTransfersResponse>;
+  hasPaid: ({
+    id,
+    payer,
+    requirements
+  }: {
+    id: string;
+    payer: string;
+    requirements: Total[];
+  }) => Promise<HasPaidResponse>;
+  listAdminActions: ({
+    limit,
+    startAfter
+  }: {
+    limit?: number;
+    startAfter?: number;
+  }) => Promise<ListAdminActionsResponse>;
+  canonicalId: ({
+    id,
+    payer
+  }: {
+    id: string;
+    payer: string;
+  }) => Promise<CanonicalIdResponse>;
+  forwarding: () => Promise<ForwardingResponse>;
+  listUnforwarded: ({
+    limit,
+    startAfter
+  }: {
+    limit?: number;
+    startAfter?: CheckedDenom;
+  }) => Promise<ListUnforwardedResponse>;
+  listOwedToOutput: ({
+    limit,
+    startAfter
+  }: {
+    limit?: number;
+    startAfter?: CheckedDenom;
+  }) => Promise<ListOwedToOutputResponse>;
+  sweepable: ({
+    denom
+  }: {
+    denom: UncheckedDenom;
+  }) => Promise<SweepableResponse>;
+  getPeriodStatement: ({
+    period
+  }: {
+    period: number;
+  }) => Promise<PeriodStatementResponse>;
+  listPeriodPayerTotals: ({
+    limit,
+    period,
+    startAfter
+  }: {
+    limit?: number;
+    period: number;
+    startAfter?: string;
+  }) => Promise<ListPeriodPayerTotalsResponse>;
+  listPeriods: ({
+    limit,
+    startAfter
+  }: {
+    limit?: number;
+    startAfter?: number;
+  }) => Promise<ListPeriodsResponse>;
+  listSignerKeys: ({
+    limit,
+    startAfter
+  }: {
+    limit?: number;
+    startAfter?: Binary;
+  }) => Promise<ListSignerKeysResponse>;
+  product: ({
+    sku
+  }: {
+    sku: string;
+  }) => Promise<ProductResponse>;
+  campaign: ({
+    id
+  }: {
+    id: string;
+  }) => Promise<CampaignResponse>;
+  campaignContributions: ({
+    id,
+    payer
+  }: {
+    id: string;
+    payer: string;
+  }) => Promise<CampaignContributionsResponse>;
+  productStock: ({
+    sku
+  }: {
+    sku: string;
+  }) => Promise<ProductStockResponse>;
+  listProducts: ({
+    limit,
+    startAfter
+  }: {
+    limit?: number;
+    startAfter?: string;
+  }) => Promise<ListProductsResponse>;
+  listReceiptIds: ({
+    limit,
+    prefix,
+    startAfter
+  }: {
+    limit?: number;
+    prefix?: string;
+    startAfter?: string;
+  }) => Promise<ListReceiptIdsResponse>;
+  listPayments: ({
+    limit,
+    startAfter
+  }: {
+    limit?: number;
+    startAfter?: [string, number];
+  }) => Promise<ListPaymentsResponse>;
   listPaymentsToId: ({
     id,
     limit,
@@ -20,10 +156,12 @@ export interface CwReceiptReadOnlyInterface {
     startAfter?: number;
   }) => Promise<ListPaymentsToIdResponse>;
   listTotalsPaidToId: ({
+    height,
     id,
     limit,
     startAfter
   }: {
+    height?: number;
     id: string;
     limit?: number;
     startAfter?: CheckedDenom;
@@ -38,10 +176,12 @@ export interface CwReceiptReadOnlyInterface {
     startAfter?: string;
   }) => Promise<ListIdsForPayerResponse>;
   listTotalsPaidByPayer: ({
+    height,
     limit,
     payer,
     startAfter
   }: {
+    height?: number;
     limit?: number;
     payer: string;
     startAfter?: CheckedDenom;
@@ -56,6 +196,28 @@ export class CwReceiptQueryClient implements CwReceiptReadOnlyInterface {
     this.client = client;
     this.contractAddress = contractAddress;
     this.output = this.output.bind(this);
+    this.idRules = this.idRules.bind(this);
+    this.receipt = this.receipt.bind(this);
+    this.receiptPayer = this.receiptPayer.bind(this);
+    this.listReceiptTransfers = this.listReceiptTransfers.bind(this);
+    this.hasPaid = this.hasPaid.bind(this);
+    this.listAdminActions = this.listAdminActions.bind(this);
+    this.canonicalId = this.canonicalId.bind(this);
+    this.forwarding = this.forwarding.bind(this);
+    this.listUnforwarded = this.listUnforwarded.bind(this);
+    this.listOwedToOutput = this.listOwedToOutput.bind(this);
+    this.sweepable = this.sweepable.bind(this);
+    this.getPeriodStatement = this.getPeriodStatement.bind(this);
+    this.listPeriodPayerTotals = this.listPeriodPayerTotals.bind(this);
+    this.listPeriods = this.listPeriods.bind(this);
+    this.listSignerKeys = this.listSignerKeys.bind(this);
+    this.product = this.product.bind(this);
+    this.campaign = this.campaign.bind(this);
+    this.campaignContributions = this.campaignContributions.bind(this);
+    this.productStock = this.productStock.bind(this);
+    this.listProducts = this.listProducts.bind(this);
+    this.listReceiptIds = this.listReceiptIds.bind(this);
+    this.listPayments = this.listPayments.bind(this);
     this.listPaymentsToId = this.listPaymentsToId.bind(this);
     this.listTotalsPaidToId = this.listTotalsPaidToId.bind(this);
     this.listIdsForPayer = this.listIdsForPayer.bind(this);
@@ -68,7 +230,34 @@ export class CwReceiptQueryClient implements CwReceiptReadOnlyInterface {
       output: {}
     });
   };
-  listPaymentsToId = async ({
+  idRules = async (): Promise<IdRulesResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      id_rules: {}
+    });
+  };
+  receipt = async ({
+    id
+  }: {
+    id: string;
+  }): Promise<ReceiptResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      receipt: {
+        id
+      }
+    });
+  };
+  receiptPayer = async ({
+    id
+  }: {
+    id: string;
+  }): Promise<ReceiptPayerResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      receipt_payer: {
+        id
+      }
+    });
+  };
+  listReceiptTransfers = async ({
     id,
     limit,
     startAfter
@@ -76,148 +265,930 @@ export class CwReceiptQueryClient implements CwReceiptReadOnlyInterface {
     id: string;
     limit?: number;
     startAfter?: number;
-  }): Promise<ListPaymentsToIdResponse> => {
+  }): Promise<ListReceiptTransfersResponse> => {
     return this.client.queryContractSmart(this.contractAddress, {
-      list_payments_to_id: {
+      list_receipt_transfers: {
         id,
         limit,
         start_after: startAfter
       }
     });
   };
-  listTotalsPaidToId = async ({
+  hasPaid = async ({
     id,
+    payer,
+    requirements
+  }: {
+    id: string;
+    payer: string;
+    requirements: Total[];
+  }): Promise<HasPaidResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      has_paid: {
+        id,
+        payer,
+        requirements
+      }
+    });
+  };
+  listAdminActions = async ({
     limit,
     startAfter
   }: {
-    id: string;
     limit?: number;
-    startAfter?: CheckedDenom;
-  }): Promise<ListTotalsPaidToIdResponse> => {
+    startAfter?: number;
+  }): Promise<ListAdminActionsResponse> => {
     return this.client.queryContractSmart(this.contractAddress, {
-      list_totals_paid_to_id: {
-        id,
+      list_admin_actions: {
         limit,
         start_after: startAfter
       }
     });
   };
-  listIdsForPayer = async ({
+  canonicalId = async ({
+    id,
+    payer
+  }: {
+    id: string;
+    payer: string;
+  }): Promise<CanonicalIdResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      canonical_id: {
+        id,
+        payer
+      }
+    });
+  };
+  forwarding = async (): Promise<ForwardingResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      forwarding: {}
+    });
+  };
+  listUnforwarded = async ({
     limit,
-    payer,
     startAfter
   }: {
     limit?: number;
-    payer: string;
-    startAfter?: string;
-  }): Promise<ListIdsForPayerResponse> => {
+    startAfter?: CheckedDenom;
+  }): Promise<ListUnforwardedResponse> => {
     return this.client.queryContractSmart(this.contractAddress, {
-      list_ids_for_payer: {
+      list_unforwarded: {
         limit,
-        payer,
         start_after: startAfter
       }
     });
   };
-  listTotalsPaidByPayer = async ({
+  listOwedToOutput = async ({
     limit,
-    payer,
     startAfter
   }: {
     limit?: number;
-    payer: string;
     startAfter?: CheckedDenom;
-  }): Promise<ListTotalsPaidByPayerResponse> => {
+  }): Promise<ListOwedToOutputResponse> => {
     return this.client.queryContractSmart(this.contractAddress, {
-      list_totals_paid_by_payer: {
+      list_owed_to_output: {
         limit,
-        payer,
         start_after: startAfter
       }
     });
   };
-  ownership = async (): Promise<OwnershipForString> => {
+  sweepable = async ({
+    denom
+  }: {
+    denom: UncheckedDenom;
+  }): Promise<SweepableResponse> => {
     return this.client.queryContractSmart(this.contractAddress, {
-      ownership: {}
+      sweepable: {
+        denom
+      }
     });
   };
-}
-export interface CwReceiptInterface extends CwReceiptReadOnlyInterface {
-  contractAddress: string;
-  sender: string;
-  receive: ({
-    amount,
-    msg,
-    sender
+  getPeriodStatement = async ({
+    period
   }: {
-    amount: Uint128;
-    msg: Binary;
-    sender: string;
-  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
-  pay: ({
-    id
+    period: number;
+  }): Promise<PeriodStatementResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      get_period_statement: {
+        period
+      }
+    });
+  };
+  listPeriodPayerTotals = async ({
+    limit,
+    period,
+    startAfter
   }: {
-    id: string;
-  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
-  updateOutput: ({
-    output
+    limit?: number;
+    period: number;
+    startAfter?: string;
+  }): Promise<ListPeriodPayerTotalsResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      list_period_payer_totals: {
+        limit,
+        period,
+        start_after: startAfter
+      }
+    });
+  };
+  listPeriods = async ({
+    limit,
+    startAfter
   }: {
-    output: string;
-  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
-  updateOwnership: (fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
-}
-export class CwReceiptClient extends CwReceiptQueryClient implements CwReceiptInterface {
-  client: SigningCosmWasmClient;
-  sender: string;
-  contractAddress: string;
-
-  constructor(client: SigningCosmWasmClient, sender: string, contractAddress: string) {
-    super(client, contractAddress);
-    this.client = client;
-    this.sender = sender;
-    this.contractAddress = contractAddress;
-    this.receive = this.receive.bind(this);
-    this.pay = this.pay.bind(this);
-    this.updateOutput = this.updateOutput.bind(this);
-    this.updateOwnership = this.updateOwnership.bind(this);
-  }
-
-  receive = async ({
-    amount,
-    msg,
-    sender
+    limit?: number;
+    startAfter?: number;
+  }): Promise<ListPeriodsResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      list_periods: {
+        limit,
+        start_after: startAfter
+      }
+    });
+  };
+  listSignerKeys = async ({
+    limit,
+    startAfter
   }: {
-    amount: Uint128;
-    msg: Binary;
-    sender: string;
-  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
-    return await this.client.execute(this.sender, this.contractAddress, {
-      receive: {
-        amount,
-        msg,
-        sender
+    limit?: number;
+    startAfter?: Binary;
+  }): Promise<ListSignerKeysResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      list_signer_keys: {
+        limit,
+        start_after: startAfter
       }
-    }, fee, memo, funds);
+    });
   };
-  pay = async ({
+  product = async ({
+    sku
+  }: {
+    sku: string;
+  }): Promise<ProductResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      product: {
+        sku
+      }
+    });
+  };
+  campaign = async ({
     id
   }: {
     id: string;
-  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
-    return await this.client.execute(this.sender, this.contractAddress, {
-      pay: {
+  }): Promise<CampaignResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      campaign: {
         id
       }
-    }, fee, memo, funds);
+    });
   };
-  updateOutput = async ({
-    output
+  campaignContributions = async ({
+    id,
+    payer
   }: {
-    output: string;
-  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
-    return await this.client.execute(this.sender, this.contractAddress, {
-      update_output: {
-        output
+    id: string;
+    payer: string;
+  }): Promise<CampaignContributionsResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      campaign_contributions: {
+        id,
+        payer
+      }
+    });
+  };
+  productStock = async ({
+    sku
+  }: {
+    sku: string;
+  }): Promise<ProductStockResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      product_stock: {
+        sku
+      }
+    });
+  };
+  listProducts = async ({
+    limit,
+    startAfter
+  }: {
+    limit?: number;
+    startAfter?: string;
+  }): Promise<ListProductsResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      list_products: {
+        limit,
+        start_after: startAfter
+      }
+    });
+  };
+  listReceiptIds = async ({
+    limit,
+    prefix,
+    startAfter
+  }: {
+    limit?: number;
+    prefix?: string;
+    startAfter?: string;
+  }): Promise<ListReceiptIdsResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      list_receipt_ids: {
+        limit,
+        prefix,
+        start_after: startAfter
+      }
+    });
+  };
+  listPayments = async ({
+    limit,
+    startAfter
+  }: {
+    limit?: number;
+    startAfter?: [string, number];
+  }): Promise<ListPaymentsResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      list_payments: {
+        limit,
+        start_after: startAfter
+      }
+    });
+  };
+  listPaymentsToId = async ({
+    id,
+    limit,
+    startAfter
+  }: {
+    id: string;
+    limit?: number;
+    startAfter?: number;
+  }): Promise<ListPaymentsToIdResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      list_payments_to_id: {
+        id,
+        limit,
+        start_after: startAfter
+      }
+    });
+  };
+  listTotalsPaidToId = async ({
+    height,
+    id,
+    limit,
+    startAfter
+  }: {
+    height?: number;
+    id: string;
+    limit?: number;
+    startAfter?: CheckedDenom;
+  }): Promise<ListTotalsPaidToIdResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      list_totals_paid_to_id: {
+        height,
+        id,
+        limit,
+        start_after: startAfter
+      }
+    });
+  };
+  listIdsForPayer = async ({
+    limit,
+    payer,
+    startAfter
+  }: {
+    limit?: number;
+    payer: string;
+    startAfter?: string;
+  }): Promise<ListIdsForPayerResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      list_ids_for_payer: {
+        limit,
+        payer,
+        start_after: startAfter
+      }
+    });
+  };
+  listTotalsPaidByPayer = async ({
+    height,
+    limit,
+    payer,
+    startAfter
+  }: {
+    height?: number;
+    limit?: number;
+    payer: string;
+    startAfter?: CheckedDenom;
+  }): Promise<ListTotalsPaidByPayerResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      list_totals_paid_by_payer: {
+        height,
+        limit,
+        payer,
+        start_after: startAfter
+      }
+    });
+  };
+  ownership = async (): Promise<OwnershipForString> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      ownership: {}
+    });
+  };
+}
+export interface CwReceiptInterface extends CwReceiptReadOnlyInterface {
+  contractAddress: string;
+  sender: string;
+  receive: ({
+    amount,
+    msg,
+    sender
+  }: {
+    amount: Uint128;
+    msg: Binary;
+    sender: string;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  pay: ({
+    id,
+    idempotencyKey
+  }: {
+    id: string;
+    idempotencyKey?: string;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  createReceipt: ({
+    metadata
+  }: {
+    metadata?: string;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  payNew: (fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  updateOutput: ({
+    output
+  }: {
+    output: string;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  updateIdRules: ({
+    idRules
+  }: {
+    idRules?: IdRules;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  assignPayer: ({
+    id,
+    payer
+  }: {
+    id: string;
+    payer?: string;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  closeReceipt: ({
+    id,
+    reason
+  }: {
+    id: string;
+    reason: string;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  reopenReceipt: ({
+    id
+  }: {
+    id: string;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  transferReceipt: ({
+    id,
+    newPayer
+  }: {
+    id: string;
+    newPayer: string;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  voidPayment: ({
+    id,
+    paymentIndex,
+    reason,
+    restock
+  }: {
+    id: string;
+    paymentIndex: number;
+    reason: string;
+    restock?: boolean;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  updateForwarding: ({
+    forwarding
+  }: {
+    forwarding: ForwardingConfig;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  flushUnforwarded: (fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  settle: ({
+    denoms
+  }: {
+    denoms: UncheckedDenom[];
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  closePeriod: (fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  sweep: ({
+    amount,
+    denom,
+    recipient
+  }: {
+    amount?: Uint128;
+    denom: UncheckedDenom;
+    recipient: string;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  payWithSignedRequest: ({
+    publicKey,
+    request,
+    signature
+  }: {
+    publicKey: Binary;
+    request: PaymentRequest;
+    signature: Binary;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  addSignerKey: ({
+    publicKey
+  }: {
+    publicKey: Binary;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  removeSignerKey: ({
+    publicKey
+  }: {
+    publicKey: Binary;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  setProduct: ({
+    active,
+    name,
+    prices,
+    sku
+  }: {
+    active: boolean;
+    name: string;
+    prices: UncheckedPrice[];
+    sku: string;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  setProductStock: ({
+    sku,
+    stock
+  }: {
+    sku: string;
+    stock?: number;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  payForItems: ({
+    id,
+    items
+  }: {
+    id: string;
+    items: [string, number][];
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  createCampaign: ({
+    deadline,
+    goal,
+    id
+  }: {
+    deadline: Expiration;
+    goal: UncheckedPrice[];
+    id: string;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  finalize: ({
+    id
+  }: {
+    id: string;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  claimRefund: ({
+    id
+  }: {
+    id: string;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  assignArbiter: ({
+    arbiter,
+    disputeWindow,
+    id
+  }: {
+    arbiter: string;
+    disputeWindow: Duration;
+    id: string;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  openDispute: ({
+    id,
+    reason
+  }: {
+    id: string;
+    reason: string;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  resolveDispute: ({
+    id,
+    releaseBps
+  }: {
+    id: string;
+    releaseBps: number;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  releaseEscrow: ({
+    id
+  }: {
+    id: string;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  updateOwnership: (fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+}
+export class CwReceiptClient extends CwReceiptQueryClient implements CwReceiptInterface {
+  client: SigningCosmWasmClient;
+  sender: string;
+  contractAddress: string;
+
+  constructor(client: SigningCosmWasmClient, sender: string, contractAddress: string) {
+    super(client, contractAddress);
+    this.client = client;
+    this.sender = sender;
+    this.contractAddress = contractAddress;
+    this.receive = this.receive.bind(this);
+    this.pay = this.pay.bind(this);
+    this.createReceipt = this.createReceipt.bind(this);
+    this.payNew = this.payNew.bind(this);
+    this.updateOutput = this.updateOutput.bind(this);
+    this.updateIdRules = this.updateIdRules.bind(this);
+    this.assignPayer = this.assignPayer.bind(this);
+    this.closeReceipt = this.closeReceipt.bind(this);
+    this.reopenReceipt = this.reopenReceipt.bind(this);
+    this.transferReceipt = this.transferReceipt.bind(this);
+    this.voidPayment = this.voidPayment.bind(this);
+    this.updateForwarding = this.updateForwarding.bind(this);
+    this.flushUnforwarded = this.flushUnforwarded.bind(this);
+    this.settle = this.settle.bind(this);
+    this.closePeriod = this.closePeriod.bind(this);
+    this.sweep = this.sweep.bind(this);
+    this.payWithSignedRequest = this.payWithSignedRequest.bind(this);
+    this.addSignerKey = this.addSignerKey.bind(this);
+    this.removeSignerKey = this.removeSignerKey.bind(this);
+    this.setProduct = this.setProduct.bind(this);
+    this.setProductStock = this.setProductStock.bind(this);
+    this.payForItems = this.payForItems.bind(this);
+    this.createCampaign = this.createCampaign.bind(this);
+    this.finalize = this.finalize.bind(this);
+    this.claimRefund = this.claimRefund.bind(this);
+    this.assignArbiter = this.assignArbiter.bind(this);
+    this.openDispute = this.openDispute.bind(this);
+    this.resolveDispute = this.resolveDispute.bind(this);
+    this.releaseEscrow = this.releaseEscrow.bind(this);
+    this.updateOwnership = this.updateOwnership.bind(this);
+  }
+
+  receive = async ({
+    amount,
+    msg,
+    sender
+  }: {
+    amount: Uint128;
+    msg: Binary;
+    sender: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      receive: {
+        amount,
+        msg,
+        sender
+      }
+    }, fee, memo, funds);
+  };
+  pay = async ({
+    id,
+    idempotencyKey
+  }: {
+    id: string;
+    idempotencyKey?: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      pay: {
+        id,
+        idempotency_key: idempotencyKey
+      }
+    }, fee, memo, funds);
+  };
+  createReceipt = async ({
+    metadata
+  }: {
+    metadata?: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      create_receipt: {
+        metadata
+      }
+    }, fee, memo, funds);
+  };
+  payNew = async (fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      pay_new: {}
+    }, fee, memo, funds);
+  };
+  updateOutput = async ({
+    output
+  }: {
+    output: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      update_output: {
+        output
+      }
+    }, fee, memo, funds);
+  };
+  updateIdRules = async ({
+    idRules
+  }: {
+    idRules?: IdRules;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      update_id_rules: {
+        id_rules: idRules
+      }
+    }, fee, memo, funds);
+  };
+  assignPayer = async ({
+    id,
+    payer
+  }: {
+    id: string;
+    payer?: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      assign_payer: {
+        id,
+        payer
+      }
+    }, fee, memo, funds);
+  };
+  closeReceipt = async ({
+    id,
+    reason
+  }: {
+    id: string;
+    reason: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      close_receipt: {
+        id,
+        reason
+      }
+    }, fee, memo, funds);
+  };
+  reopenReceipt = async ({
+    id
+  }: {
+    id: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      reopen_receipt: {
+        id
+      }
+    }, fee, memo, funds);
+  };
+  transferReceipt = async ({
+    id,
+    newPayer
+  }: {
+    id: string;
+    newPayer: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      transfer_receipt: {
+        id,
+        new_payer: newPayer
+      }
+    }, fee, memo, funds);
+  };
+  voidPayment = async ({
+    id,
+    paymentIndex,
+    reason,
+    restock
+  }: {
+    id: string;
+    paymentIndex: number;
+    reason: string;
+    restock?: boolean;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      void_payment: {
+        id,
+        payment_index: paymentIndex,
+        reason,
+        restock
+      }
+    }, fee, memo, funds);
+  };
+  updateForwarding = async ({
+    forwarding
+  }: {
+    forwarding: ForwardingConfig;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      update_forwarding: {
+        forwarding
+      }
+    }, fee, memo, funds);
+  };
+  flushUnforwarded = async (fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      flush_unforwarded: {}
+    }, fee, memo, funds);
+  };
+  settle = async ({
+    denoms
+  }: {
+    denoms: UncheckedDenom[];
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      settle: {
+        denoms
+      }
+    }, fee, memo, funds);
+  };
+  closePeriod = async (fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      close_period: {}
+    }, fee, memo, funds);
+  };
+  sweep = async ({
+    amount,
+    denom,
+    recipient
+  }: {
+    amount?: Uint128;
+    denom: UncheckedDenom;
+    recipient: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      sweep: {
+        amount,
+        denom,
+        recipient
+      }
+    }, fee, memo, funds);
+  };
+  payWithSignedRequest = async ({
+    publicKey,
+    request,
+    signature
+  }: {
+    publicKey: Binary;
+    request: PaymentRequest;
+    signature: Binary;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      pay_with_signed_request: {
+        public_key: publicKey,
+        request,
+        signature
+      }
+    }, fee, memo, funds);
+  };
+  addSignerKey = async ({
+    publicKey
+  }: {
+    publicKey: Binary;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      add_signer_key: {
+        public_key: publicKey
+      }
+    }, fee, memo, funds);
+  };
+  removeSignerKey = async ({
+    publicKey
+  }: {
+    publicKey: Binary;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      remove_signer_key: {
+        public_key: publicKey
+      }
+    }, fee, memo, funds);
+  };
+  setProduct = async ({
+    active,
+    name,
+    prices,
+    sku
+  }: {
+    active: boolean;
+    name: string;
+    prices: UncheckedPrice[];
+    sku: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      set_product: {
+        active,
+        name,
+        prices,
+        sku
+      }
+    }, fee, memo, funds);
+  };
+  setProductStock = async ({
+    sku,
+    stock
+  }: {
+    sku: string;
+    stock?: number;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      set_product_stock: {
+        sku,
+        stock
+      }
+    }, fee, memo, funds);
+  };
+  payForItems = async ({
+    id,
+    items
+  }: {
+    id: string;
+    items: [string, number][];
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      pay_for_items: {
+        id,
+        items
+      }
+    }, fee, memo, funds);
+  };
+  createCampaign = async ({
+    deadline,
+    goal,
+    id
+  }: {
+    deadline: Expiration;
+    goal: UncheckedPrice[];
+    id: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      create_campaign: {
+        deadline,
+        goal,
+        id
+      }
+    }, fee, memo, funds);
+  };
+  finalize = async ({
+    id
+  }: {
+    id: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      finalize: {
+        id
+      }
+    }, fee, memo, funds);
+  };
+  claimRefund = async ({
+    id
+  }: {
+    id: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      claim_refund: {
+        id
+      }
+    }, fee, memo, funds);
+  };
+  assignArbiter = async ({
+    arbiter,
+    disputeWindow,
+    id
+  }: {
+    arbiter: string;
+    disputeWindow: Duration;
+    id: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      assign_arbiter: {
+        arbiter,
+        dispute_window: disputeWindow,
+        id
+      }
+    }, fee, memo, funds);
+  };
+  openDispute = async ({
+    id,
+    reason
+  }: {
+    id: string;
+    reason: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      open_dispute: {
+        id,
+        reason
+      }
+    }, fee, memo, funds);
+  };
+  resolveDispute = async ({
+    id,
+    releaseBps
+  }: {
+    id: string;
+    releaseBps: number;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      resolve_dispute: {
+        id,
+        release_bps: releaseBps
+      }
+    }, fee, memo, funds);
+  };
+  releaseEscrow = async ({
+    id
+  }: {
+    id: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      release_escrow: {
+        id
       }
     }, fee, memo, funds);
   };
@@ -8,7 +8,7 @@ import { Coin } from "@cosmjs/amino";
 import { MsgExecuteContractEncodeObject } from "cosmwasm";
 import { MsgExecuteContract } from "cosmjs-types/cosmwasm/wasm/v1/tx";
 import { toUtf8 } from "@cosmjs/encoding";
-import { InstantiateMsg, ExecuteMsg, Uint128, Binary, Action, Expiration, Timestamp, Uint64, Cw20ReceiveMsg, QueryMsg, CheckedDenom, Addr, ListIdsForPayerResponse, ListPaymentsToIdResponse, PaymentWithId, Payment, BlockInfo, ListTotalsPaidByPayerResponse, Total, ListTotalsPaidToIdResponse, OutputResponse, OwnershipForString } from "./CwReceipt.types";
+import { Uint128, CheckedDenom, Addr, Decimal, IdCase, IdCharset, InstantiateMsg, ForwardingConfig, BatchConfig, Total, SettlementConfig, ReferencePrice, IdRules, ExecuteMsg, Binary, UncheckedDenom, Expiration, Timestamp, Uint64, Duration, Action, Cw20ReceiveMsg, PaymentRequest, UncheckedPrice, QueryMsg, MigrateMsg, CampaignResponse, Campaign, CampaignContributionsResponse, CanonicalIdResponse, ForwardingResponse, PeriodStatementResponse, Period, HasPaidResponse, IdRulesResponse, AdminAction, ListAdminActionsResponse, AdminLogItem, AdminLogEntry, OwnershipForAddr, Closure, Product, Escrow, Dispute, ListIdsForPayerResponse, ListOwedToOutputResponse, ListPaymentsResponse, ReceiptPayment, Payment, LineItem, Void, ListPaymentsToIdResponse, ReceiptPaymentWithoutId, ListPeriodPayerTotalsResponse, PayerTotals, ListPeriodsResponse, PeriodItem, ListProductsResponse, ProductItem, ListReceiptIdsResponse, ReceiptIdItem, ListReceiptTransfersResponse, ReceiptTransfer, Transfer, ListSignerKeysResponse, ListTotalsPaidByPayerResponse, ListTotalsPaidToIdResponse, ListUnforwardedResponse, OutputResponse, OwnershipForString, ProductResponse, ProductStockResponse, ReceiptResponse, Creation, ReceiptEscrow, ReceiptPayerResponse, SweepableResponse } from "./CwReceipt.types";
 export interface CwReceiptMessage {
   contractAddress: string;
   sender: string;
@@ -22,15 +22,177 @@ export interface CwReceiptMessage {
     sender: string;
   }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
   pay: ({
-    id
+    id,
+    idempotencyKey
   }: {
     id: string;
+    idempotencyKey?: string;
+  }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  createReceipt: ({
+    metadata
+  }: {
+    metadata?: string;
   }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  payNew: (funds?: Coin[]) => MsgExecuteContractEncodeObject;
   updateOutput: ({
     output
   }: {
     output: string;
   }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  updateIdRules: ({
+    idRules
+  }: {
+    idRules?: IdRules;
+  }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  assignPayer: ({
+    id,
+    payer
+  }: {
+    id: string;
+    payer?: string;
+  }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  closeReceipt: ({
+    id,
+    reason
+  }: {
+    id: string;
+    reason: string;
+  }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  reopenReceipt: ({
+    id
+  }: {
+    id: string;
+  }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  transferReceipt: ({
+    id,
+    newPayer
+  }: {
+    id: string;
+    newPayer: string;
+  }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  voidPayment: ({
+    id,
+    paymentIndex,
+    reason,
+    restock
+  }: {
+    id: string;
+    paymentIndex: number;
+    reason: string;
+    restock?: boolean;
+  }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  updateForwarding: ({
+    forwarding
+  }: {
+    forwarding: ForwardingConfig;
+  }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  flushUnforwarded: (funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  settle: ({
+    denoms
+  }: {
+    denoms: UncheckedDenom[];
+  }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  closePeriod: (funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  sweep: ({
+    amount,
+    denom,
+    recipient
+  }: {
+    amount?: Uint128;
+    denom: UncheckedDenom;
+    recipient: string;
+  }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  payWithSignedRequest: ({
+    publicKey,
+    request,
+    signature
+  }: {
+    publicKey: Binary;
+    request: PaymentRequest;
+    signature: Binary;
+  }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  addSignerKey: ({
+    publicKey
+  }: {
+    publicKey: Binary;
+  }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  removeSignerKey: ({
+    publicKey
+  }: {
+    publicKey: Binary;
+  }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  setProduct: ({
+    active,
+    name,
+    prices,
+    sku
+  }: {
+    active: boolean;
+    name: string;
+    prices: UncheckedPrice[];
+    sku: string;
+  }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  setProductStock: ({
+    sku,
+    stock
+  }: {
+    sku: string;
+    stock?: number;
+  }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  payForItems: ({
+    id,
+    items
+  }: {
+    id: string;
+    items: [string, number][];
+  }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  createCampaign: ({
+    deadline,
+    goal,
+    id
+  }: {
+    deadline: Expiration;
+    goal: UncheckedPrice[];
+    id: string;
+  }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  finalize: ({
+    id
+  }: {
+    id: string;
+  }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  claimRefund: ({
+    id
+  }: {
+    id: string;
+  }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  assignArbiter: ({
+    arbiter,
+    disputeWindow,
+    id
+  }: {
+    arbiter: string;
+    disputeWindow: Duration;
+    id: string;
+  }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  openDispute: ({
+    id,
+    reason
+  }: {
+    id: string;
+    reason: string;
+  }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  resolveDispute: ({
+    id,
+    releaseBps
+  }: {
+    id: string;
+    releaseBps: number;
+  }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  releaseEscrow: ({
+    id
+  }: {
+    id: string;
+  }, funds?: Coin[]) => MsgExecuteContractEncodeObject;
   updateOwnership: (funds?: Coin[]) => MsgExecuteContractEncodeObject;
 }
 export class CwReceiptMessageComposer implements CwReceiptMessage {
@@ -42,7 +204,33 @@ export class CwReceiptMessageComposer implements CwReceiptMessage {
     this.contractAddress = contractAddress;
     this.receive = this.receive.bind(this);
     this.pay = this.pay.bind(this);
+    this.createReceipt = this.createReceipt.bind(this);
+    this.payNew = this.payNew.bind(this);
     this.updateOutput = this.updateOutput.bind(this);
+    this.updateIdRules = this.updateIdRules.bind(this);
+    this.assignPayer = this.assignPayer.bind(this);
+    this.closeReceipt = this.closeReceipt.bind(this);
+    this.reopenReceipt = this.reopenReceipt.bind(this);
+    this.transferReceipt = this.transferReceipt.bind(this);
+    this.voidPayment = this.voidPayment.bind(this);
+    this.updateForwarding = this.updateForwarding.bind(this);
+    this.flushUnforwarded = this.flushUnforwarded.bind(this);
+    this.settle = this.settle.bind(this);
+    this.closePeriod = this.closePeriod.bind(this);
+    this.sweep = this.sweep.bind(this);
+    this.payWithSignedRequest = this.payWithSignedRequest.bind(this);
+    this.addSignerKey = this.addSignerKey.bind(this);
+    this.removeSignerKey = this.removeSignerKey.bind(this);
+    this.setProduct = this.setProduct.bind(this);
+    this.setProductStock = this.setProductStock.bind(this);
+    this.payForItems = this.payForItems.bind(this);
+    this.createCampaign = this.createCampaign.bind(this);
+    this.finalize = this.finalize.bind(this);
+    this.claimRefund = this.claimRefund.bind(this);
+    this.assignArbiter = this.assignArbiter.bind(this);
+    this.openDispute = this.openDispute.bind(this);
+    this.resolveDispute = this.resolveDispute.bind(this);
+    this.releaseEscrow = this.releaseEscrow.bind(this);
     this.updateOwnership = this.updateOwnership.bind(this);
   }
 
@@ -72,9 +260,11 @@ export class CwReceiptMessageComposer implements CwReceiptMessage {
     };
   };
   pay = ({
-    id
+    id,
+    idempotencyKey
   }: {
     id: string;
+    idempotencyKey?: string;
   }, funds?: Coin[]): MsgExecuteContractEncodeObject => {
     return {
       typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
@@ -83,13 +273,46 @@ export class CwReceiptMessageComposer implements CwReceiptMessage {
         contract: this.contractAddress,
         msg: toUtf8(JSON.stringify({
           pay: {
-            id
+            id,
+            idempotency_key: idempotencyKey
+          }
+        })),
+        funds
+      })
+    };
+  };
+  createReceipt = ({
+    metadata
+  }: {
+    metadata?: string;
+  }, funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          create_receipt: {
+            metadata
           }
         })),
         funds
       })
     };
   };
+  payNew = (funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          pay_new: {}
+        })),
+        funds
+      })
+    };
+  };
   updateOutput = ({
     output
   }: {
@@ -109,6 +332,513 @@ export class CwReceiptMessageComposer implements CwReceiptMessage {
       })
     };
   };
+  updateIdRules = ({
+    idRules
+  }: {
+    idRules?: IdRules;
+  }, funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          update_id_rules: {
+            id_rules: idRules
+          }
+        })),
+        funds
+      })
+    };
+  };
+  assignPayer = ({
+    id,
+    payer
+  }: {
+    id: string;
+    payer?: string;
+  }, funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          assign_payer: {
+            id,
+            payer
+          }
+        })),
+        funds
+      })
+    };
+  };
+  closeReceipt = ({
+    id,
+    reason
+  }: {
+    id: string;
+    reason: string;
+  }, funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          close_receipt: {
+            id,
+            reason
+          }
+        })),
+        funds
+      })
+    };
+  };
+  reopenReceipt = ({
+    id
+  }: {
+    id: string;
+  }, funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          reopen_receipt: {
+            id
+          }
+        })),
+        funds
+      })
+    };
+  };
+  transferReceipt = ({
+    id,
+    newPayer
+  }: {
+    id: string;
+    newPayer: string;
+  }, funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          transfer_receipt: {
+            id,
+            new_payer: newPayer
+          }
+        })),
+        funds
+      })
+    };
+  };
+  voidPayment = ({
+    id,
+    paymentIndex,
+    reason,
+    restock
+  }: {
+    id: string;
+    paymentIndex: number;
+    reason: string;
+    restock?: boolean;
+  }, funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          void_payment: {
+            id,
+            payment_index: paymentIndex,
+            reason,
+            restock
+          }
+        })),
+        funds
+      })
+    };
+  };
+  updateForwarding = ({
+    forwarding
+  }: {
+    forwarding: ForwardingConfig;
+  }, funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          update_forwarding: {
+            forwarding
+          }
+        })),
+        funds
+      })
+    };
+  };
+  flushUnforwarded = (funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          flush_unforwarded: {}
+        })),
+        funds
+      })
+    };
+  };
+  settle = ({
+    denoms
+  }: {
+    denoms: UncheckedDenom[];
+  }, funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          settle: {
+            denoms
+          }
+        })),
+        funds
+      })
+    };
+  };
+  closePeriod = (funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          close_period: {}
+        })),
+        funds
+      })
+    };
+  };
+  sweep = ({
+    amount,
+    denom,
+    recipient
+  }: {
+    amount?: Uint128;
+    denom: UncheckedDenom;
+    recipient: string;
+  }, funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          sweep: {
+            amount,
+            denom,
+            recipient
+          }
+        })),
+        funds
+      })
+    };
+  };
+  payWithSignedRequest = ({
+    publicKey,
+    request,
+    signature
+  }: {
+    publicKey: Binary;
+    request: PaymentRequest;
+    signature: Binary;
+  }, funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          pay_with_signed_request: {
+            public_key: publicKey,
+            request,
+            signature
+          }
+        })),
+        funds
+      })
+    };
+  };
+  addSignerKey = ({
+    publicKey
+  }: {
+    publicKey: Binary;
+  }, funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          add_signer_key: {
+            public_key: publicKey
+          }
+        })),
+        funds
+      })
+    };
+  };
+  removeSignerKey = ({
+    publicKey
+  }: {
+    publicKey: Binary;
+  }, funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          remove_signer_key: {
+            public_key: publicKey
+          }
+        })),
+        funds
+      })
+    };
+  };
+  setProduct = ({
+    active,
+    name,
+    prices,
+    sku
+  }: {
+    active: boolean;
+    name: string;
+    prices: UncheckedPrice[];
+    sku: string;
+  }, funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          set_product: {
+            active,
+            name,
+            prices,
+            sku
+          }
+        })),
+        funds
+      })
+    };
+  };
+  setProductStock = ({
+    sku,
+    stock
+  }: {
+    sku: string;
+    stock?: number;
+  }, funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          set_product_stock: {
+            sku,
+            stock
+          }
+        })),
+        funds
+      })
+    };
+  };
+  payForItems = ({
+    id,
+    items
+  }: {
+    id: string;
+    items: [string, number][];
+  }, funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          pay_for_items: {
+            id,
+            items
+          }
+        })),
+        funds
+      })
+    };
+  };
+  createCampaign = ({
+    deadline,
+    goal,
+    id
+  }: {
+    deadline: Expiration;
+    goal: UncheckedPrice[];
+    id: string;
+  }, funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          create_campaign: {
+            deadline,
+            goal,
+            id
+          }
+        })),
+        funds
+      })
+    };
+  };
+  finalize = ({
+    id
+  }: {
+    id: string;
+  }, funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          finalize: {
+            id
+          }
+        })),
+        funds
+      })
+    };
+  };
+  claimRefund = ({
+    id
+  }: {
+    id: string;
+  }, funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          claim_refund: {
+            id
+          }
+        })),
+        funds
+      })
+    };
+  };
+  assignArbiter = ({
+    arbiter,
+    disputeWindow,
+    id
+  }: {
+    arbiter: string;
+    disputeWindow: Duration;
+    id: string;
+  }, funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          assign_arbiter: {
+            arbiter,
+            dispute_window: disputeWindow,
+            id
+          }
+        })),
+        funds
+      })
+    };
+  };
+  openDispute = ({
+    id,
+    reason
+  }: {
+    id: string;
+    reason: string;
+  }, funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          open_dispute: {
+            id,
+            reason
+          }
+        })),
+        funds
+      })
+    };
+  };
+  resolveDispute = ({
+    id,
+    releaseBps
+  }: {
+    id: string;
+    releaseBps: number;
+  }, funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          resolve_dispute: {
+            id,
+            release_bps: releaseBps
+          }
+        })),
+        funds
+      })
+    };
+  };
+  releaseEscrow = ({
+    id
+  }: {
+    id: string;
+  }, funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          release_escrow: {
+            id
+          }
+        })),
+        funds
+      })
+    };
+  };
   updateOwnership = (funds?: Coin[]): MsgExecuteContractEncodeObject => {
     return {
       typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
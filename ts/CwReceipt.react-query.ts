@@ -7,7 +7,7 @@
 import { UseQueryOptions, useQuery, useMutation, UseMutationOptions } from "@tanstack/react-query";
 import { ExecuteResult } from "@cosmjs/cosmwasm-stargate";
 import { StdFee, Coin } from "@cosmjs/amino";
-import { InstantiateMsg, ExecuteMsg, Uint128, Binary, Action, Expiration, Timestamp, Uint64, Cw20ReceiveMsg, QueryMsg, CheckedDenom, Addr, ListIdsForPayerResponse, ListPaymentsToIdResponse, PaymentWithId, Payment, BlockInfo, ListTotalsPaidByPayerResponse, Total, ListTotalsPaidToIdResponse, OutputResponse, OwnershipForString } from "./CwReceipt.types";
+import { Uint128, CheckedDenom, Addr, Decimal, IdCase, IdCharset, InstantiateMsg, ForwardingConfig, BatchConfig, Total, SettlementConfig, ReferencePrice, IdRules, ExecuteMsg, Binary, UncheckedDenom, Expiration, Timestamp, Uint64, Duration, Action, Cw20ReceiveMsg, PaymentRequest, UncheckedPrice, QueryMsg, MigrateMsg, CampaignResponse, Campaign, CampaignContributionsResponse, CanonicalIdResponse, ForwardingResponse, PeriodStatementResponse, Period, HasPaidResponse, IdRulesResponse, AdminAction, ListAdminActionsResponse, AdminLogItem, AdminLogEntry, OwnershipForAddr, Closure, Product, Escrow, Dispute, ListIdsForPayerResponse, ListOwedToOutputResponse, ListPaymentsResponse, ReceiptPayment, Payment, LineItem, Void, ListPaymentsToIdResponse, ReceiptPaymentWithoutId, ListPeriodPayerTotalsResponse, PayerTotals, ListPeriodsResponse, PeriodItem, ListProductsResponse, ProductItem, ListReceiptIdsResponse, ReceiptIdItem, ListReceiptTransfersResponse, ReceiptTransfer, Transfer, ListSignerKeysResponse, ListTotalsPaidByPayerResponse, ListTotalsPaidToIdResponse, ListUnforwardedResponse, OutputResponse, OwnershipForString, ProductResponse, ProductStockResponse, ReceiptResponse, Creation, ReceiptEscrow, ReceiptPayerResponse, SweepableResponse } from "./CwReceipt.types";
 import { CwReceiptQueryClient, CwReceiptClient } from "./CwReceipt.client";
 export const cwReceiptQueryKeys = {
   contract: ([{
@@ -20,6 +20,94 @@ export const cwReceiptQueryKeys = {
     method: "output",
     args
   }] as const),
+  idRules: (contractAddress: string, args?: Record<string, unknown>) => ([{ ...cwReceiptQueryKeys.address(contractAddress)[0],
+    method: "id_rules",
+    args
+  }] as const),
+  receipt: (contractAddress: string, args?: Record<string, unknown>) => ([{ ...cwReceiptQueryKeys.address(contractAddress)[0],
+    method: "receipt",
+    args
+  }] as const),
+  receiptPayer: (contractAddress: string, args?: Record<string, unknown>) => ([{ ...cwReceiptQueryKeys.address(contractAddress)[0],
+    method: "receipt_payer",
+    args
+  }] as const),
+  listReceiptTransfers: (contractAddress: string, args?: Record<string, unknown>) => ([{ ...cwReceiptQueryKeys.address(contractAddress)[0],
+    method: "list_receipt_transfers",
+    args
+  }] as const),
+  hasPaid: (contractAddress: string, args?: Record<string, unknown>) => ([{ ...cwReceiptQueryKeys.address(contractAddress)[0],
+    method: "has_paid",
+    args
+  }] as const),
+  listAdminActions: (contractAddress: string, args?: Record<string, unknown>) => ([{ ...cwReceiptQueryKeys.address(contractAddress)[0],
+    method: "list_admin_actions",
+    args
+  }] as const),
+  canonicalId: (contractAddress: string, args?: Record<string, unknown>) => ([{ ...cwReceiptQueryKeys.address(contractAddress)[0],
+    method: "canonical_id",
+    args
+  }] as const),
+  forwarding: (contractAddress: string, args?: Record<string, unknown>) => ([{ ...cwReceiptQueryKeys.address(contractAddress)[0],
+    method: "forwarding",
+    args
+  }] as const),
+  listUnforwarded: (contractAddress: string, args?: Record<string, unknown>) => ([{ ...cwReceiptQueryKeys.address(contractAddress)[0],
+    method: "list_unforwarded",
+    args
+  }] as const),
+  listOwedToOutput: (contractAddress: string, args?: Record<string, unknown>) => ([{ ...cwReceiptQueryKeys.address(contractAddress)[0],
+    method: "list_owed_to_output",
+    args
+  }] as const),
+  sweepable: (contractAddress: string, args?: Record<string, unknown>) => ([{ ...cwReceiptQueryKeys.address(contractAddress)[0],
+    method: "sweepable",
+    args
+  }] as const),
+  getPeriodStatement: (contractAddress: string, args?: Record<string, unknown>) => ([{ ...cwReceiptQueryKeys.address(contractAddress)[0],
+    method: "get_period_statement",
+    args
+  }] as const),
+  listPeriodPayerTotals: (contractAddress: string, args?: Record<string, unknown>) => ([{ ...cwReceiptQueryKeys.address(contractAddress)[0],
+    method: "list_period_payer_totals",
+    args
+  }] as const),
+  listPeriods: (contractAddress: string, args?: Record<string, unknown>) => ([{ ...cwReceiptQueryKeys.address(contractAddress)[0],
+    method: "list_periods",
+    args
+  }] as const),
+  listSignerKeys: (contractAddress: string, args?: Record<string, unknown>) => ([{ ...cwReceiptQueryKeys.address(contractAddress)[0],
+    method: "list_signer_keys",
+    args
+  }] as const),
+  product: (contractAddress: string, args?: Record<string, unknown>) => ([{ ...cwReceiptQueryKeys.address(contractAddress)[0],
+    method: "product",
+    args
+  }] as const),
+  campaign: (contractAddress: string, args?: Record<string, unknown>) => ([{ ...cwReceiptQueryKeys.address(contractAddress)[0],
+    method: "campaign",
+    args
+  }] as const),
+  campaignContributions: (contractAddress: string, args?: Record<string, unknown>) => ([{ ...cwReceiptQueryKeys.address(contractAddress)[0],
+    method: "campaign_contributions",
+    args
+  }] as const),
+  productStock: (contractAddress: string, args?: Record<string, unknown>) => ([{ ...cwReceiptQueryKeys.address(contractAddress)[0],
+    method: "product_stock",
+    args
+  }] as const),
+  listProducts: (contractAddress: string, args?: Record<string, unknown>) => ([{ ...cwReceiptQueryKeys.address(contractAddress)[0],
+    method: "list_products",
+    args
+  }] as const),
+  listReceiptIds: (contractAddress: string, args?: Record<string, unknown>) => ([{ ...cwReceiptQueryKeys.address(contractAddress)[0],
+    method: "list_receipt_ids",
+    args
+  }] as const),
+  listPayments: (contractAddress: string, args?: Record<string, unknown>) => ([{ ...cwReceiptQueryKeys.address(contractAddress)[0],
+    method: "list_payments",
+    args
+  }] as const),
   listPaymentsToId: (contractAddress: string, args?: Record<string, unknown>) => ([{ ...cwReceiptQueryKeys.address(contractAddress)[0],
     method: "list_payments_to_id",
     args
@@ -56,6 +144,7 @@ export function useCwReceiptOwnershipQuery<TData = OwnershipForString>({
 }
 export interface CwReceiptListTotalsPaidByPayerQuery<TData> extends CwReceiptReactQuery<ListTotalsPaidByPayerResponse, TData> {
   args: {
+    height?: number;
     limit?: number;
     payer: string;
     startAfter?: CheckedDenom;
@@ -67,6 +156,7 @@ export function useCwReceiptListTotalsPaidByPayerQuery<TData = ListTotalsPaidByP
   options
 }: CwReceiptListTotalsPaidByPayerQuery<TData>) {
   return useQuery<ListTotalsPaidByPayerResponse, Error, TData>(cwReceiptQueryKeys.listTotalsPaidByPayer(client.contractAddress, args), () => client.listTotalsPaidByPayer({
+    height: args.height,
     limit: args.limit,
     payer: args.payer,
     startAfter: args.startAfter
@@ -92,6 +182,7 @@ export function useCwReceiptListIdsForPayerQuery<TData = ListIdsForPayerResponse
 }
 export interface CwReceiptListTotalsPaidToIdQuery<TData> extends CwReceiptReactQuery<ListTotalsPaidToIdResponse, TData> {
   args: {
+    height?: number;
     id: string;
     limit?: number;
     startAfter?: CheckedDenom;
@@ -103,6 +194,7 @@ export function useCwReceiptListTotalsPaidToIdQuery<TData = ListTotalsPaidToIdRe
   options
 }: CwReceiptListTotalsPaidToIdQuery<TData>) {
   return useQuery<ListTotalsPaidToIdResponse, Error, TData>(cwReceiptQueryKeys.listTotalsPaidToId(client.contractAddress, args), () => client.listTotalsPaidToId({
+    height: args.height,
     id: args.id,
     limit: args.limit,
     startAfter: args.startAfter
@@ -126,59 +218,969 @@ export function useCwReceiptListPaymentsToIdQuery<TData = ListPaymentsToIdRespon
     startAfter: args.startAfter
   }), options);
 }
-export interface CwReceiptOutputQuery<TData> extends CwReceiptReactQuery<OutputResponse, TData> {}
-export function useCwReceiptOutputQuery<TData = OutputResponse>({
+export interface CwReceiptListPaymentsQuery<TData> extends CwReceiptReactQuery<ListPaymentsResponse, TData> {
+  args: {
+    limit?: number;
+    startAfter?: [string, number];
+  };
+}
+export function useCwReceiptListPaymentsQuery<TData = ListPaymentsResponse>({
   client,
+  args,
   options
-}: CwReceiptOutputQuery<TData>) {
-  return useQuery<OutputResponse, Error, TData>(cwReceiptQueryKeys.output(client.contractAddress), () => client.output(), options);
+}: CwReceiptListPaymentsQuery<TData>) {
+  return useQuery<ListPaymentsResponse, Error, TData>(cwReceiptQueryKeys.listPayments(client.contractAddress, args), () => client.listPayments({
+    limit: args.limit,
+    startAfter: args.startAfter
+  }), options);
 }
-export interface CwReceiptUpdateOwnershipMutation {
-  client: CwReceiptClient;
-  msg: Action;
-  args?: {
-    fee?: number | StdFee | "auto";
-    memo?: string;
-    funds?: Coin[];
+export interface CwReceiptListReceiptIdsQuery<TData> extends CwReceiptReactQuery<ListReceiptIdsResponse, TData> {
+  args: {
+    limit?: number;
+    prefix?: string;
+    startAfter?: string;
   };
 }
-export function useCwReceiptUpdateOwnershipMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptUpdateOwnershipMutation>, "mutationFn">) {
-  return useMutation<ExecuteResult, Error, CwReceiptUpdateOwnershipMutation>(({
-    client,
-    msg,
-    args: {
-      fee,
-      memo,
-      funds
-    } = {}
-  }) => client.updateOwnership(msg, fee, memo, funds), options);
+export function useCwReceiptListReceiptIdsQuery<TData = ListReceiptIdsResponse>({
+  client,
+  args,
+  options
+}: CwReceiptListReceiptIdsQuery<TData>) {
+  return useQuery<ListReceiptIdsResponse, Error, TData>(cwReceiptQueryKeys.listReceiptIds(client.contractAddress, args), () => client.listReceiptIds({
+    limit: args.limit,
+    prefix: args.prefix,
+    startAfter: args.startAfter
+  }), options);
 }
-export interface CwReceiptUpdateOutputMutation {
-  client: CwReceiptClient;
-  msg: {
-    output: string;
+export interface CwReceiptListProductsQuery<TData> extends CwReceiptReactQuery<ListProductsResponse, TData> {
+  args: {
+    limit?: number;
+    startAfter?: string;
   };
-  args?: {
-    fee?: number | StdFee | "auto";
-    memo?: string;
-    funds?: Coin[];
+}
+export function useCwReceiptListProductsQuery<TData = ListProductsResponse>({
+  client,
+  args,
+  options
+}: CwReceiptListProductsQuery<TData>) {
+  return useQuery<ListProductsResponse, Error, TData>(cwReceiptQueryKeys.listProducts(client.contractAddress, args), () => client.listProducts({
+    limit: args.limit,
+    startAfter: args.startAfter
+  }), options);
+}
+export interface CwReceiptProductStockQuery<TData> extends CwReceiptReactQuery<ProductStockResponse, TData> {
+  args: {
+    sku: string;
   };
 }
-export function useCwReceiptUpdateOutputMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptUpdateOutputMutation>, "mutationFn">) {
-  return useMutation<ExecuteResult, Error, CwReceiptUpdateOutputMutation>(({
-    client,
-    msg,
-    args: {
-      fee,
-      memo,
-      funds
-    } = {}
-  }) => client.updateOutput(msg, fee, memo, funds), options);
+export function useCwReceiptProductStockQuery<TData = ProductStockResponse>({
+  client,
+  args,
+  options
+}: CwReceiptProductStockQuery<TData>) {
+  return useQuery<ProductStockResponse, Error, TData>(cwReceiptQueryKeys.productStock(client.contractAddress, args), () => client.productStock({
+    sku: args.sku
+  }), options);
+}
+export interface CwReceiptCampaignContributionsQuery<TData> extends CwReceiptReactQuery<CampaignContributionsResponse, TData> {
+  args: {
+    id: string;
+    payer: string;
+  };
+}
+export function useCwReceiptCampaignContributionsQuery<TData = CampaignContributionsResponse>({
+  client,
+  args,
+  options
+}: CwReceiptCampaignContributionsQuery<TData>) {
+  return useQuery<CampaignContributionsResponse, Error, TData>(cwReceiptQueryKeys.campaignContributions(client.contractAddress, args), () => client.campaignContributions({
+    id: args.id,
+    payer: args.payer
+  }), options);
+}
+export interface CwReceiptCampaignQuery<TData> extends CwReceiptReactQuery<CampaignResponse, TData> {
+  args: {
+    id: string;
+  };
+}
+export function useCwReceiptCampaignQuery<TData = CampaignResponse>({
+  client,
+  args,
+  options
+}: CwReceiptCampaignQuery<TData>) {
+  return useQuery<CampaignResponse, Error, TData>(cwReceiptQueryKeys.campaign(client.contractAddress, args), () => client.campaign({
+    id: args.id
+  }), options);
+}
+export interface CwReceiptProductQuery<TData> extends CwReceiptReactQuery<ProductResponse, TData> {
+  args: {
+    sku: string;
+  };
+}
+export function useCwReceiptProductQuery<TData = ProductResponse>({
+  client,
+  args,
+  options
+}: CwReceiptProductQuery<TData>) {
+  return useQuery<ProductResponse, Error, TData>(cwReceiptQueryKeys.product(client.contractAddress, args), () => client.product({
+    sku: args.sku
+  }), options);
+}
+export interface CwReceiptListSignerKeysQuery<TData> extends CwReceiptReactQuery<ListSignerKeysResponse, TData> {
+  args: {
+    limit?: number;
+    startAfter?: Binary;
+  };
+}
+export function useCwReceiptListSignerKeysQuery<TData = ListSignerKeysResponse>({
+  client,
+  args,
+  options
+}: CwReceiptListSignerKeysQuery<TData>) {
+  return useQuery<ListSignerKeysResponse, Error, TData>(cwReceiptQueryKeys.listSignerKeys(client.contractAddress, args), () => client.listSignerKeys({
+    limit: args.limit,
+    startAfter: args.startAfter
+  }), options);
+}
+export interface CwReceiptListPeriodsQuery<TData> extends CwReceiptReactQuery<ListPeriodsResponse, TData> {
+  args: {
+    limit?: number;
+    startAfter?: number;
+  };
+}
+export function useCwReceiptListPeriodsQuery<TData = ListPeriodsResponse>({
+  client,
+  args,
+  options
+}: CwReceiptListPeriodsQuery<TData>) {
+  return useQuery<ListPeriodsResponse, Error, TData>(cwReceiptQueryKeys.listPeriods(client.contractAddress, args), () => client.listPeriods({
+    limit: args.limit,
+    startAfter: args.startAfter
+  }), options);
+}
+export interface CwReceiptListPeriodPayerTotalsQuery<TData> extends CwReceiptReactQuery<ListPeriodPayerTotalsResponse, TData> {
+  args: {
+    limit?: number;
+    period: number;
+    startAfter?: string;
+  };
+}
+export function useCwReceiptListPeriodPayerTotalsQuery<TData = ListPeriodPayerTotalsResponse>({
+  client,
+  args,
+  options
+}: CwReceiptListPeriodPayerTotalsQuery<TData>) {
+  return useQuery<ListPeriodPayerTotalsResponse, Error, TData>(cwReceiptQueryKeys.listPeriodPayerTotals(client.contractAddress, args), () => client.listPeriodPayerTotals({
+    limit: args.limit,
+    period: args.period,
+    startAfter: args.startAfter
+  }), options);
+}
+export interface CwReceiptGetPeriodStatementQuery<TData> extends CwReceiptReactQuery<PeriodStatementResponse, TData> {
+  args: {
+    period: number;
+  };
+}
+export function useCwReceiptGetPeriodStatementQuery<TData = PeriodStatementResponse>({
+  client,
+  args,
+  options
+}: CwReceiptGetPeriodStatementQuery<TData>) {
+  return useQuery<PeriodStatementResponse, Error, TData>(cwReceiptQueryKeys.getPeriodStatement(client.contractAddress, args), () => client.getPeriodStatement({
+    period: args.period
+  }), options);
+}
+export interface CwReceiptSweepableQuery<TData> extends CwReceiptReactQuery<SweepableResponse, TData> {
+  args: {
+    denom: UncheckedDenom;
+  };
+}
+export function useCwReceiptSweepableQuery<TData = SweepableResponse>({
+  client,
+  args,
+  options
+}: CwReceiptSweepableQuery<TData>) {
+  return useQuery<SweepableResponse, Error, TData>(cwReceiptQueryKeys.sweepable(client.contractAddress, args), () => client.sweepable({
+    denom: args.denom
+  }), options);
+}
+export interface CwReceiptListOwedToOutputQuery<TData> extends CwReceiptReactQuery<ListOwedToOutputResponse, TData> {
+  args: {
+    limit?: number;
+    startAfter?: CheckedDenom;
+  };
+}
+export function useCwReceiptListOwedToOutputQuery<TData = ListOwedToOutputResponse>({
+  client,
+  args,
+  options
+}: CwReceiptListOwedToOutputQuery<TData>) {
+  return useQuery<ListOwedToOutputResponse, Error, TData>(cwReceiptQueryKeys.listOwedToOutput(client.contractAddress, args), () => client.listOwedToOutput({
+    limit: args.limit,
+    startAfter: args.startAfter
+  }), options);
+}
+export interface CwReceiptListUnforwardedQuery<TData> extends CwReceiptReactQuery<ListUnforwardedResponse, TData> {
+  args: {
+    limit?: number;
+    startAfter?: CheckedDenom;
+  };
+}
+export function useCwReceiptListUnforwardedQuery<TData = ListUnforwardedResponse>({
+  client,
+  args,
+  options
+}: CwReceiptListUnforwardedQuery<TData>) {
+  return useQuery<ListUnforwardedResponse, Error, TData>(cwReceiptQueryKeys.listUnforwarded(client.contractAddress, args), () => client.listUnforwarded({
+    limit: args.limit,
+    startAfter: args.startAfter
+  }), options);
+}
+export interface CwReceiptForwardingQuery<TData> extends CwReceiptReactQuery<ForwardingResponse, TData> {}
+export function useCwReceiptForwardingQuery<TData = ForwardingResponse>({
+  client,
+  options
+}: CwReceiptForwardingQuery<TData>) {
+  return useQuery<ForwardingResponse, Error, TData>(cwReceiptQueryKeys.forwarding(client.contractAddress), () => client.forwarding(), options);
+}
+export interface CwReceiptCanonicalIdQuery<TData> extends CwReceiptReactQuery<CanonicalIdResponse, TData> {
+  args: {
+    id: string;
+    payer: string;
+  };
+}
+export function useCwReceiptCanonicalIdQuery<TData = CanonicalIdResponse>({
+  client,
+  args,
+  options
+}: CwReceiptCanonicalIdQuery<TData>) {
+  return useQuery<CanonicalIdResponse, Error, TData>(cwReceiptQueryKeys.canonicalId(client.contractAddress, args), () => client.canonicalId({
+    id: args.id,
+    payer: args.payer
+  }), options);
+}
+export interface CwReceiptListAdminActionsQuery<TData> extends CwReceiptReactQuery<ListAdminActionsResponse, TData> {
+  args: {
+    limit?: number;
+    startAfter?: number;
+  };
+}
+export function useCwReceiptListAdminActionsQuery<TData = ListAdminActionsResponse>({
+  client,
+  args,
+  options
+}: CwReceiptListAdminActionsQuery<TData>) {
+  return useQuery<ListAdminActionsResponse, Error, TData>(cwReceiptQueryKeys.listAdminActions(client.contractAddress, args), () => client.listAdminActions({
+    limit: args.limit,
+    startAfter: args.startAfter
+  }), options);
+}
+export interface CwReceiptHasPaidQuery<TData> extends CwReceiptReactQuery<HasPaidResponse, TData> {
+  args: {
+    id: string;
+    payer: string;
+    requirements: Total[];
+  };
+}
+export function useCwReceiptHasPaidQuery<TData = HasPaidResponse>({
+  client,
+  args,
+  options
+}: CwReceiptHasPaidQuery<TData>) {
+  return useQuery<HasPaidResponse, Error, TData>(cwReceiptQueryKeys.hasPaid(client.contractAddress, args), () => client.hasPaid({
+    id: args.id,
+    payer: args.payer,
+    requirements: args.requirements
+  }), options);
+}
+export interface CwReceiptListReceiptTransfersQuery<TData> extends CwReceiptReactQuery<ListReceiptTransfersResponse, TData> {
+  args: {
+    id: string;
+    limit?: number;
+    startAfter?: number;
+  };
+}
+export function useCwReceiptListReceiptTransfersQuery<TData = ListReceiptTransfersResponse>({
+  client,
+  args,
+  options
+}: CwReceiptListReceiptTransfersQuery<TData>) {
+  return useQuery<ListReceiptTransfersResponse, Error, TData>(cwReceiptQueryKeys.listReceiptTransfers(client.contractAddress, args), () => client.listReceiptTransfers({
+    id: args.id,
+    limit: args.limit,
+    startAfter: args.startAfter
+  }), options);
+}
+export interface CwReceiptReceiptPayerQuery<TData> extends CwReceiptReactQuery<ReceiptPayerResponse, TData> {
+  args: {
+    id: string;
+  };
+}
+export function useCwReceiptReceiptPayerQuery<TData = ReceiptPayerResponse>({
+  client,
+  args,
+  options
+}: CwReceiptReceiptPayerQuery<TData>) {
+  return useQuery<ReceiptPayerResponse, Error, TData>(cwReceiptQueryKeys.receiptPayer(client.contractAddress, args), () => client.receiptPayer({
+    id: args.id
+  }), options);
+}
+export interface CwReceiptReceiptQuery<TData> extends CwReceiptReactQuery<ReceiptResponse, TData> {
+  args: {
+    id: string;
+  };
+}
+export function useCwReceiptReceiptQuery<TData = ReceiptResponse>({
+  client,
+  args,
+  options
+}: CwReceiptReceiptQuery<TData>) {
+  return useQuery<ReceiptResponse, Error, TData>(cwReceiptQueryKeys.receipt(client.contractAddress, args), () => client.receipt({
+    id: args.id
+  }), options);
+}
+export interface CwReceiptIdRulesQuery<TData> extends CwReceiptReactQuery<IdRulesResponse, TData> {}
+export function useCwReceiptIdRulesQuery<TData = IdRulesResponse>({
+  client,
+  options
+}: CwReceiptIdRulesQuery<TData>) {
+  return useQuery<IdRulesResponse, Error, TData>(cwReceiptQueryKeys.idRules(client.contractAddress), () => client.idRules(), options);
+}
+export interface CwReceiptOutputQuery<TData> extends CwReceiptReactQuery<OutputResponse, TData> {}
+export function useCwReceiptOutputQuery<TData = OutputResponse>({
+  client,
+  options
+}: CwReceiptOutputQuery<TData>) {
+  return useQuery<OutputResponse, Error, TData>(cwReceiptQueryKeys.output(client.contractAddress), () => client.output(), options);
+}
+export interface CwReceiptUpdateOwnershipMutation {
+  client: CwReceiptClient;
+  msg: Action;
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptUpdateOwnershipMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptUpdateOwnershipMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptUpdateOwnershipMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.updateOwnership(msg, fee, memo, funds), options);
+}
+export interface CwReceiptReleaseEscrowMutation {
+  client: CwReceiptClient;
+  msg: {
+    id: string;
+  };
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptReleaseEscrowMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptReleaseEscrowMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptReleaseEscrowMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.releaseEscrow(msg, fee, memo, funds), options);
+}
+export interface CwReceiptResolveDisputeMutation {
+  client: CwReceiptClient;
+  msg: {
+    id: string;
+    releaseBps: number;
+  };
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptResolveDisputeMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptResolveDisputeMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptResolveDisputeMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.resolveDispute(msg, fee, memo, funds), options);
+}
+export interface CwReceiptOpenDisputeMutation {
+  client: CwReceiptClient;
+  msg: {
+    id: string;
+    reason: string;
+  };
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptOpenDisputeMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptOpenDisputeMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptOpenDisputeMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.openDispute(msg, fee, memo, funds), options);
+}
+export interface CwReceiptAssignArbiterMutation {
+  client: CwReceiptClient;
+  msg: {
+    arbiter: string;
+    disputeWindow: Duration;
+    id: string;
+  };
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptAssignArbiterMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptAssignArbiterMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptAssignArbiterMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.assignArbiter(msg, fee, memo, funds), options);
+}
+export interface CwReceiptClaimRefundMutation {
+  client: CwReceiptClient;
+  msg: {
+    id: string;
+  };
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptClaimRefundMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptClaimRefundMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptClaimRefundMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.claimRefund(msg, fee, memo, funds), options);
+}
+export interface CwReceiptFinalizeMutation {
+  client: CwReceiptClient;
+  msg: {
+    id: string;
+  };
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptFinalizeMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptFinalizeMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptFinalizeMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.finalize(msg, fee, memo, funds), options);
+}
+export interface CwReceiptCreateCampaignMutation {
+  client: CwReceiptClient;
+  msg: {
+    deadline: Expiration;
+    goal: UncheckedPrice[];
+    id: string;
+  };
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptCreateCampaignMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptCreateCampaignMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptCreateCampaignMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.createCampaign(msg, fee, memo, funds), options);
+}
+export interface CwReceiptPayForItemsMutation {
+  client: CwReceiptClient;
+  msg: {
+    id: string;
+    items: [string, number][];
+  };
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptPayForItemsMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptPayForItemsMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptPayForItemsMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.payForItems(msg, fee, memo, funds), options);
+}
+export interface CwReceiptSetProductStockMutation {
+  client: CwReceiptClient;
+  msg: {
+    sku: string;
+    stock?: number;
+  };
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptSetProductStockMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptSetProductStockMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptSetProductStockMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.setProductStock(msg, fee, memo, funds), options);
+}
+export interface CwReceiptSetProductMutation {
+  client: CwReceiptClient;
+  msg: {
+    active: boolean;
+    name: string;
+    prices: UncheckedPrice[];
+    sku: string;
+  };
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptSetProductMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptSetProductMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptSetProductMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.setProduct(msg, fee, memo, funds), options);
+}
+export interface CwReceiptRemoveSignerKeyMutation {
+  client: CwReceiptClient;
+  msg: {
+    publicKey: Binary;
+  };
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptRemoveSignerKeyMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptRemoveSignerKeyMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptRemoveSignerKeyMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.removeSignerKey(msg, fee, memo, funds), options);
+}
+export interface CwReceiptAddSignerKeyMutation {
+  client: CwReceiptClient;
+  msg: {
+    publicKey: Binary;
+  };
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptAddSignerKeyMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptAddSignerKeyMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptAddSignerKeyMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.addSignerKey(msg, fee, memo, funds), options);
+}
+export interface CwReceiptPayWithSignedRequestMutation {
+  client: CwReceiptClient;
+  msg: {
+    publicKey: Binary;
+    request: PaymentRequest;
+    signature: Binary;
+  };
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptPayWithSignedRequestMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptPayWithSignedRequestMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptPayWithSignedRequestMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.payWithSignedRequest(msg, fee, memo, funds), options);
+}
+export interface CwReceiptSweepMutation {
+  client: CwReceiptClient;
+  msg: {
+    amount?: Uint128;
+    denom: UncheckedDenom;
+    recipient: string;
+  };
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptSweepMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptSweepMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptSweepMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.sweep(msg, fee, memo, funds), options);
+}
+export interface CwReceiptClosePeriodMutation {
+  client: CwReceiptClient;
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptClosePeriodMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptClosePeriodMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptClosePeriodMutation>(({
+    client,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.closePeriod(fee, memo, funds), options);
+}
+export interface CwReceiptSettleMutation {
+  client: CwReceiptClient;
+  msg: {
+    denoms: UncheckedDenom[];
+  };
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptSettleMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptSettleMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptSettleMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.settle(msg, fee, memo, funds), options);
+}
+export interface CwReceiptFlushUnforwardedMutation {
+  client: CwReceiptClient;
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptFlushUnforwardedMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptFlushUnforwardedMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptFlushUnforwardedMutation>(({
+    client,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.flushUnforwarded(fee, memo, funds), options);
+}
+export interface CwReceiptUpdateForwardingMutation {
+  client: CwReceiptClient;
+  msg: {
+    forwarding: ForwardingConfig;
+  };
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptUpdateForwardingMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptUpdateForwardingMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptUpdateForwardingMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.updateForwarding(msg, fee, memo, funds), options);
+}
+export interface CwReceiptVoidPaymentMutation {
+  client: CwReceiptClient;
+  msg: {
+    id: string;
+    paymentIndex: number;
+    reason: string;
+    restock?: boolean;
+  };
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptVoidPaymentMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptVoidPaymentMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptVoidPaymentMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.voidPayment(msg, fee, memo, funds), options);
+}
+export interface CwReceiptTransferReceiptMutation {
+  client: CwReceiptClient;
+  msg: {
+    id: string;
+    newPayer: string;
+  };
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptTransferReceiptMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptTransferReceiptMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptTransferReceiptMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.transferReceipt(msg, fee, memo, funds), options);
+}
+export interface CwReceiptReopenReceiptMutation {
+  client: CwReceiptClient;
+  msg: {
+    id: string;
+  };
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptReopenReceiptMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptReopenReceiptMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptReopenReceiptMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.reopenReceipt(msg, fee, memo, funds), options);
+}
+export interface CwReceiptCloseReceiptMutation {
+  client: CwReceiptClient;
+  msg: {
+    id: string;
+    reason: string;
+  };
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptCloseReceiptMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptCloseReceiptMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptCloseReceiptMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.closeReceipt(msg, fee, memo, funds), options);
+}
+export interface CwReceiptAssignPayerMutation {
+  client: CwReceiptClient;
+  msg: {
+    id: string;
+    payer?: string;
+  };
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptAssignPayerMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptAssignPayerMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptAssignPayerMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.assignPayer(msg, fee, memo, funds), options);
+}
+export interface CwReceiptUpdateIdRulesMutation {
+  client: CwReceiptClient;
+  msg: {
+    idRules?: IdRules;
+  };
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptUpdateIdRulesMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptUpdateIdRulesMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptUpdateIdRulesMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.updateIdRules(msg, fee, memo, funds), options);
+}
+export interface CwReceiptUpdateOutputMutation {
+  client: CwReceiptClient;
+  msg: {
+    output: string;
+  };
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptUpdateOutputMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptUpdateOutputMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptUpdateOutputMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.updateOutput(msg, fee, memo, funds), options);
+}
+export interface CwReceiptPayNewMutation {
+  client: CwReceiptClient;
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptPayNewMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptPayNewMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptPayNewMutation>(({
+    client,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.payNew(fee, memo, funds), options);
+}
+export interface CwReceiptCreateReceiptMutation {
+  client: CwReceiptClient;
+  msg: {
+    metadata?: string;
+  };
+  args?: {
+    fee?: number | StdFee | "auto";
+    memo?: string;
+    funds?: Coin[];
+  };
+}
+export function useCwReceiptCreateReceiptMutation(options?: Omit<UseMutationOptions<ExecuteResult, Error, CwReceiptCreateReceiptMutation>, "mutationFn">) {
+  return useMutation<ExecuteResult, Error, CwReceiptCreateReceiptMutation>(({
+    client,
+    msg,
+    args: {
+      fee,
+      memo,
+      funds
+    } = {}
+  }) => client.createReceipt(msg, fee, memo, funds), options);
 }
 export interface CwReceiptPayMutation {
   client: CwReceiptClient;
   msg: {
     id: string;
+    idempotencyKey?: string;
   };
   args?: {
     fee?: number | StdFee | "auto";
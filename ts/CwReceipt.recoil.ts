@@ -6,7 +6,7 @@
 
 import { selectorFamily } from "recoil";
 import { cosmWasmClient } from "./chain";
-import { InstantiateMsg, ExecuteMsg, Uint128, Binary, Action, Expiration, Timestamp, Uint64, Cw20ReceiveMsg, QueryMsg, CheckedDenom, Addr, ListIdsForPayerResponse, ListPaymentsToIdResponse, PaymentWithId, Payment, BlockInfo, ListTotalsPaidByPayerResponse, Total, ListTotalsPaidToIdResponse, OutputResponse, OwnershipForString } from "./CwReceipt.types";
+import { Uint128, CheckedDenom, Addr, Decimal, IdCase, IdCharset, InstantiateMsg, ForwardingConfig, BatchConfig, Total, SettlementConfig, ReferencePrice, IdRules, ExecuteMsg, Binary, UncheckedDenom, Expiration, Timestamp, Uint64, Duration, Action, Cw20ReceiveMsg, PaymentRequest, UncheckedPrice, QueryMsg, MigrateMsg, CampaignResponse, Campaign, CampaignContributionsResponse, CanonicalIdResponse, ForwardingResponse, PeriodStatementResponse, Period, HasPaidResponse, IdRulesResponse, AdminAction, ListAdminActionsResponse, AdminLogItem, AdminLogEntry, OwnershipForAddr, Closure, Product, Escrow, Dispute, ListIdsForPayerResponse, ListOwedToOutputResponse, ListPaymentsResponse, ReceiptPayment, Payment, LineItem, Void, ListPaymentsToIdResponse, ReceiptPaymentWithoutId, ListPeriodPayerTotalsResponse, PayerTotals, ListPeriodsResponse, PeriodItem, ListProductsResponse, ProductItem, ListReceiptIdsResponse, ReceiptIdItem, ListReceiptTransfersResponse, ReceiptTransfer, Transfer, ListSignerKeysResponse, ListTotalsPaidByPayerResponse, ListTotalsPaidToIdResponse, ListUnforwardedResponse, OutputResponse, OwnershipForString, ProductResponse, ProductStockResponse, ReceiptResponse, Creation, ReceiptEscrow, ReceiptPayerResponse, SweepableResponse } from "./CwReceipt.types";
 import { CwReceiptQueryClient } from "./CwReceipt.client";
 type QueryClientParams = {
   contractAddress: string;
@@ -36,6 +36,314 @@ export const outputSelector = selectorFamily<OutputResponse, QueryClientParams &
     return await client.output(...params);
   }
 });
+export const idRulesSelector = selectorFamily<IdRulesResponse, QueryClientParams & {
+  params: Parameters<CwReceiptQueryClient["idRules"]>;
+}>({
+  key: "cwReceiptIdRules",
+  get: ({
+    params,
+    ...queryClientParams
+  }) => async ({
+    get
+  }) => {
+    const client = get(queryClient(queryClientParams));
+    return await client.idRules(...params);
+  }
+});
+export const receiptSelector = selectorFamily<ReceiptResponse, QueryClientParams & {
+  params: Parameters<CwReceiptQueryClient["receipt"]>;
+}>({
+  key: "cwReceiptReceipt",
+  get: ({
+    params,
+    ...queryClientParams
+  }) => async ({
+    get
+  }) => {
+    const client = get(queryClient(queryClientParams));
+    return await client.receipt(...params);
+  }
+});
+export const receiptPayerSelector = selectorFamily<ReceiptPayerResponse, QueryClientParams & {
+  params: Parameters<CwReceiptQueryClient["receiptPayer"]>;
+}>({
+  key: "cwReceiptReceiptPayer",
+  get: ({
+    params,
+    ...queryClientParams
+  }) => async ({
+    get
+  }) => {
+    const client = get(queryClient(queryClientParams));
+    return await client.receiptPayer(...params);
+  }
+});
+export const listReceiptTransfersSelector = selectorFamily<ListReceiptTransfersResponse, QueryClientParams & {
+  params: Parameters<CwReceiptQueryClient["listReceiptTransfers"]>;
+}>({
+  key: "cwReceiptListReceiptTransfers",
+  get: ({
+    params,
+    ...queryClientParams
+  }) => async ({
+    get
+  }) => {
+    const client = get(queryClient(queryClientParams));
+    return await client.listReceiptTransfers(...params);
+  }
+});
+export const hasPaidSelector = selectorFamily<HasPaidResponse, QueryClientParams & {
+  params: Parameters<CwReceiptQueryClient["hasPaid"]>;
+}>({
+  key: "cwReceiptHasPaid",
+  get: ({
+    params,
+    ...queryClientParams
+  }) => async ({
+    get
+  }) => {
+    const client = get(queryClient(queryClientParams));
+    return await client.hasPaid(...params);
+  }
+});
+export const listAdminActionsSelector = selectorFamily<ListAdminActionsResponse, QueryClientParams & {
+  params: Parameters<CwReceiptQueryClient["listAdminActions"]>;
+}>({
+  key: "cwReceiptListAdminActions",
+  get: ({
+    params,
+    ...queryClientParams
+  }) => async ({
+    get
+  }) => {
+    const client = get(queryClient(queryClientParams));
+    return await client.listAdminActions(...params);
+  }
+});
+export const canonicalIdSelector = selectorFamily<CanonicalIdResponse, QueryClientParams & {
+  params: Parameters<CwReceiptQueryClient["canonicalId"]>;
+}>({
+  key: "cwReceiptCanonicalId",
+  get: ({
+    params,
+    ...queryClientParams
+  }) => async ({
+    get
+  }) => {
+    const client = get(queryClient(queryClientParams));
+    return await client.canonicalId(...params);
+  }
+});
+export const forwardingSelector = selectorFamily<ForwardingResponse, QueryClientParams & {
+  params: Parameters<CwReceiptQueryClient["forwarding"]>;
+}>({
+  key: "cwReceiptForwarding",
+  get: ({
+    params,
+    ...queryClientParams
+  }) => async ({
+    get
+  }) => {
+    const client = get(queryClient(queryClientParams));
+    return await client.forwarding(...params);
+  }
+});
+export const listUnforwardedSelector = selectorFamily<ListUnforwardedResponse, QueryClientParams & {
+  params: Parameters<CwReceiptQueryClient["listUnforwarded"]>;
+}>({
+  key: "cwReceiptListUnforwarded",
+  get: ({
+    params,
+    ...queryClientParams
+  }) => async ({
+    get
+  }) => {
+    const client = get(queryClient(queryClientParams));
+    return await client.listUnforwarded(...params);
+  }
+});
+export const listOwedToOutputSelector = selectorFamily<ListOwedToOutputResponse, QueryClientParams & {
+  params: Parameters<CwReceiptQueryClient["listOwedToOutput"]>;
+}>({
+  key: "cwReceiptListOwedToOutput",
+  get: ({
+    params,
+    ...queryClientParams
+  }) => async ({
+    get
+  }) => {
+    const client = get(queryClient(queryClientParams));
+    return await client.listOwedToOutput(...params);
+  }
+});
+export const sweepableSelector = selectorFamily<SweepableResponse, QueryClientParams & {
+  params: Parameters<CwReceiptQueryClient["sweepable"]>;
+}>({
+  key: "cwReceiptSweepable",
+  get: ({
+    params,
+    ...queryClientParams
+  }) => async ({
+    get
+  }) => {
+    const client = get(queryClient(queryClientParams));
+    return await client.sweepable(...params);
+  }
+});
+export const getPeriodStatementSelector = selectorFamily<PeriodStatementResponse, QueryClientParams & {
+  params: Parameters<CwReceiptQueryClient["getPeriodStatement"]>;
+}>({
+  key: "cwReceiptGetPeriodStatement",
+  get: ({
+    params,
+    ...queryClientParams
+  }) => async ({
+    get
+  }) => {
+    const client = get(queryClient(queryClientParams));
+    return await client.getPeriodStatement(...params);
+  }
+});
+export const listPeriodPayerTotalsSelector = selectorFamily<ListPeriodPayerTotalsResponse, QueryClientParams & {
+  params: Parameters<CwReceiptQueryClient["listPeriodPayerTotals"]>;
+}>({
+  key: "cwReceiptListPeriodPayerTotals",
+  get: ({
+    params,
+    ...queryClientParams
+  }) => async ({
+    get
+  }) => {
+    const client = get(queryClient(queryClientParams));
+    return await client.listPeriodPayerTotals(...params);
+  }
+});
+export const listPeriodsSelector = selectorFamily<ListPeriodsResponse, QueryClientParams & {
+  params: Parameters<CwReceiptQueryClient["listPeriods"]>;
+}>({
+  key: "cwReceiptListPeriods",
+  get: ({
+    params,
+    ...queryClientParams
+  }) => async ({
+    get
+  }) => {
+    const client = get(queryClient(queryClientParams));
+    return await client.listPeriods(...params);
+  }
+});
+export const listSignerKeysSelector = selectorFamily<ListSignerKeysResponse, QueryClientParams & {
+  params: Parameters<CwReceiptQueryClient["listSignerKeys"]>;
+}>({
+  key: "cwReceiptListSignerKeys",
+  get: ({
+    params,
+    ...queryClientParams
+  }) => async ({
+    get
+  }) => {
+    const client = get(queryClient(queryClientParams));
+    return await client.listSignerKeys(...params);
+  }
+});
+export const productSelector = selectorFamily<ProductResponse, QueryClientParams & {
+  params: Parameters<CwReceiptQueryClient["product"]>;
+}>({
+  key: "cwReceiptProduct",
+  get: ({
+    params,
+    ...queryClientParams
+  }) => async ({
+    get
+  }) => {
+    const client = get(queryClient(queryClientParams));
+    return await client.product(...params);
+  }
+});
+export const campaignSelector = selectorFamily<CampaignResponse, QueryClientParams & {
+  params: Parameters<CwReceiptQueryClient["campaign"]>;
+}>({
+  key: "cwReceiptCampaign",
+  get: ({
+    params,
+    ...queryClientParams
+  }) => async ({
+    get
+  }) => {
+    const client = get(queryClient(queryClientParams));
+    return await client.campaign(...params);
+  }
+});
+export const campaignContributionsSelector = selectorFamily<CampaignContributionsResponse, QueryClientParams & {
+  params: Parameters<CwReceiptQueryClient["campaignContributions"]>;
+}>({
+  key: "cwReceiptCampaignContributions",
+  get: ({
+    params,
+    ...queryClientParams
+  }) => async ({
+    get
+  }) => {
+    const client = get(queryClient(queryClientParams));
+    return await client.campaignContributions(...params);
+  }
+});
+export const productStockSelector = selectorFamily<ProductStockResponse, QueryClientParams & {
+  params: Parameters<CwReceiptQueryClient["productStock"]>;
+}>({
+  key: "cwReceiptProductStock",
+  get: ({
+    params,
+    ...queryClientParams
+  }) => async ({
+    get
+  }) => {
+    const client = get(queryClient(queryClientParams));
+    return await client.productStock(...params);
+  }
+});
+export const listProductsSelector = selectorFamily<ListProductsResponse, QueryClientParams & {
+  params: Parameters<CwReceiptQueryClient["listProducts"]>;
+}>({
+  key: "cwReceiptListProducts",
+  get: ({
+    params,
+    ...queryClientParams
+  }) => async ({
+    get
+  }) => {
+    const client = get(queryClient(queryClientParams));
+    return await client.listProducts(...params);
+  }
+});
+export const listReceiptIdsSelector = selectorFamily<ListReceiptIdsResponse, QueryClientParams & {
+  params: Parameters<CwReceiptQueryClient["listReceiptIds"]>;
+}>({
+  key: "cwReceiptListReceiptIds",
+  get: ({
+    params,
+    ...queryClientParams
+  }) => async ({
+    get
+  }) => {
+    const client = get(queryClient(queryClientParams));
+    return await client.listReceiptIds(...params);
+  }
+});
+export const listPaymentsSelector = selectorFamily<ListPaymentsResponse, QueryClientParams & {
+  params: Parameters<CwReceiptQueryClient["listPayments"]>;
+}>({
+  key: "cwReceiptListPayments",
+  get: ({
+    params,
+    ...queryClientParams
+  }) => async ({
+    get
+  }) => {
+    const client = get(queryClient(queryClientParams));
+    return await client.listPayments(...params);
+  }
+});
 export const listPaymentsToIdSelector = selectorFamily<ListPaymentsToIdResponse, QueryClientParams & {
   params: Parameters<CwReceiptQueryClient["listPaymentsToId"]>;
 }>({
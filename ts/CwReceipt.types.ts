@@ -4,31 +4,193 @@
 * and run the @cosmwasm/ts-codegen generate command to regenerate this file.
 */
 
+export type Uint128 = string;
+export type CheckedDenom = {
+  native: string;
+} | {
+  cw20: Addr;
+};
+export type Addr = string;
+export type Decimal = string;
+export type IdCase = "lower" | "upper";
+export type IdCharset = "alphanumeric" | "alphanumeric_with_separators" | {
+  custom: string;
+};
 export interface InstantiateMsg {
+  forwarding?: ForwardingConfig | null;
+  id_rules?: IdRules | null;
   output: string;
   owner?: string | null;
 }
+export interface ForwardingConfig {
+  batch?: BatchConfig | null;
+  resilient: boolean;
+  settlement?: SettlementConfig | null;
+}
+export interface BatchConfig {
+  thresholds: Total[];
+}
+export interface Total {
+  amount: Uint128;
+  denom: CheckedDenom;
+}
+export interface SettlementConfig {
+  denom: CheckedDenom;
+  max_slippage: Decimal;
+  reference_prices: ReferencePrice[];
+  router: Addr;
+}
+export interface ReferencePrice {
+  denom: CheckedDenom;
+  price: Decimal;
+}
+export interface IdRules {
+  bind_to_payer?: boolean;
+  case?: IdCase | null;
+  charset?: IdCharset | null;
+  max_length?: number | null;
+  min_length?: number | null;
+  prefix?: string | null;
+}
 export type ExecuteMsg = {
   receive: Cw20ReceiveMsg;
 } | {
   pay: {
     id: string;
+    idempotency_key?: string | null;
+  };
+} | {
+  create_receipt: {
+    metadata?: string | null;
   };
+} | {
+  pay_new: {};
 } | {
   update_output: {
     output: string;
   };
+} | {
+  update_id_rules: {
+    id_rules?: IdRules | null;
+  };
+} | {
+  assign_payer: {
+    id: string;
+    payer?: string | null;
+  };
+} | {
+  close_receipt: {
+    id: string;
+    reason: string;
+  };
+} | {
+  reopen_receipt: {
+    id: string;
+  };
+} | {
+  transfer_receipt: {
+    id: string;
+    new_payer: string;
+  };
+} | {
+  void_payment: {
+    id: string;
+    payment_index: number;
+    reason: string;
+    restock?: boolean;
+  };
+} | {
+  update_forwarding: {
+    forwarding: ForwardingConfig;
+  };
+} | {
+  flush_unforwarded: {};
+} | {
+  settle: {
+    denoms: UncheckedDenom[];
+  };
+} | {
+  close_period: {};
+} | {
+  sweep: {
+    amount?: Uint128 | null;
+    denom: UncheckedDenom;
+    recipient: string;
+  };
+} | {
+  pay_with_signed_request: {
+    public_key: Binary;
+    request: PaymentRequest;
+    signature: Binary;
+  };
+} | {
+  add_signer_key: {
+    public_key: Binary;
+  };
+} | {
+  remove_signer_key: {
+    public_key: Binary;
+  };
+} | {
+  set_product: {
+    active: boolean;
+    name: string;
+    prices: UncheckedPrice[];
+    sku: string;
+  };
+} | {
+  set_product_stock: {
+    sku: string;
+    stock?: number | null;
+  };
+} | {
+  pay_for_items: {
+    id: string;
+    items: [string, number][];
+  };
+} | {
+  create_campaign: {
+    deadline: Expiration;
+    goal: UncheckedPrice[];
+    id: string;
+  };
+} | {
+  finalize: {
+    id: string;
+  };
+} | {
+  claim_refund: {
+    id: string;
+  };
+} | {
+  assign_arbiter: {
+    arbiter: string;
+    dispute_window: Duration;
+    id: string;
+  };
+} | {
+  open_dispute: {
+    id: string;
+    reason: string;
+  };
+} | {
+  resolve_dispute: {
+    id: string;
+    release_bps: number;
+  };
+} | {
+  release_escrow: {
+    id: string;
+  };
 } | {
   update_ownership: Action;
 };
-export type Uint128 = string;
 export type Binary = string;
-export type Action = {
-  transfer_ownership: {
-    expiry?: Expiration | null;
-    new_owner: string;
-  };
-} | "accept_ownership" | "renounce_ownership";
+export type UncheckedDenom = {
+  native: string;
+} | {
+  cw20: string;
+};
 export type Expiration = {
   at_height: number;
 } | {
@@ -38,13 +200,136 @@ export type Expiration = {
 };
 export type Timestamp = Uint64;
 export type Uint64 = string;
+export type Duration = {
+  height: number;
+} | {
+  time: number;
+};
+export type Action = {
+  transfer_ownership: {
+    expiry?: Expiration | null;
+    new_owner: string;
+  };
+} | "accept_ownership" | "renounce_ownership";
 export interface Cw20ReceiveMsg {
   amount: Uint128;
   msg: Binary;
   sender: string;
 }
+export interface PaymentRequest {
+  amounts: Total[];
+  expires: Expiration;
+  id: string;
+  nonce: number;
+  payer: string;
+}
+export interface UncheckedPrice {
+  amount: Uint128;
+  denom: UncheckedDenom;
+}
 export type QueryMsg = {
   output: {};
+} | {
+  id_rules: {};
+} | {
+  receipt: {
+    id: string;
+  };
+} | {
+  receipt_payer: {
+    id: string;
+  };
+} | {
+  list_receipt_transfers: {
+    id: string;
+    limit?: number | null;
+    start_after?: number | null;
+  };
+} | {
+  has_paid: {
+    id: string;
+    payer: string;
+    requirements: Total[];
+  };
+} | {
+  list_admin_actions: {
+    limit?: number | null;
+    start_after?: number | null;
+  };
+} | {
+  canonical_id: {
+    id: string;
+    payer: string;
+  };
+} | {
+  forwarding: {};
+} | {
+  list_unforwarded: {
+    limit?: number | null;
+    start_after?: CheckedDenom | null;
+  };
+} | {
+  list_owed_to_output: {
+    limit?: number | null;
+    start_after?: CheckedDenom | null;
+  };
+} | {
+  sweepable: {
+    denom: UncheckedDenom;
+  };
+} | {
+  get_period_statement: {
+    period: number;
+  };
+} | {
+  list_period_payer_totals: {
+    limit?: number | null;
+    period: number;
+    start_after?: string | null;
+  };
+} | {
+  list_periods: {
+    limit?: number | null;
+    start_after?: number | null;
+  };
+} | {
+  list_signer_keys: {
+    limit?: number | null;
+    start_after?: Binary | null;
+  };
+} | {
+  product: {
+    sku: string;
+  };
+} | {
+  campaign: {
+    id: string;
+  };
+} | {
+  campaign_contributions: {
+    id: string;
+    payer: string;
+  };
+} | {
+  product_stock: {
+    sku: string;
+  };
+} | {
+  list_products: {
+    limit?: number | null;
+    start_after?: string | null;
+  };
+} | {
+  list_receipt_ids: {
+    limit?: number | null;
+    prefix?: string | null;
+    start_after?: string | null;
+  };
+} | {
+  list_payments: {
+    limit?: number | null;
+    start_after?: [string, number] | null;
+  };
 } | {
   list_payments_to_id: {
     id: string;
@@ -53,6 +338,7 @@ export type QueryMsg = {
   };
 } | {
   list_totals_paid_to_id: {
+    height?: number | null;
     id: string;
     limit?: number | null;
     start_after?: CheckedDenom | null;
@@ -65,6 +351,7 @@ export type QueryMsg = {
   };
 } | {
   list_totals_paid_by_payer: {
+    height?: number | null;
     limit?: number | null;
     payer: string;
     start_after?: CheckedDenom | null;
@@ -72,43 +359,281 @@ export type QueryMsg = {
 } | {
   ownership: {};
 };
-export type CheckedDenom = {
-  native: string;
+export interface MigrateMsg {
+  limit?: number | null;
+}
+export interface CampaignResponse {
+  campaign: Campaign;
+  goal_met: boolean;
+  raised: Total[];
+}
+export interface Campaign {
+  deadline: Expiration;
+  finalized: boolean;
+  goal: Total[];
+}
+export interface CampaignContributionsResponse {
+  contributions: Total[];
+}
+export interface CanonicalIdResponse {
+  id: string;
+}
+export interface ForwardingResponse {
+  forwarding: ForwardingConfig;
+}
+export interface PeriodStatementResponse {
+  closed?: Period | null;
+  period: number;
+  totals: Total[];
+  voided: Total[];
+}
+export interface Period {
+  closed_by: Addr;
+  height: number;
+  time: Timestamp;
+}
+export interface HasPaidResponse {
+  missing: Total[];
+  paid: boolean;
+}
+export interface IdRulesResponse {
+  id_rules?: IdRules | null;
+}
+export type AdminAction = {
+  update_output: {
+    new: Addr;
+    old: Addr;
+  };
 } | {
-  cw20: Addr;
+  update_ownership: {
+    new: OwnershipForAddr;
+    old: OwnershipForAddr;
+  };
+} | {
+  update_id_rules: {
+    new?: IdRules | null;
+    old?: IdRules | null;
+  };
+} | {
+  assign_payer: {
+    id: string;
+    new?: Addr | null;
+    old?: Addr | null;
+  };
+} | {
+  close_receipt: {
+    id: string;
+    reason: string;
+  };
+} | {
+  reopen_receipt: {
+    id: string;
+    old: Closure;
+  };
+} | {
+  transfer_receipt: {
+    id: string;
+    new: Addr;
+    old: Addr;
+  };
+} | {
+  void_payment: {
+    id: string;
+    payment_index: number;
+    reason: string;
+    restock?: boolean;
+  };
+} | {
+  update_forwarding: {
+    new: ForwardingConfig;
+    old: ForwardingConfig;
+  };
+} | {
+  flush_unforwarded: {
+    amounts: Total[];
+  };
+} | {
+  close_period: {
+    period: number;
+  };
+} | {
+  sweep: {
+    amount: Uint128;
+    denom: CheckedDenom;
+    recipient: Addr;
+  };
+} | {
+  add_signer_key: {
+    public_key: Binary;
+  };
+} | {
+  remove_signer_key: {
+    public_key: Binary;
+  };
+} | {
+  set_product: {
+    new: Product;
+    old?: Product | null;
+    sku: string;
+  };
+} | {
+  set_product_stock: {
+    new?: number | null;
+    old?: number | null;
+    sku: string;
+  };
+} | {
+  create_campaign: {
+    campaign: Campaign;
+    id: string;
+  };
+} | {
+  assign_arbiter: {
+    id: string;
+    new: Escrow;
+    old?: Escrow | null;
+  };
 };
-export type Addr = string;
+export interface ListAdminActionsResponse {
+  actions: AdminLogItem[];
+}
+export interface AdminLogItem {
+  entry: AdminLogEntry;
+  entry_id: number;
+}
+export interface AdminLogEntry {
+  action: AdminAction;
+  actor: Addr;
+  height: number;
+  time: Timestamp;
+}
+export interface OwnershipForAddr {
+  owner?: Addr | null;
+  pending_expiry?: Expiration | null;
+  pending_owner?: Addr | null;
+}
+export interface Closure {
+  closed_by: Addr;
+  height: number;
+  reason: string;
+  time: Timestamp;
+}
+export interface Product {
+  active: boolean;
+  name: string;
+  prices: Total[];
+}
+export interface Escrow {
+  arbiter: Addr;
+  dispute?: Dispute | null;
+  dispute_window: Duration;
+  released: boolean;
+  window_ends?: Expiration | null;
+}
+export interface Dispute {
+  height: number;
+  reason: string;
+  release_bps?: number | null;
+  time: Timestamp;
+}
 export interface ListIdsForPayerResponse {
   ids: string[];
 }
-export interface ListPaymentsToIdResponse {
-  payments: PaymentWithId[];
+export interface ListOwedToOutputResponse {
+  owed: Total[];
+}
+export interface ListPaymentsResponse {
+  payments: ReceiptPayment[];
 }
-export interface PaymentWithId {
-  id: number;
+export interface ReceiptPayment {
   payment: Payment;
+  receipt_id: string;
+  receipt_payment_id: number;
 }
 export interface Payment {
   amount: Uint128;
-  block: BlockInfo;
+  block_height: number;
+  block_time: Timestamp;
   denom: CheckedDenom;
+  items: LineItem[];
+  payer: Addr;
+  period: number;
+  settled?: Total | null;
+  voided?: Void | null;
 }
-export interface BlockInfo {
-  chain_id: string;
+export interface LineItem {
+  quantity: number;
+  sku: string;
+  unit_price: Uint128;
+}
+export interface Void {
   height: number;
+  reason: string;
   time: Timestamp;
-  [k: string]: unknown;
+  voided_by: Addr;
 }
-export interface ListTotalsPaidByPayerResponse {
+export interface ListPaymentsToIdResponse {
+  payments: ReceiptPaymentWithoutId[];
+}
+export interface ReceiptPaymentWithoutId {
+  payment: Payment;
+  receipt_payment_id: number;
+}
+export interface ListPeriodPayerTotalsResponse {
+  payers: PayerTotals[];
+}
+export interface PayerTotals {
+  payer: Addr;
   totals: Total[];
 }
-export interface Total {
-  amount: Uint128;
-  denom: CheckedDenom;
+export interface ListPeriodsResponse {
+  current_period: number;
+  periods: PeriodItem[];
+}
+export interface PeriodItem {
+  closed: Period;
+  period: number;
+}
+export interface ListProductsResponse {
+  products: ProductItem[];
+}
+export interface ProductItem {
+  product: Product;
+  sku: string;
+}
+export interface ListReceiptIdsResponse {
+  receipts: ReceiptIdItem[];
+}
+export interface ReceiptIdItem {
+  id: string;
+  payment_count: number;
+}
+export interface ListReceiptTransfersResponse {
+  transfers: ReceiptTransfer[];
+}
+export interface ReceiptTransfer {
+  transfer: Transfer;
+  transfer_id: number;
+}
+export interface Transfer {
+  from: Addr;
+  height: number;
+  time: Timestamp;
+  to: Addr;
+  transferred_by: Addr;
+}
+export interface ListSignerKeysResponse {
+  public_keys: Binary[];
+}
+export interface ListTotalsPaidByPayerResponse {
+  totals: Total[];
 }
 export interface ListTotalsPaidToIdResponse {
   totals: Total[];
 }
+export interface ListUnforwardedResponse {
+  unforwarded: Total[];
+}
 export interface OutputResponse {
   output: Addr;
 }
@@ -116,4 +641,37 @@ export interface OwnershipForString {
   owner?: string | null;
   pending_expiry?: Expiration | null;
   pending_owner?: string | null;
+}
+export interface ProductResponse {
+  product?: Product | null;
+}
+export interface ProductStockResponse {
+  stock?: number | null;
+}
+export interface ReceiptResponse {
+  closure?: Closure | null;
+  creation?: Creation | null;
+  escrow?: ReceiptEscrow | null;
+  id: string;
+  payer?: Addr | null;
+  payment_count: number;
+}
+export interface Creation {
+  creator: Addr;
+  height: number;
+  metadata?: string | null;
+  time: Timestamp;
+}
+export interface ReceiptEscrow {
+  escrow: Escrow;
+  held: Total[];
+}
+export interface ReceiptPayerResponse {
+  payer?: Addr | null;
+}
+export interface SweepableResponse {
+  balance: Uint128;
+  denom: CheckedDenom;
+  owed: Uint128;
+  sweepable: Uint128;
 }
\ No newline at end of file